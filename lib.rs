@@ -2,16 +2,15 @@
 
 #[ink::contract]
 mod contract {
-    use core::{
-        ops::{Div, Rem},
-    };
-
     use ink::{
+        codegen::Env,
         prelude::{string::String, vec::Vec},
-        storage::{traits::StorageLayout, Mapping, StorageVec},
+        storage::{
+            traits::{Packed, StorageKey, StorageLayout},
+            Mapping, StorageVec,
+        },
     };
     //use scale::{Decode, Encode};
-    use scale_info::prelude::format;
     //use scale_info::prelude::vec::Vec;
 
     pub const COMPRADOR: Rol = Rol::Comprador;
@@ -66,6 +65,54 @@ mod contract {
         UsuarioNoCorresponde,
         NoTieneCalificaciones,
         ErrorSuma,
+        PagoInsuficiente,
+        PagoExcedente,
+        ErrorTransferencia,
+        NoEsOwner,
+        NoEsAdmin,
+        YaEsAdmin,
+        SinTransferenciaPendiente,
+        NoEsOwnerPropuesto,
+        OrdenNoDisputable,
+        OrdenNoEnDisputa,
+        NoEsArbitro,
+        YaEsArbitro,
+        ArbitroEsParte,
+        MaxEvidenciasAlcanzado,
+        VendedorDistintoEnCarrito,
+        PublicacionNoEnCarrito,
+        CarritoLleno,
+        PlazoInvalido,
+        PlazoNoVencido,
+        OrdenSinPlazo,
+        SinCancelacionPendiente,
+        CancelacionPropia,
+        PrecisionInvalida,
+        ProductoAjeno,
+        SkuInvalido,
+        SkuYaExistente,
+        SkuInexistente,
+        SinOfertas,
+        PublicacionAjena,
+        PublicacionCerrada,
+        PublicacionYaPausada,
+        PublicacionNoPausada,
+        PublicacionInactiva,
+        ProductoRetirado,
+        ProductoConPublicacionesActivas,
+        ProductoConOrdenesAbiertas,
+        TamanoPaginaInvalido,
+        CompraPropia,
+        LimiteCalificacionesAlcanzado,
+        LimiteCalificacionesInvalido,
+        VidaMediaInvalida,
+        ResenaInvalida,
+        ResenaInexistente,
+        NoEsDestinatarioResena,
+        ResenaYaRespondida,
+        SinCalificacionesPendientes,
+        CalificacionCerrada,
+        PublicacionYaAbierta, //el vendedor ya tiene una publicación sin cerrar del producto
     }
 
     pub trait GestionProducto {
@@ -80,7 +127,48 @@ mod contract {
 
         fn producto_existe(&self, p: &Producto) -> bool;
 
-        fn _listar_productos(&self) -> Vec<Producto>;
+        fn _listar_productos(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Producto>, ErroresContrato>;
+
+        fn _listar_productos_vendedor(
+            &self,
+            id_vendedor: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Producto>, ErroresContrato>;
+
+        fn _compartir_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            compartido: bool,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _asignar_sku(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            sku: String,
+        ) -> Result<(), ErroresContrato>;
+
+        fn get_producto_by_sku(&self, sku: &str) -> Result<Producto, ErroresContrato>;
+
+        fn _editar_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            nombre: String,
+            descripcion: String,
+            categoria: String,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _ajustar_stock_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            stock: u32,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _retirar_producto(&mut self, id_producto: u32, id_vendedor: AccountId) -> Result<(), ErroresContrato>;
     }
 
     pub trait GestionUsuario {
@@ -90,19 +178,26 @@ mod contract {
             nombre: String,
             mail: String,
             rol: Rol,
-        ) -> Result<String, ErroresContrato>;
+        ) -> Result<(), ErroresContrato>;
 
         fn get_user(&mut self, id: &AccountId) -> Result<Usuario, ErroresContrato>;
 
         fn _get_cantidad_usuarios(&self) -> u32;
 
-        fn _listar_usuarios(&self, pagina: u32, tamano_pagina: u32) -> Vec<Usuario>;
+        fn _listar_usuarios(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Usuario>, ErroresContrato>;
 
         fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato>;
 
         fn get_usuario_by_username(&self, name: &str) -> Result<Usuario, ErroresContrato>;
 
-        fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, ErroresContrato>;
+        fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<(), ErroresContrato>;
+
+        fn _actualizar_perfil(
+            &mut self,
+            id: AccountId,
+            nombre: String,
+            mail: String,
+        ) -> Result<(), ErroresContrato>;
     }
 
     pub trait GestionOrden {
@@ -111,9 +206,39 @@ mod contract {
             id_pub: u32,
             id_comprador: AccountId,
             cantidad: u32,
+            pago: Balance,
         ) -> Result<u32, ErroresContrato>;
 
-        fn _listar_ordenes(&self) -> Vec<Orden>;
+        fn _listar_ordenes(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Orden>, ErroresContrato>;
+
+        fn _listar_compras(
+            &self,
+            id_comprador: AccountId,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato>;
+
+        fn _listar_ventas(
+            &self,
+            id_vendedor: AccountId,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato>;
+
+        fn _get_cantidad_ordenes(&self, id: AccountId) -> CantidadOrdenes;
+
+        fn _get_historial_orden(&self, id_orden: u32) -> Result<Vec<TransicionOrden>, ErroresContrato>;
+
+        fn _procesar_vencimiento(&mut self, id_orden: u32, actor: AccountId) -> Result<EstadoOrden, ErroresContrato>;
+
+        fn _crear_orden_con_items(
+            &mut self,
+            id_comprador: AccountId,
+            items: &[ItemCarrito],
+            pago: Balance,
+        ) -> Result<u32, ErroresContrato>;
 
         fn _enviar_orden(
             &mut self,
@@ -127,7 +252,14 @@ mod contract {
             id_comprador: AccountId,
         ) -> Result<(), ErroresContrato>;
 
-        fn _cancelar_orden(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<String, ErroresContrato>;
+        fn _cancelar_orden(
+            &mut self,
+            id_orden: u32,
+            id_usuario: AccountId,
+            motivo: MotivoCancelacion,
+        ) -> Result<ResultadoCancelacion, ErroresContrato>;
+
+        fn _rechazar_cancelacion(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<(), ErroresContrato>;
 
         fn _calificar_orden(
             &mut self,
@@ -135,6 +267,8 @@ mod contract {
             id: AccountId,
             puntaje: u8,
         ) -> Result<(), ErroresContrato>;
+
+        fn _revelar_calificaciones(&mut self, id_orden: u32) -> Result<(), ErroresContrato>;
     }
 
     pub trait GestionPublicacion {
@@ -150,19 +284,146 @@ mod contract {
 
         fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato>; // HAY QUE VOLARLO A LA MIERDA EN LA 2DA ENTREGA
 
-        fn _listar_publicaciones(&self) -> Vec<Publicacion>;
+        fn _listar_publicaciones(
+            &self,
+            solo_activas: bool,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato>;
+
+        fn _listar_publicaciones_propias(
+            &self,
+            id_usuario: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato>;
+
+        fn _listar_ofertas(&self, id_producto: u32) -> Vec<Publicacion>;
+
+        fn _mejor_oferta(
+            &self,
+            id_producto: u32,
+            id_comprador: AccountId,
+            cantidad: u32,
+        ) -> Result<Publicacion, ErroresContrato>;
+
+        fn _actualizar_precio(
+            &mut self,
+            id_pub: u32,
+            id_usuario: AccountId,
+            precio: Balance,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _reponer_stock(
+            &mut self,
+            id_pub: u32,
+            id_usuario: AccountId,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _pausar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _reanudar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato>;
 
-        fn _listar_publicaciones_propias(&self, id_usuario: AccountId) -> Vec<Publicacion>;
+        fn _cerrar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _buscar_publicaciones(
+            &self,
+            filtro: &FiltroPublicaciones,
+            orden: OrdenBusqueda,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato>;
     }
 
     pub trait GestionCategoria {
-        fn _registrar_categoria(&mut self, nombre: String) -> Result<String, ErroresContrato>;
+        fn _registrar_categoria(&mut self, nombre: String) -> Result<u32, ErroresContrato>;
+
+        fn _listar_categorias(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Categoria>, ErroresContrato>;
+
+        fn get_categoria_by_name(&self, nombre: &str) -> Result<u32, ErroresContrato>;
+
+        fn clean_cat_name(&self, nombre: &str) -> Result<String, ErroresContrato>;
+    }
+
+    pub trait GestionCarrito {
+        fn _agregar_al_carrito(
+            &mut self,
+            id_comprador: AccountId,
+            id_pub: u32,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _actualizar_carrito(
+            &mut self,
+            id_comprador: AccountId,
+            id_pub: u32,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _quitar_del_carrito(&mut self, id_comprador: AccountId, id_pub: u32) -> Result<(), ErroresContrato>;
+
+        fn _ver_carrito(&self, id_comprador: AccountId) -> Vec<ItemCarrito>;
+
+        fn _confirmar_carrito(&mut self, id_comprador: AccountId, pago: Balance) -> Result<u32, ErroresContrato>;
+    }
+
+    pub trait GestionDisputa {
+        fn _registrar_arbitro(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _quitar_arbitro(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _abrir_disputa(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _aportar_evidencia(
+            &mut self,
+            id_orden: u32,
+            id_usuario: AccountId,
+            evidencia: Hash,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _resolver_disputa(
+            &mut self,
+            id_orden: u32,
+            id_arbitro: AccountId,
+            fallo: FalloDisputa,
+        ) -> Result<(), ErroresContrato>;
+    }
+
+    pub trait GestionResena {
+        fn _resenar_orden(
+            &mut self,
+            id_orden: u32,
+            autor: AccountId,
+            puntaje: u8,
+            texto: String,
+        ) -> Result<u32, ErroresContrato>;
+
+        fn _responder_resena(
+            &mut self,
+            id_resena: u32,
+            id_usuario: AccountId,
+            texto: String,
+        ) -> Result<(), ErroresContrato>;
+
+        fn _listar_resenas(
+            &self,
+            id_usuario: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Resena>, ErroresContrato>;
+    }
 
-        fn _listar_categorias(&self) -> Vec<Categoria>;
+    pub trait GestionAdmin {
+        fn _es_admin(&self, id: AccountId) -> bool;
 
-        fn get_categoria_by_name(&self, nombre: &String) -> Result<u32, ErroresContrato>;
+        fn _agregar_admin(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato>;
 
-        fn clean_cat_name(&self, nombre: &String) -> Result<String, ErroresContrato>;
+        fn _quitar_admin(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _proponer_owner(&mut self, caller: AccountId, nuevo: AccountId) -> Result<(), ErroresContrato>;
+
+        fn _aceptar_owner(&mut self, caller: AccountId) -> Result<(), ErroresContrato>;
     }
 
     pub trait ControlStock {
@@ -191,15 +452,310 @@ mod contract {
         }
     }
 
+    // Eventos emitidos por el contrato
+
+    /// Se emite al registrar un nuevo usuario
+    #[ink(event)]
+    pub struct UsuarioRegistrado {
+        #[ink(topic)]
+        pub id: AccountId,
+        pub nombre: String,
+    }
+
+    /// Se emite cuando un usuario modifica su nombre o su mail
+    #[ink(event)]
+    pub struct PerfilActualizado {
+        #[ink(topic)]
+        pub id: AccountId,
+        pub nombre: String,
+    }
+
+    /// Se emite cada vez que un usuario obtiene un rol nuevo
+    #[ink(event)]
+    pub struct RolAsignado {
+        #[ink(topic)]
+        pub id: AccountId,
+        pub rol: Rol,
+    }
+
+    /// Se emite al registrar una nueva categoría
+    #[ink(event)]
+    pub struct CategoriaRegistrada {
+        pub id: u32,
+        pub nombre: String,
+    }
+
+    /// Se emite al crear un nuevo producto
+    #[ink(event)]
+    pub struct ProductoCreado {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub categoria: u32,
+    }
+
+    /// Se emite cuando el vendedor edita un producto o ajusta su stock
+    #[ink(event)]
+    pub struct ProductoActualizado {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub categoria: u32,
+        pub stock: u32,
+    }
+
+    /// Se emite cuando el vendedor retira un producto de su catálogo
+    #[ink(event)]
+    pub struct ProductoDadoDeBaja {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+    }
+
+    /// Se emite cuando el dueño de un producto lo comparte o deja de compartirlo con otros vendedores
+    #[ink(event)]
+    pub struct ProductoCompartido {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub compartido: bool,
+    }
+
+    /// Se emite cuando un producto se incorpora al catálogo compartido con un SKU/GTIN
+    #[ink(event)]
+    pub struct ProductoCatalogado {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub sku: String,
+    }
+
+    /// Se emite al crear una nueva publicación
+    #[ink(event)]
+    pub struct PublicacionCreada {
+        pub id: u32,
+        pub id_producto: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub stock: u32,
+        pub precio_unitario: Balance,
+    }
+
+    /// Se emite cuando el vendedor modifica, pausa, reanuda o cierra una publicación
+    #[ink(event)]
+    pub struct PublicacionActualizada {
+        pub id: u32,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub stock: u32,
+        pub precio_unitario: Balance,
+        pub activa: bool,
+        pub pausada: bool,
+        pub cerrada: bool,
+    }
+
+    /// Se emite al crear una orden de compra, que comienza en estado `Pendiente`
+    #[ink(event)]
+    pub struct OrdenCreada {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub id_comprador: AccountId,
+        #[ink(topic)]
+        pub id_vendedor: AccountId,
+        pub lineas: Vec<LineaOrden>,
+        pub precio_total: Balance,
+    }
+
+    /// Se emite en cada cambio de estado de una orden
+    #[ink(event)]
+    pub struct EstadoOrdenActualizado {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub actor: AccountId,
+        pub anterior: EstadoOrden,
+        pub nuevo: EstadoOrden,
+    }
+
+    /// Se emite cuando una de las partes califica a la otra, sin el puntaje,
+    /// que queda oculto hasta que se revelan las calificaciones de la orden
+    #[ink(event)]
+    pub struct CalificacionPendiente {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub calificador: AccountId,
+    }
+
+    /// Se emite por cada calificación de una orden al revelarse
+    #[ink(event)]
+    pub struct CalificacionRegistrada {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub calificador: AccountId,
+        #[ink(topic)]
+        pub calificado: AccountId,
+        pub puntaje: u8,
+    }
+
+    /// Se emite cuando una de las partes deja una reseña escrita junto a su calificación
+    #[ink(event)]
+    pub struct ResenaPublicada {
+        pub id_resena: u32,
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub autor: AccountId,
+        #[ink(topic)]
+        pub destinatario: AccountId,
+    }
+
+    /// Se emite cuando el destinatario de una reseña la responde
+    #[ink(event)]
+    pub struct ResenaRespondida {
+        #[ink(topic)]
+        pub id_resena: u32,
+        #[ink(topic)]
+        pub destinatario: AccountId,
+    }
+
+    /// Se emite cuando el owner otorga el permiso de administrador a una cuenta
+    #[ink(event)]
+    pub struct AdminAgregado {
+        #[ink(topic)]
+        pub id: AccountId,
+    }
+
+    /// Se emite cuando el owner revoca el permiso de administrador de una cuenta
+    #[ink(event)]
+    pub struct AdminQuitado {
+        #[ink(topic)]
+        pub id: AccountId,
+    }
+
+    /// Se emite cuando el owner propone a una cuenta como su sucesor
+    #[ink(event)]
+    pub struct TransferenciaOwnerPropuesta {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub propuesto: AccountId,
+    }
+
+    /// Se emite cuando la cuenta propuesta acepta ser el nuevo owner
+    #[ink(event)]
+    pub struct OwnerTransferido {
+        #[ink(topic)]
+        pub anterior: AccountId,
+        #[ink(topic)]
+        pub nuevo: AccountId,
+    }
+
+    /// Se emite cuando una de las partes propone cancelar una orden
+    #[ink(event)]
+    pub struct CancelacionPropuesta {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub propuesta_por: AccountId,
+        pub motivo: MotivoCancelacion,
+    }
+
+    /// Se emite cuando la contraparte rechaza la cancelación propuesta
+    #[ink(event)]
+    pub struct CancelacionRechazada {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub rechazada_por: AccountId,
+    }
+
+    /// Se emite cuando una de las partes abre una disputa sobre una orden
+    #[ink(event)]
+    pub struct DisputaAbierta {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub abierta_por: AccountId,
+    }
+
+    /// Se emite cuando una de las partes aporta evidencia a una disputa
+    #[ink(event)]
+    pub struct EvidenciaAportada {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub autor: AccountId,
+        pub evidencia: Hash,
+    }
+
+    /// Se emite cuando un árbitro resuelve una disputa
+    #[ink(event)]
+    pub struct DisputaResuelta {
+        #[ink(topic)]
+        pub id_orden: u32,
+        #[ink(topic)]
+        pub arbitro: AccountId,
+        pub fallo: FalloDisputa,
+    }
+
     ///Estructura principal del contrato
     #[ink(storage)]
     pub struct Sistema {
+        owner: AccountId,
+        owner_propuesto: Option<AccountId>,
+        admins: Mapping<AccountId, ()>,
+        arbitros: Mapping<AccountId, ()>,
+        carritos: Mapping<AccountId, Vec<ItemCarrito>>,
+        plazo_envio: Timestamp,
+        plazo_confirmacion: Timestamp,
+        max_calificaciones_por_par: u32,
+        ventana_calificaciones: Timestamp,
+        calificaciones_por_par: Mapping<ParCalificacion, Vec<Timestamp>>,
+        valor_minimo_calificacion: Balance,
+        vida_media_reputacion: BlockNumber,
+        plazo_revelacion: Timestamp,
+        calificaciones_ocultas: Mapping<u32, CalificacionesOcultas>,
         m_usuarios: Mapping<AccountId, Usuario>,
         v_usuarios: StorageVec<AccountId>,
+        usuarios_por_mail: Mapping<String, AccountId>,
+        usuarios_por_nombre: Mapping<String, AccountId>,
         productos: StorageVec<Producto>,
+        productos_por_clave: Mapping<ClaveProducto, u32>,
+        productos_por_vendedor: Mapping<PosicionUsuario, u32>,
+        cantidad_productos_por_vendedor: Mapping<AccountId, u32>,
+        productos_por_sku: Mapping<String, u32>,
+        publicaciones_por_producto: Mapping<u32, Vec<u32>>,
+        publicaciones_por_vendedor: Mapping<PosicionUsuario, u32>,
+        publicacion_abierta: Mapping<(AccountId, u32), u32>, //(vendedor, producto) -> publicación sin cerrar
+        cantidad_publicaciones_por_vendedor: Mapping<AccountId, u32>,
+        ordenes_abiertas_por_producto: Mapping<u32, u32>,
         ordenes: StorageVec<Orden>,
+        ordenes_por_comprador: Mapping<PosicionUsuario, u32>,
+        ordenes_por_vendedor: Mapping<PosicionUsuario, u32>,
+        cantidad_ordenes: Mapping<AccountId, CantidadOrdenes>,
+        historial_ordenes: Mapping<u32, Vec<TransicionOrden>>,
+        resenas: StorageVec<Resena>,
+        resenas_por_usuario: Mapping<PosicionUsuario, u32>,
+        cantidad_resenas_por_usuario: Mapping<AccountId, u32>,
         publicaciones: StorageVec<Publicacion>,
         categorias: StorageVec<Categoria>,
+        ventas_por_producto: Mapping<u32, VentasAcumuladas>,
+        ventas_por_categoria: Mapping<u32, VentasAcumuladas>,
+        ranking_productos: StorageVec<u32>, //productos vendidos, de más a menos unidades
+        posicion_ranking_productos: Mapping<u32, u32>,
+        ranking_vendedores: StorageVec<AccountId>, //vendedores calificados, de mejor a peor promedio
+        posicion_ranking_vendedores: Mapping<AccountId, u32>,
+        ranking_compradores: StorageVec<AccountId>, //compradores calificados, de mejor a peor promedio
+        posicion_ranking_compradores: Mapping<AccountId, u32>,
+    }
+
+    impl Default for Sistema {
+        fn default() -> Self {
+            Self::new()
+        }
     }
 
     impl Sistema {
@@ -208,18 +764,58 @@ mod contract {
         /// Inicializa todas las estructuras de almacenamiento (`Mapping` y `Vec`) vacías.
         ///
         /// Se ejecuta una única vez al desplegar el contrato en la blockchain.
-        /// No realiza ninguna lógica adicional.
+        /// La cuenta que lo despliega queda registrada como owner del contrato.
         ///
         /// Retorna una instancia del contrato lista para ser utilizada.
         #[ink(constructor)]
         pub fn new() -> Self {
             Sistema {
+                owner: Self::env().caller(),
+                owner_propuesto: None,
+                admins: Mapping::default(),
+                arbitros: Mapping::default(),
+                carritos: Mapping::default(),
+                plazo_envio: PLAZO_ENVIO_DEFAULT,
+                plazo_confirmacion: PLAZO_CONFIRMACION_DEFAULT,
+                max_calificaciones_por_par: MAX_CALIFICACIONES_POR_PAR_DEFAULT,
+                ventana_calificaciones: VENTANA_CALIFICACIONES_DEFAULT,
+                calificaciones_por_par: Mapping::default(),
+                valor_minimo_calificacion: 0,
+                vida_media_reputacion: VIDA_MEDIA_REPUTACION_DEFAULT,
+                plazo_revelacion: PLAZO_REVELACION_DEFAULT,
+                calificaciones_ocultas: Mapping::default(),
                 m_usuarios: Mapping::default(),
                 v_usuarios: StorageVec::new(),
+                usuarios_por_mail: Mapping::default(),
+                usuarios_por_nombre: Mapping::default(),
                 productos: StorageVec::default(),
+                productos_por_clave: Mapping::default(),
+                productos_por_vendedor: Mapping::default(),
+                cantidad_productos_por_vendedor: Mapping::default(),
+                productos_por_sku: Mapping::default(),
+                publicaciones_por_producto: Mapping::default(),
+                publicaciones_por_vendedor: Mapping::default(),
+                publicacion_abierta: Mapping::default(),
+                cantidad_publicaciones_por_vendedor: Mapping::default(),
+                ordenes_abiertas_por_producto: Mapping::default(),
                 ordenes: StorageVec::default(),
+                ordenes_por_comprador: Mapping::default(),
+                ordenes_por_vendedor: Mapping::default(),
+                cantidad_ordenes: Mapping::default(),
+                historial_ordenes: Mapping::default(),
+                resenas: StorageVec::default(),
+                resenas_por_usuario: Mapping::default(),
+                cantidad_resenas_por_usuario: Mapping::default(),
                 publicaciones: StorageVec::default(),
                 categorias: StorageVec::default(),
+                ventas_por_producto: Mapping::default(),
+                ventas_por_categoria: Mapping::default(),
+                ranking_productos: StorageVec::default(),
+                posicion_ranking_productos: Mapping::default(),
+                ranking_vendedores: StorageVec::default(),
+                posicion_ranking_vendedores: Mapping::default(),
+                ranking_compradores: StorageVec::default(),
+                posicion_ranking_compradores: Mapping::default(),
             }
         }
 
@@ -241,10 +837,34 @@ mod contract {
             nombre: String,
             mail: String,
             rol: Rol,
-        ) -> Result<String, ErroresContrato> {
+        ) -> Result<(), ErroresContrato> {
             self._registrar_usuario(self.env().caller(), nombre, mail, rol)
         }
 
+        /// Actualiza el nombre de usuario y el mail del usuario que llama.
+        ///
+        /// Los índices de búsqueda por mail y por nombre se actualizan en el mismo paso,
+        /// liberando los valores anteriores para que puedan ser usados por otros usuarios.
+        ///
+        /// # Parámetros
+        /// - `nombre`: Nuevo nombre de usuario.
+        /// - `mail`: Nuevo correo electrónico.
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste` si el llamador no está registrado.
+        /// - `NombreUsuarioVacio` si el campo de nombre se encuentra vacío.
+        /// - `MailUsuarioVacio` si el campo de mail se encuentra vacío.
+        /// - `MailYaExistente` si el mail pertenece a otro usuario.
+        /// - `UsuarioYaExistente` si el nombre pertenece a otro usuario.
+        #[ink(message)]
+        pub fn actualizar_perfil(
+            &mut self,
+            nombre: String,
+            mail: String,
+        ) -> Result<(), ErroresContrato> {
+            self._actualizar_perfil(self.env().caller(), nombre, mail)
+        }
+
         /// Publica un producto previamente registrado en el contrato, generando una publicación activa.
         ///
         /// # Parámetros
@@ -262,6 +882,8 @@ mod contract {
         /// - `PrecioInvalido` si el precio introducido es 0
         /// - `RolNoApropiado` si el usuario no posee el rol `Vendedor`
         /// - `StockInsuficiente` si el stock introducido es más de lo disponible del producto
+        /// - `ProductoAjeno` si el producto es de otro vendedor y no está compartido
+        /// - `PublicacionYaAbierta` si el caller ya tiene una publicación sin cerrar del producto
         #[ink(message)]
         pub fn crear_publicacion(
             &mut self,
@@ -272,22 +894,87 @@ mod contract {
             self._crear_publicacion(id_producto, self.env().caller(), stock, precio)
         }
 
+        /// Cambia el precio unitario de una publicación propia. Las órdenes ya creadas
+        /// conservan el precio con que se compraron.
+        ///
+        /// # Errores
+        /// - `PrecioInvalido` si el precio es 0.
+        /// - `PublicacionNoExiste` si la publicación no existe.
+        /// - `PublicacionAjena` si el caller no es quien creó la publicación.
+        /// - `PublicacionCerrada` si la publicación fue cerrada.
+        #[ink(message)]
+        pub fn actualizar_precio(&mut self, id_pub: u32, precio: Balance) -> Result<(), ErroresContrato> {
+            self._actualizar_precio(id_pub, self.env().caller(), precio)
+        }
+
+        /// Agrega unidades a una publicación propia, descontándolas del stock del producto.
+        /// Si la publicación es de un producto compartido de otro vendedor, las unidades
+        /// son propias y no se descuentan del producto.
+        ///
+        /// # Errores
+        /// - `StockInvalido` si la cantidad es 0.
+        /// - `PublicacionNoExiste` si la publicación no existe.
+        /// - `PublicacionAjena` si el caller no es quien creó la publicación.
+        /// - `PublicacionCerrada` si la publicación fue cerrada.
+        /// - `StockInsuficiente` si el producto no tiene las unidades pedidas.
+        #[ink(message)]
+        pub fn reponer_stock(&mut self, id_pub: u32, cantidad: u32) -> Result<(), ErroresContrato> {
+            self._reponer_stock(id_pub, self.env().caller(), cantidad)
+        }
+
+        /// Pausa una publicación propia: deja de aceptar compras hasta que se reanude.
+        ///
+        /// # Errores
+        /// - `PublicacionNoExiste` si la publicación no existe.
+        /// - `PublicacionAjena` si el caller no es quien creó la publicación.
+        /// - `PublicacionCerrada` si la publicación fue cerrada.
+        /// - `PublicacionYaPausada` si ya estaba pausada.
+        #[ink(message)]
+        pub fn pausar_publicacion(&mut self, id_pub: u32) -> Result<(), ErroresContrato> {
+            self._pausar_publicacion(id_pub, self.env().caller())
+        }
+
+        /// Reanuda una publicación pausada. Vuelve a estar activa si le queda stock.
+        ///
+        /// # Errores
+        /// - `PublicacionNoExiste` si la publicación no existe.
+        /// - `PublicacionAjena` si el caller no es quien creó la publicación.
+        /// - `PublicacionCerrada` si la publicación fue cerrada.
+        /// - `PublicacionNoPausada` si la publicación no estaba pausada.
+        #[ink(message)]
+        pub fn reanudar_publicacion(&mut self, id_pub: u32) -> Result<(), ErroresContrato> {
+            self._reanudar_publicacion(id_pub, self.env().caller())
+        }
+
+        /// Cierra definitivamente una publicación propia. Las unidades sin vender vuelven
+        /// al stock del producto.
+        ///
+        /// # Errores
+        /// - `PublicacionNoExiste` si la publicación no existe.
+        /// - `PublicacionAjena` si el caller no es quien creó la publicación.
+        /// - `PublicacionCerrada` si la publicación ya estaba cerrada.
+        #[ink(message)]
+        pub fn cerrar_publicacion(&mut self, id_pub: u32) -> Result<(), ErroresContrato> {
+            self._cerrar_publicacion(id_pub, self.env().caller())
+        }
+
         /// Registra una nueva categoría de productos en el contrato.
         ///
         /// # Parámetros
         /// - `nombre`: Nombre de la categoría a registrar.
         ///
+        /// Retorna el ID de la categoría creada.
+        ///
         /// # Requisitos
-        /// - El caller debe estar previamente registrado como usuario.
+        /// - El caller debe ser el owner o un administrador del contrato.
         ///
         /// # Errores
-        /// - `UsuarioNoExiste`: Si el usuario que intenta registrar la categoría no está registrado.
+        /// - `NoEsAdmin`: Si el caller no es owner ni administrador.
         /// - `CategoriaYaExistente`: Si la categoria ya existe actualmente.
         /// - `MaxCategoriasAlcanzado`: Si se ha alcanzado la cantidad máxima de categorías posibles para registrar
         #[ink(message)]
-        pub fn registrar_categoria(&mut self, nombre: String) -> Result<String, ErroresContrato> {
-            // Comprobar que el usuario esta registrado en la plataforma
-            self.get_user(&self.env().caller())?;
+        pub fn registrar_categoria(&mut self, nombre: String) -> Result<u32, ErroresContrato> {
+            self._solo_admin(self.env().caller())?;
             self._registrar_categoria(nombre)
         }
 
@@ -304,7 +991,7 @@ mod contract {
         ///
         /// # Errores
         /// - `UsuarioNoExiste` si el usuario no está registrado.
-        /// - `ProductoYaExistente` si ya existe un producto con ese nombre y categoría.
+        /// - `ProductoYaExistente` si el vendedor ya tiene un producto con ese nombre y categoría.
         /// - `DatosInvalidos` si el nombre o la descripción se encuentran vacíos
         /// - `StockInvalido` si el stock introducido es 0 
         /// - `UsuarioNoEsVendedor` si el usuario no tiene el rol `Vendedor`
@@ -320,8 +1007,34 @@ mod contract {
             self._crear_producto(self.env().caller(), nombre, descripcion, categoria, stock)
         }
 
+        /// Habilita o deshabilita que otros vendedores publiquen un producto propio.
+        ///
+        /// Por defecto un producto solo puede ser publicado por quien lo registró. Al
+        /// compartirlo, otros vendedores pueden crear publicaciones sobre él con su propio
+        /// stock, sin descontar el del producto original.
+        ///
+        /// # Parámetros
+        /// - `id_producto`: ID del producto.
+        /// - `compartido`: `true` para compartirlo, `false` para dejar de hacerlo.
+        ///
+        /// # Errores
+        /// - `ProductoInexistente` si el producto no existe.
+        /// - `ProductoAjeno` si el llamador no es el vendedor que registró el producto.
+        #[ink(message)]
+        pub fn compartir_producto(
+            &mut self,
+            id_producto: u32,
+            compartido: bool,
+        ) -> Result<(), ErroresContrato> {
+            self._compartir_producto(id_producto, self.env().caller(), compartido)
+        }
+
         /// Crea una orden de compra sobre una publicación activa.
         ///
+        /// El pago se envía junto con la llamada y queda retenido en el contrato hasta que
+        /// el comprador confirme la recepción (se libera al vendedor) o la orden se cancele
+        /// (se devuelve al comprador).
+        ///
         /// # Parámetros
         /// - `id_publicacion`: ID de la publicación a comprar.
         /// - `cantidad`: Cantidad solicitada.
@@ -329,6 +1042,7 @@ mod contract {
         /// # Requisitos
         /// - El caller debe estar registrado y tener el rol `Comprador`.
         /// - El usuario que inició la publiacación debe seguir teniendo el rol `Vendedor`
+        /// - El valor transferido debe ser exactamente el precio total de la orden
         ///
         /// # Errores
         /// - `UsuarioNoExiste` si el caller no está registrado.
@@ -337,39 +1051,145 @@ mod contract {
         /// - `ProductoInexistente` si el producto vinculado a la publicación no existe.
         /// - `ErrorMultiplicacion` si se produjo un error al multiplicar el precio del producto por la cantidad solicitada.
         /// - `StockInsuficiente` si el stock de la publicación es menor a lo solicitado en la orden
+        /// - `PublicacionInactiva` si la publicación está pausada o cerrada
         /// - `CantidadEnCarritoMenorAUno` si la cantidad solicitada para comprar es menor a 1
-        #[ink(message)]
+        /// - `PagoInsuficiente` si el valor transferido es menor al precio total
+        /// - `PagoExcedente` si el valor transferido es mayor al precio total
+        #[ink(message, payable)]
         pub fn crear_orden(
             &mut self,
             id_pub: u32,
             cantidad: u32,
-            //precio_total: Decimal
         ) -> Result<u32, ErroresContrato> {
-            self._crear_orden(id_pub, self.env().caller(), cantidad)
+            let pago = self.env().transferred_value();
+            self._crear_orden(id_pub, self.env().caller(), cantidad, pago)
         }
 
-        /// Marca una orden como `Enviada`.
+        /// Crea una orden de compra sobre un producto, resolviendo automáticamente la mejor
+        /// oferta disponible (ver `mejor_oferta`) con stock suficiente para la cantidad pedida.
         ///
         /// # Parámetros
-        /// - `id_orden`: ID de la orden a actualizar.
-        ///
-        /// # Requisitos
-        /// - El caller debe estar registrado y tener rol de `Vendedor`.
-        /// - El caller debe tener una orden asociada como vendedor
-        /// - La orden debe estar en estado `Pendiente`
+        /// - `id_producto`: ID del producto a comprar.
+        /// - `cantidad`: Cantidad solicitada.
         ///
         /// # Errores
-        /// - `OrdenInexistente` si no existe la orden.
-        /// - `OrdenNoPendiente` si la orden ya fue enviada, recibida o cancelada.
-        /// - `CuentaNoRegistrada` si el caller no está registrado.
-        /// - `RolNoApropiado` si el usuario no tiene el rol de `Vendedor`
-        /// - `NoEsVendedorOriginal` si el usuario no corresponde con el vendedor que inició la orden
-        #[ink(message)]
-        pub fn enviar_producto(&mut self, id_orden: u32) -> Result<String, ErroresContrato> {
-            // Compruebo que el usuario existe y posee rol de vendedor
-            self._usuario_con_rol(VENDEDOR)?;
-            self._enviar_orden(id_orden, self.env().caller())?;
-            Ok(String::from("La orden fue enviada correctamente"))
+        /// - `SinOfertas` si ninguna publicación activa de otro vendedor tiene stock suficiente.
+        /// - Los mismos errores que `crear_orden` sobre la publicación elegida.
+        #[ink(message, payable)]
+        pub fn crear_orden_por_producto(
+            &mut self,
+            id_producto: u32,
+            cantidad: u32,
+        ) -> Result<u32, ErroresContrato> {
+            let pago = self.env().transferred_value();
+            let caller = self.env().caller();
+            let oferta = self._mejor_oferta(id_producto, caller, cantidad)?;
+            self._crear_orden(oferta.get_id(), caller, cantidad, pago)
+        }
+
+        /// Agrega una publicación al carrito del caller. Si ya estaba en el carrito se suma
+        /// la cantidad a la línea existente.
+        ///
+        /// # Parámetros
+        /// - `id_publicacion`: ID de la publicación a agregar.
+        /// - `cantidad`: Cantidad a agregar.
+        ///
+        /// # Requisitos
+        /// - El caller debe estar registrado y tener el rol `Comprador`.
+        /// - Todas las publicaciones del carrito deben ser del mismo vendedor.
+        ///
+        /// # Errores
+        /// - `CuentaNoRegistrada` si el caller no está registrado.
+        /// - `RolNoApropiado` si el caller no tiene el rol `Comprador`.
+        /// - `CantidadEnCarritoMenorAUno` si la cantidad es 0.
+        /// - `PublicacionNoExiste` si no existe la publicación.
+        /// - `VendedorDistintoEnCarrito` si la publicación es de otro vendedor que las del carrito.
+        /// - `StockInsuficiente` si la cantidad total supera el stock de la publicación.
+        /// - `CarritoLleno` si el carrito ya tiene la cantidad máxima de líneas.
+        #[ink(message)]
+        pub fn agregar_al_carrito(&mut self, id_pub: u32, cantidad: u32) -> Result<(), ErroresContrato> {
+            self._agregar_al_carrito(self.env().caller(), id_pub, cantidad)
+        }
+
+        /// Reemplaza la cantidad de una línea del carrito del caller.
+        ///
+        /// # Parámetros
+        /// - `id_publicacion`: ID de la publicación de la línea a modificar.
+        /// - `cantidad`: Nueva cantidad.
+        ///
+        /// # Errores
+        /// - `CantidadEnCarritoMenorAUno` si la cantidad es 0.
+        /// - `PublicacionNoEnCarrito` si la publicación no está en el carrito.
+        /// - `StockInsuficiente` si la cantidad supera el stock de la publicación.
+        #[ink(message)]
+        pub fn actualizar_carrito(&mut self, id_pub: u32, cantidad: u32) -> Result<(), ErroresContrato> {
+            self._actualizar_carrito(self.env().caller(), id_pub, cantidad)
+        }
+
+        /// Quita una línea del carrito del caller.
+        ///
+        /// # Errores
+        /// - `PublicacionNoEnCarrito` si la publicación no está en el carrito.
+        #[ink(message)]
+        pub fn quitar_del_carrito(&mut self, id_pub: u32) -> Result<(), ErroresContrato> {
+            self._quitar_del_carrito(self.env().caller(), id_pub)
+        }
+
+        /// Vacía el carrito del caller.
+        #[ink(message)]
+        pub fn vaciar_carrito(&mut self) {
+            self.carritos.remove(self.env().caller());
+        }
+
+        /// Devuelve las líneas del carrito del caller.
+        #[ink(message)]
+        pub fn ver_carrito(&self) -> Vec<ItemCarrito> {
+            self._ver_carrito(self.env().caller())
+        }
+
+        /// Confirma el carrito del caller creando una única orden con una línea por publicación.
+        ///
+        /// El stock de todas las líneas se descuenta en conjunto: si alguna no tiene stock
+        /// suficiente no se crea la orden y el carrito queda intacto. Al confirmarse, el carrito
+        /// se vacía. El pago se retiene igual que en `crear_orden`.
+        ///
+        /// # Requisitos
+        /// - El valor transferido debe ser exactamente la suma de los subtotales de las líneas.
+        ///
+        /// # Errores
+        /// - `ListaSinProductos` si el carrito está vacío.
+        /// - `RolNoApropiado` si uno o ambos usuarios no tienen los roles apropiados para crear una orden.
+        /// - `StockInsuficiente` si alguna publicación no tiene stock suficiente.
+        /// - `PagoInsuficiente` si el valor transferido es menor al precio total
+        /// - `PagoExcedente` si el valor transferido es mayor al precio total
+        #[ink(message, payable)]
+        pub fn confirmar_carrito(&mut self) -> Result<u32, ErroresContrato> {
+            let pago = self.env().transferred_value();
+            self._confirmar_carrito(self.env().caller(), pago)
+        }
+
+        /// Marca una orden como `Enviada`.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden a actualizar.
+        ///
+        /// # Requisitos
+        /// - El caller debe estar registrado y tener rol de `Vendedor`.
+        /// - El caller debe tener una orden asociada como vendedor
+        /// - La orden debe estar en estado `Pendiente`
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si no existe la orden.
+        /// - `OrdenNoPendiente` si la orden ya fue enviada, recibida o cancelada.
+        /// - `CuentaNoRegistrada` si el caller no está registrado.
+        /// - `RolNoApropiado` si el usuario no tiene el rol de `Vendedor`
+        /// - `NoEsVendedorOriginal` si el usuario no corresponde con el vendedor que inició la orden
+        #[ink(message)]
+        pub fn enviar_producto(&mut self, id_orden: u32) -> Result<EstadoOrden, ErroresContrato> {
+            // Compruebo que el usuario existe y posee rol de vendedor
+            self._usuario_con_rol(VENDEDOR)?;
+            self._enviar_orden(id_orden, self.env().caller())?;
+            Ok(EstadoOrden::Enviada)
         }
 
         /// Marca una orden como `Recibida`.
@@ -377,6 +1197,8 @@ mod contract {
         /// # Parámetros
         /// - `id_orden`: ID de la orden a actualizar.
         ///
+        /// Retorna el nuevo estado de la orden.
+        ///
         /// # Requisitos
         /// - El caller debe estar registrado y tener rol de `Comprador`.
         /// - El caller debe tener una orden asociada como comprador
@@ -388,37 +1210,69 @@ mod contract {
         /// - `CuentaNoRegistrada` si el caller no está registrado.
         /// - `RolNoApropiado` si el usuario no tiene el rol de `Comprador`
         /// - `NoEsCompradorOriginal` si el usuario no es el comprador que inició la orden
+        /// - `ErrorTransferencia` si no se pudo liberar el pago retenido al vendedor
         #[ink(message)]
-        pub fn recibir_producto(&mut self, id_orden: u32) -> Result<String, ErroresContrato> {
+        pub fn recibir_producto(&mut self, id_orden: u32) -> Result<EstadoOrden, ErroresContrato> {
             // Compruebo que el usuario existe y posee rol de vendedor
             self._usuario_con_rol(COMPRADOR)?;
             self._recibir_orden(id_orden, self.env().caller())?;
-            Ok(String::from("La orden fue recibida correctamente"))
+            Ok(EstadoOrden::Recibida)
         }
 
-        /// Cancela una orden pendiente o aún no enviada.
+        /// Propone o confirma la cancelación de una orden aún no enviada.
+        ///
+        /// Cualquiera de las partes puede proponer la cancelación de una orden `Pendiente`,
+        /// que pasa a `PreCancelada`. Cuando la otra parte llama a este mensaje la cancelación
+        /// se confirma: se devuelve el stock a la publicación y el pago al comprador.
         ///
         /// # Parámetros
         /// - `id_orden`: ID de la orden a cancelar
+        /// - `motivo`: Motivo de la cancelación. Solo se registra al proponerla.
+        ///
+        /// Retorna `CancelacionIniciada` al proponerla y `CancelacionConfirmada` al confirmarla.
         ///
         /// # Requisitos
-        /// - El caller debe estar registrado y tener rol de `Comprador`.
-        /// - El caller debe tener una orden asociada
+        /// - El caller debe ser el comprador (con rol `Comprador`) o el vendedor (con rol `Vendedor`) de la orden.
         ///
         /// # Errores
         /// - `OrdenInexistente` si la orden no existe.
         /// - `OrdenYaCancelada` si ya fue cancelada previamente.
         /// - `CuentaNoRegistrada` si el caller no está registrado.
-        /// - `CancelacionDeOrdenSinConsenso` si el vendedor intenta cancelar la orden antes que el comprador
-        /// - `OrdenNoPendiente` si la orden ya fue enviada o recibida, o la cancelación ya fue iniciada
+        /// - `CancelacionDeOrdenSinConsenso` si el caller ya propuso la cancelación y falta la confirmación de la otra parte
+        /// - `OrdenNoPendiente` si la orden ya fue enviada o recibida
+        /// - `UsuarioNoCorresponde` si el usuario no pertenece a la orden
+        /// - `ErrorTransferencia` si no se pudo reembolsar el pago retenido al comprador
+        #[ink(message)]
+        pub fn cancelar_orden(
+            &mut self,
+            id_orden: u32,
+            motivo: MotivoCancelacion,
+        ) -> Result<ResultadoCancelacion, ErroresContrato> {
+            self._cancelar_orden(id_orden, self.env().caller(), motivo)
+        }
+
+        /// Rechaza la cancelación propuesta por la otra parte. La orden vuelve al estado
+        /// que tenía antes de la propuesta.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `SinCancelacionPendiente` si la orden no tiene una cancelación propuesta.
         /// - `UsuarioNoCorresponde` si el usuario no pertenece a la orden
+        /// - `CancelacionPropia` si el caller es quien propuso la cancelación
         #[ink(message)]
-        pub fn cancelar_orden(&mut self, id_orden: u32) -> Result<String, ErroresContrato> {
-            self._cancelar_orden(id_orden, self.env().caller())
+        pub fn rechazar_cancelacion(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+            self._rechazar_cancelacion(id_orden, self.env().caller())
         }
 
         /// Asigna una calificación según el rol del Usuario
         ///
+        /// La calificación queda oculta hasta que califica la otra parte o vence el plazo de
+        /// revelación (ver `revelar_calificaciones`), así ninguna parte califica sabiendo qué
+        /// puntaje recibió. Recién ahí se guarda en la orden y suma a la reputación.
+        ///
         /// # Parámetros
         /// - `id_orden`: Id de la orden a calificar
         /// - `puntaje`: puntaje a otorgar a la orden
@@ -431,6 +1285,7 @@ mod contract {
         /// - `PuntajeInvalido` si el puntaje no es entre 1 y 5
         /// - `OrdenNoRecibida` si el estado de la orden no es "Recibida"
         /// - `YaCalificado` si el usuario ya ha calificado la orden previamente
+        /// - `CalificacionCerrada` si las calificaciones de la orden ya se revelaron por vencimiento
         /// - `UsuarioNoCorresponde` si el usuario no es comprador ni vendedor de la orden
         /// - `OrdenInexistente` si la orden no existe
         /// - `UsuarioNoExiste` si uno de los usuarios de la orden dejó de estar registrado
@@ -440,10 +1295,92 @@ mod contract {
             &mut self,
             id_orden: u32,
             puntaje: u8,
-        ) -> Result<String, ErroresContrato> {
-            let id = self.env().caller();
-            self._calificar_orden(id_orden, id, puntaje)?;
-            Ok(String::from("La calificación fue exitosa"))
+        ) -> Result<(), ErroresContrato> {
+            self._calificar_orden(id_orden, self.env().caller(), puntaje)
+        }
+
+        /// Califica una orden igual que `calificar_compra` y deja además una reseña escrita
+        /// para la otra parte. Como la calificación, se puede hacer una sola vez por orden
+        /// y no se lista hasta que se revelan las calificaciones de la orden.
+        ///
+        /// Retorna el id de la reseña.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: Id de la orden a calificar
+        /// - `puntaje`: puntaje a otorgar a la orden
+        /// - `texto`: reseña de hasta `MAX_LARGO_RESENA` bytes
+        ///
+        /// # Errores
+        /// - `ResenaInvalida` si el texto está vacío o supera `MAX_LARGO_RESENA`.
+        /// - Los mismos errores que `calificar_compra`.
+        #[ink(message)]
+        pub fn calificar_con_resena(
+            &mut self,
+            id_orden: u32,
+            puntaje: u8,
+            texto: String,
+        ) -> Result<u32, ErroresContrato> {
+            self._resenar_orden(id_orden, self.env().caller(), puntaje, texto)
+        }
+
+        /// Revela las calificaciones de una orden a la que le falta la de una de las partes,
+        /// una vez vencido el plazo de revelación. Puede llamarlo cualquier cuenta. Después de
+        /// revelarlas la otra parte ya no puede calificar la orden.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: Id de la orden.
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `SinCalificacionesPendientes` si la orden no tiene calificaciones ocultas.
+        /// - `PlazoNoVencido` si no pasó el plazo de revelación desde la primera calificación.
+        /// - `UsuarioNoExiste` si uno de los usuarios de la orden dejó de estar registrado.
+        #[ink(message)]
+        pub fn revelar_calificaciones(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+            self._revelar_calificaciones(id_orden)
+        }
+
+        /// Publica la respuesta a una reseña recibida. Cada reseña admite una sola respuesta.
+        ///
+        /// # Parámetros
+        /// - `id_resena`: Id de la reseña a responder
+        /// - `texto`: respuesta de hasta `MAX_LARGO_RESENA` bytes
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el usuario calificado en la reseña.
+        ///
+        /// # Errores
+        /// - `ResenaInvalida` si el texto está vacío o supera `MAX_LARGO_RESENA`.
+        /// - `ResenaInexistente` si la reseña no existe o su calificación todavía está oculta.
+        /// - `NoEsDestinatarioResena` si el caller no es el usuario calificado.
+        /// - `ResenaYaRespondida` si la reseña ya tiene respuesta.
+        #[ink(message)]
+        pub fn responder_resena(&mut self, id_resena: u32, texto: String) -> Result<(), ErroresContrato> {
+            self._responder_resena(id_resena, self.env().caller(), texto)
+        }
+
+        /// Devuelve una página de las reseñas que recibió un usuario, de la más antigua a la más reciente.
+        ///
+        /// # Parámetros
+        /// - `id_usuario`: AccountId del usuario calificado.
+        /// - `desde`, `tamano_pagina`: Paginado, igual que en `listar_usuarios`. Las reseñas de
+        ///   órdenes con calificaciones todavía ocultas se omiten dentro de la página, por lo que
+        ///   puede traer menos elementos; `total` cuenta todas las reseñas recibidas.
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste` si el usuario no está registrado.
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_resenas(
+            &self,
+            id_usuario: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Resena>, ErroresContrato> {
+            if !self.m_usuarios.contains(id_usuario) {
+                return Err(ErroresContrato::UsuarioNoExiste);
+            }
+            self._listar_resenas(id_usuario, desde, tamano_pagina)
         }
 
         ///Asigna un rol al usuario correspondiente al AccountId que lo envía
@@ -459,2652 +1396,8071 @@ mod contract {
         /// - `UsuarioNoExiste` si el caller no es un usuario registrado
         ///
         #[ink(message)]
-        pub fn asignar_rol(&mut self, rol: Rol) -> Result<String, ErroresContrato> {
+        pub fn asignar_rol(&mut self, rol: Rol) -> Result<(), ErroresContrato> {
             self._asignar_rol(self.env().caller(), rol)
         }
 
+        /// Devuelve la reputación de un usuario como comprador y como vendedor.
+        ///
+        /// # Parámetros
+        /// - `id`: AccountId del usuario a consultar.
+        /// - `decimales`: Cantidad de decimales del promedio, que se devuelve multiplicado
+        ///   por 10^`decimales` (por ejemplo 4,25 con 2 decimales es 425).
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste` si el usuario no está registrado.
+        /// - `PrecisionInvalida` si `decimales` supera `MAX_DECIMALES_PROMEDIO`.
+        #[ink(message)]
+        pub fn get_reputacion(&self, id: AccountId, decimales: u8) -> Result<ReputacionUsuario, ErroresContrato> {
+            let usuario = self
+                .m_usuarios
+                .get(id)
+                .ok_or(ErroresContrato::UsuarioNoExiste)?;
+            Ok(ReputacionUsuario {
+                id,
+                como_comprador: usuario.get_reputacion_comprador(decimales)?,
+                como_vendedor: usuario.get_reputacion_vendedor(decimales)?,
+            })
+        }
+
         /// Devuelve la cantidad de usuarios registrados en el contrato.
         #[ink(message)]
         pub fn get_cantidad_usuarios(&self) -> u32 {
             self._get_cantidad_usuarios()
         }
 
-        /// Devuelve una lista de todos los usuarios registrados en el contrato.
+        /// Devuelve una página de los usuarios registrados, en orden de registro.
+        ///
+        /// Todos los mensajes de listado se paginan igual: `desde` es la posición del primer
+        /// elemento (`0` para la primera página) y la página indica el `total` de elementos y
+        /// en `siguiente` dónde empieza la próxima, o `None` si es la última.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_usuarios(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Usuario>, ErroresContrato> {
+            self._listar_usuarios(desde, tamano_pagina)
+        }
+
+        /// Devuelve una página de los productos registrados en el contrato.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_productos(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Producto>, ErroresContrato> {
+            self._listar_productos(desde, tamano_pagina)
+        }
+
+        /// Devuelve los productos con los ids indicados, en el mismo orden.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si se piden más de `MAX_TAMANO_PAGINA` ids.
+        /// - `ProductoInexistente` si alguno de los productos no existe.
         #[ink(message)]
-        pub fn listar_usuarios(&self, pagina: u32, tamano_pagina: u32) -> Vec<Usuario> {
-            self._listar_usuarios(pagina, tamano_pagina)
+        pub fn get_productos(&self, ids: Vec<u32>) -> Result<Vec<Producto>, ErroresContrato> {
+            obtener_por_ids(ids, ErroresContrato::ProductoInexistente, |id| self.productos.get(id))
         }
 
-        /// Devuelve una lista de todos los productos registrados en el contrato.
+        /// Modifica el nombre, la descripción y la categoría de un producto propio.
+        ///
+        /// # Parámetros
+        /// - `id_producto`: ID del producto.
+        /// - `nombre`: Nuevo nombre.
+        /// - `descripcion`: Nueva descripción.
+        /// - `categoria`: Nombre de la nueva categoría.
+        ///
+        /// # Errores
+        /// - `DatosInvalidos` si el nombre o la descripción se encuentran vacíos.
+        /// - `ProductoInexistente` si el producto no existe.
+        /// - `ProductoAjeno` si el llamador no es el vendedor que registró el producto.
+        /// - `ProductoRetirado` si el producto fue retirado.
+        /// - `CategoriaInexistente` si la categoría no existe.
+        /// - `ProductoYaExistente` si el vendedor ya tiene otro producto con ese nombre y categoría.
         #[ink(message)]
-        pub fn listar_productos(&self) -> Vec<Producto> {
-            self._listar_productos()
+        pub fn editar_producto(
+            &mut self,
+            id_producto: u32,
+            nombre: String,
+            descripcion: String,
+            categoria: String,
+        ) -> Result<(), ErroresContrato> {
+            self._editar_producto(id_producto, self.env().caller(), nombre, descripcion, categoria)
         }
 
-        /// Devuelve una lista de todas las publicaciones en el contrato.
+        /// Fija el stock en depósito de un producto propio, es decir las unidades que todavía
+        /// no fueron asignadas a ninguna publicación.
+        ///
+        /// # Errores
+        /// - `ProductoInexistente` si el producto no existe.
+        /// - `ProductoAjeno` si el llamador no es el vendedor que registró el producto.
+        /// - `ProductoRetirado` si el producto fue retirado.
         #[ink(message)]
-        pub fn listar_publicaciones(&self) -> Vec<Publicacion> {
-            self._listar_publicaciones()
+        pub fn ajustar_stock_producto(&mut self, id_producto: u32, stock: u32) -> Result<(), ErroresContrato> {
+            self._ajustar_stock_producto(id_producto, self.env().caller(), stock)
         }
 
-        /// Devuelve una lista de todas las publicaciones del usuario loggeado
+        /// Retira un producto propio del catálogo. El producto se conserva para el historial
+        /// de órdenes, pero ya no puede publicarse ni modificarse, y su nombre y SKU quedan libres.
         ///
         /// # Requisitos
-        /// - El caller debe estar registrado y tener rol de `Vendedor`.
+        /// - Todas las publicaciones del producto deben estar cerradas.
+        /// - Ninguna orden sobre sus publicaciones puede estar sin terminar.
         ///
         /// # Errores
-        /// - `CuentaNoRegistrada` si el caller no está registrado.
         /// - `ProductoInexistente` si el producto no existe.
+        /// - `ProductoAjeno` si el llamador no es el vendedor que registró el producto.
+        /// - `ProductoRetirado` si el producto ya fue retirado.
+        /// - `ProductoConPublicacionesActivas` si alguna publicación del producto sigue abierta.
+        /// - `ProductoConOrdenesAbiertas` si hay órdenes sin terminar sobre el producto.
         #[ink(message)]
-        pub fn listar_publicaciones_propias(&self) -> Result<Vec<Publicacion>, ErroresContrato> {
-            let id = self.env().caller();
-            self._usuario_con_rol(VENDEDOR)?;
-            Ok(self._listar_publicaciones_propias(id))
+        pub fn retirar_producto(&mut self, id_producto: u32) -> Result<(), ErroresContrato> {
+            self._retirar_producto(id_producto, self.env().caller())
         }
 
-        /// Devuelve una lista de todas las ordenes de compra registradas en el contrato.
+        /// Incorpora un producto propio al catálogo compartido, identificándolo con un SKU/GTIN.
+        ///
+        /// Un producto con SKU queda compartido: cualquier vendedor puede ofrecerlo a través de
+        /// su propia publicación. Si el producto ya tenía un SKU, se reemplaza.
+        ///
+        /// # Parámetros
+        /// - `id_producto`: ID del producto.
+        /// - `sku`: Código SKU/GTIN, sin distinguir mayúsculas.
+        ///
+        /// # Errores
+        /// - `SkuInvalido` si el código está vacío o supera `MAX_LARGO_SKU`.
+        /// - `ProductoInexistente` si el producto no existe.
+        /// - `ProductoAjeno` si el llamador no es el vendedor que registró el producto.
+        /// - `SkuYaExistente` si otro producto ya usa ese código.
         #[ink(message)]
-        pub fn listar_ordenes(&self) -> Vec<Orden> {
-            self._listar_ordenes()
+        pub fn asignar_sku(&mut self, id_producto: u32, sku: String) -> Result<(), ErroresContrato> {
+            self._asignar_sku(id_producto, self.env().caller(), sku)
         }
 
-        /// Devuelve una lista de todas las categorias registradas en el contrato.
+        /// Devuelve el producto del catálogo compartido identificado por un SKU/GTIN.
         #[ink(message)]
-        pub fn listar_categorias(&self) -> Vec<Categoria> {
-            self._listar_categorias()
+        pub fn buscar_producto_por_sku(&self, sku: String) -> Result<Producto, ErroresContrato> {
+            self.get_producto_by_sku(&sku)
         }
 
-        fn _usuario_con_rol(&self, rol: Rol) -> Result<(), ErroresContrato> {
-            let caller = self.env().caller();
-            let usuario = self
-                .m_usuarios
-                .get(caller)
-                .ok_or(ErroresContrato::CuentaNoRegistrada)?;
-            if usuario.has_role(rol) {
-                return Ok(());
-            }
-            Err(ErroresContrato::RolNoApropiado)
+        /// Devuelve una página de las ofertas activas de un producto, ordenadas por precio
+        /// unitario y, a igual precio, por reputación del vendedor.
+        ///
+        /// Cada vendedor tiene a lo sumo una publicación sin cerrar por producto, así que se
+        /// ordena una oferta por vendedor.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ofertas(
+            &self,
+            id_producto: u32,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            paginar(self._listar_ofertas(id_producto), desde, tamano_pagina)
         }
-    }
 
-    impl GestionProducto for Sistema {
-        fn _crear_producto(
-            &mut self,
+        /// Devuelve la mejor oferta de un producto (la primera de `listar_ofertas`) que tenga
+        /// stock para la cantidad pedida. Se omiten las publicaciones del caller, que no puede
+        /// comprarse a sí mismo.
+        ///
+        /// # Errores
+        /// - `SinOfertas` si ninguna publicación activa de otro vendedor tiene stock suficiente.
+        #[ink(message)]
+        pub fn mejor_oferta(&self, id_producto: u32, cantidad: u32) -> Result<Publicacion, ErroresContrato> {
+            self._mejor_oferta(id_producto, self.env().caller(), cantidad)
+        }
+
+        /// Devuelve una página de los productos registrados por un vendedor.
+        ///
+        /// La página recorre `tamano_pagina` productos del vendedor y omite los retirados, por
+        /// lo que puede traer menos elementos; `total` cuenta todos los que registró.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_productos_vendedor(
+            &self,
             id_vendedor: AccountId,
-            nombre: String,
-            descripcion: String,
-            categoria: String,
-            stock: u32,
-        ) -> Result<u32, ErroresContrato> {
-            // 1. valido que el nombre y descripcion no sean vacias
-            if nombre.trim().is_empty() || descripcion.trim().is_empty() {
-                return Err(ErroresContrato::DatosInvalidos);
-            }
-            // 2. valido que el stock no sea 0
-            if stock == 0 {
-                return Err(ErroresContrato::StockInvalido);
-            }
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Producto>, ErroresContrato> {
+            self._listar_productos_vendedor(id_vendedor, desde, tamano_pagina)
+        }
 
-            let id = self.productos.len();
-            let usuario = self.get_user(&id_vendedor)?;
-            if usuario.has_role(VENDEDOR) {
-                let id_cat = self.get_categoria_by_name(&categoria)?;
-                let producto = Producto::new(id, id_vendedor, nombre, descripcion, id_cat, stock);
-                if !self.producto_existe(&producto) {
-                    self.productos.push(&producto);
-                    Ok(id)
-                } else {
-                    return Err(ErroresContrato::ProductoYaExistente);
-                }
-            } else {
-                return Err(ErroresContrato::UsuarioNoEsVendedor);
-            }
+        /// Devuelve una página de las publicaciones en el contrato.
+        ///
+        /// # Parámetros
+        /// - `solo_activas`: si es `true`, omite las publicaciones agotadas, pausadas o cerradas.
+        ///   La página recorre igualmente `tamano_pagina` publicaciones almacenadas, por lo que
+        ///   puede traer menos elementos; `total` cuenta todas las publicaciones.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_publicaciones(
+            &self,
+            solo_activas: bool,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            self._listar_publicaciones(solo_activas, desde, tamano_pagina)
         }
 
-        fn producto_existe(&self, p: &Producto) -> bool {
-            for i in 0..self.productos.len() {
-                if let Some(prod) = self.productos.get(i) {
-                    if prod.eq(p) {
-                        return true;
-                    }
-                }
-            }
-            false
+        /// Devuelve las publicaciones con los ids indicados, en el mismo orden.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si se piden más de `MAX_TAMANO_PAGINA` ids.
+        /// - `PublicacionNoExiste` si alguna de las publicaciones no existe.
+        #[ink(message)]
+        pub fn get_publicaciones(&self, ids: Vec<u32>) -> Result<Vec<Publicacion>, ErroresContrato> {
+            obtener_por_ids(ids, ErroresContrato::PublicacionNoExiste, |id| self.publicaciones.get(id))
         }
 
-        fn _listar_productos(&self) -> Vec<Producto> {
-            let mut resultado = Vec::new();
-            for i in 0..self.productos.len() {
-                if let Some(producto) = self.productos.get(i) {
-                    resultado.push(producto);
-                }
-            }
-            resultado
+        /// Busca publicaciones que cumplan con un filtro y devuelve una página de resultados.
+        ///
+        /// Las publicaciones se revisan en orden de publicación (con `MasRecientes`, desde la
+        /// última) hasta juntar `tamano_pagina` que cumplan el filtro o revisar
+        /// `MAX_REVISADAS_POR_BUSQUEDA`. `siguiente` es la posición donde se dejó de revisar, así
+        /// que recorrer las páginas devuelve cada publicación que cumple el filtro una sola vez.
+        /// Con `Precio` y `ReputacionVendedor` el orden es el de los resultados de cada página,
+        /// no entre páginas.
+        ///
+        /// # Parámetros
+        /// - `filtro`: Criterios de búsqueda (categoría, vendedor, rango de precio, stock mínimo,
+        ///   solo activas y reputación mínima del vendedor).
+        /// - `orden`: Criterio de orden de los resultados dentro de la página.
+        /// - `desde`: Posición de la primera publicación a revisar; `0` para la primera página y
+        ///   luego el `siguiente` de la página anterior.
+        /// - `tamano_pagina`: Cantidad máxima de resultados, hasta `MAX_TAMANO_PAGINA`. La página
+        ///   puede traer menos si se alcanza el límite de revisadas; `total` cuenta todas las
+        ///   publicaciones.
+        ///
+        /// # Errores
+        /// - `CategoriaInexistente` si se filtra por una categoría que no existe.
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn buscar_publicaciones(
+            &self,
+            filtro: FiltroPublicaciones,
+            orden: OrdenBusqueda,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            self._buscar_publicaciones(&filtro, orden, desde, tamano_pagina)
         }
-    }
 
-    impl GestionUsuario for Sistema {
-        fn _registrar_usuario(
-            &mut self,
-            id: AccountId,
-            nombre: String,
-            mail: String,
-            rol: Rol,
-        ) -> Result<String, ErroresContrato> {
-            //Verifico que el usuario, y mail no esten vacios
-            if nombre.is_empty() {
-                return Err(ErroresContrato::NombreUsuarioVacio);
-            }
-            if mail.is_empty() {
-                return Err(ErroresContrato::MailUsuarioVacio);
-            }
-
-            // Verifico que el email no exista
-            if self.get_usuario_by_mail(&mail).is_ok() {
-                return Err(ErroresContrato::MailYaExistente);
-            };
+        /// Devuelve una página de las publicaciones del usuario loggeado
+        ///
+        /// # Requisitos
+        /// - El caller debe estar registrado y tener rol de `Vendedor`.
+        ///
+        /// # Errores
+        /// - `CuentaNoRegistrada` si el caller no está registrado.
+        /// - `ProductoInexistente` si el producto no existe.
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_publicaciones_propias(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            let id = self.env().caller();
+            self._usuario_con_rol(VENDEDOR)?;
+            self._listar_publicaciones_propias(id, desde, tamano_pagina)
+        }
 
-            // Verifico que el usuario no exista
-            if self.get_usuario_by_username(&nombre).is_ok() {
-                return Err(ErroresContrato::UsuarioYaExistente);
-            };
+        /// Devuelve una página de las ordenes de compra registradas en el contrato.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ordenes(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Orden>, ErroresContrato> {
+            self._listar_ordenes(desde, tamano_pagina)
+        }
 
-            // Verifico que el AccountId no esté registrado
-            if self.get_user(&id).is_ok() {
-                return Err(ErroresContrato::UsuarioYaExistente);
+        /// Devuelve una página de las compras del caller, en orden de creación.
+        ///
+        /// # Parámetros
+        /// - `estado`: Si se indica, solo se devuelven las órdenes en ese estado.
+        ///   La página recorre igualmente `tamano_pagina` órdenes del caller, por lo que
+        ///   puede traer menos elementos; `total` cuenta todas sus compras.
+        /// - `desde`, `tamano_pagina`: Paginado, igual que en `listar_usuarios`.
+        ///
+        /// # Errores
+        /// - `CuentaNoRegistrada` si el caller no está registrado.
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_mis_compras(
+            &self,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato> {
+            let caller = self.env().caller();
+            if !self.m_usuarios.contains(caller) {
+                return Err(ErroresContrato::CuentaNoRegistrada);
             }
+            self._listar_compras(caller, estado, desde, tamano_pagina)
+        }
 
-            // Instancio nuevo usuario
-            let usuario = Usuario::new(id, nombre, mail);
+        /// Devuelve una página de las ventas del caller, en orden de creación.
+        ///
+        /// # Parámetros
+        /// - `estado`: Si se indica, solo se devuelven las órdenes en ese estado.
+        ///   La página recorre igualmente `tamano_pagina` órdenes del caller, por lo que
+        ///   puede traer menos elementos; `total` cuenta todas sus ventas.
+        /// - `desde`, `tamano_pagina`: Paginado, igual que en `listar_usuarios`.
+        ///
+        /// # Errores
+        /// - `CuentaNoRegistrada` si el caller no está registrado.
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_mis_ventas(
+            &self,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato> {
+            let caller = self.env().caller();
+            if !self.m_usuarios.contains(caller) {
+                return Err(ErroresContrato::CuentaNoRegistrada);
+            }
+            self._listar_ventas(caller, estado, desde, tamano_pagina)
+        }
 
-            // Inserto el usuario tanto en el Mapping como en el Vec
-            self.m_usuarios.insert(id, &usuario);
-            self.v_usuarios.push(&id);
-            self._asignar_rol(id, rol)?;
+        /// Devuelve cuántas órdenes tiene un usuario como comprador y como vendedor.
+        ///
+        /// # Parámetros
+        /// - `id`: AccountId del usuario a consultar.
+        ///
+        /// # Errores
+        /// - `UsuarioNoExiste` si el usuario no está registrado.
+        #[ink(message)]
+        pub fn get_cantidad_ordenes(&self, id: AccountId) -> Result<CantidadOrdenes, ErroresContrato> {
+            if !self.m_usuarios.contains(id) {
+                return Err(ErroresContrato::UsuarioNoExiste);
+            }
+            Ok(self._get_cantidad_ordenes(id))
+        }
 
-            Ok(String::from("El usuario fue registrado correctamente"))
+        /// Devuelve una página de los productos vendidos, de más a menos unidades vendidas, con
+        /// sus ventas acumuladas. Una venta se acumula cuando la orden pasa a `Recibida`.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_productos_mas_vendidos(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<(u32, VentasAcumuladas)>, ErroresContrato> {
+            paginar_indices(self.ranking_productos.len(), desde, tamano_pagina, |i| {
+                let id_producto = self.ranking_productos.get(i)?;
+                Some((id_producto, self.ventas_por_producto.get(id_producto)?))
+            })
         }
 
-        ///Devuelve el usuario segun el AccountId provisto
-        fn get_user(&mut self, id: &AccountId) -> Result<Usuario, ErroresContrato> {
-            self.m_usuarios
-                .get(id)
-                .ok_or(ErroresContrato::UsuarioNoExiste)
+        /// Devuelve una página de las categorias, en orden de registro, con sus ventas acumuladas.
+        ///
+        /// La página recorre `tamano_pagina` categorias y omite las que no tienen ventas, por lo
+        /// que puede traer menos elementos; `total` cuenta todas las categorias.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_ventas_por_categoria(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<(u32, VentasAcumuladas)>, ErroresContrato> {
+            paginar_indices(self.categorias.len(), desde, tamano_pagina, |id_categoria| {
+                Some((id_categoria, self.ventas_por_categoria.get(id_categoria)?))
+            })
         }
 
-        fn _get_cantidad_usuarios(&self) -> u32 {
-            self.v_usuarios.len()
+        /// Devuelve una página de los usuarios calificados como vendedor, de mejor a peor
+        /// promedio y, a igual promedio, de más a menos calificaciones.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_mejores_vendedores(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Usuario>, ErroresContrato> {
+            paginar_indices(self.ranking_vendedores.len(), desde, tamano_pagina, |i| {
+                self.m_usuarios.get(self.ranking_vendedores.get(i)?)
+            })
         }
 
-        fn _listar_usuarios(&self, pagina: u32, tamano_pagina: u32) -> Vec<Usuario> {
-            let total_usuarios = self.v_usuarios.len();
+        /// Devuelve una página de los usuarios calificados como comprador, de mejor a peor
+        /// promedio y, a igual promedio, de más a menos calificaciones.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_mejores_compradores(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Usuario>, ErroresContrato> {
+            paginar_indices(self.ranking_compradores.len(), desde, tamano_pagina, |i| {
+                self.m_usuarios.get(self.ranking_compradores.get(i)?)
+            })
+        }
 
-            // Si no hay usuarios registrados, devolvemos un vector vacío directamente
-            if total_usuarios == 0 {
-                return Vec::new();
-            }
+        /// Devuelve los cambios de estado de una orden, del más antiguo al más reciente.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: Id de la orden a consultar.
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si no existe la orden.
+        #[ink(message)]
+        pub fn get_historial_orden(&self, id_orden: u32) -> Result<Vec<TransicionOrden>, ErroresContrato> {
+            self._get_historial_orden(id_orden)
+        }
 
-            let inicio;
-            let fin;
+        /// Devuelve una página de las categorias registradas en el contrato.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si el tamaño de página es 0 o supera `MAX_TAMANO_PAGINA`.
+        #[ink(message)]
+        pub fn listar_categorias(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Categoria>, ErroresContrato> {
+            self._listar_categorias(desde, tamano_pagina)
+        }
 
-            // Lógica para definir los límites según la página
-            if pagina == 0 {
-                // Página 0: traemos absolutamente todos los usuarios
-                inicio = 0;
-                fin = total_usuarios;
-            } else {
-                // Pagina > 0: paginacion normal. Asumimos que la página 1 es la primera, 
-                // restamos 1 para que el índice sea 0.
-                let indice_pagina = pagina.saturating_sub(1);
-                
-                inicio = match indice_pagina.checked_mul(tamano_pagina) {
-                    Some(val) => val,
-                    None => return Vec::new(), // Si hay overflow, devolvemos vacío
-                };
+        /// Devuelve las categorias con los ids indicados, en el mismo orden.
+        ///
+        /// # Errores
+        /// - `TamanoPaginaInvalido` si se piden más de `MAX_TAMANO_PAGINA` ids.
+        /// - `CategoriaInexistente` si alguna de las categorias no existe.
+        #[ink(message)]
+        pub fn get_categorias(&self, ids: Vec<u32>) -> Result<Vec<Categoria>, ErroresContrato> {
+            obtener_por_ids(ids, ErroresContrato::CategoriaInexistente, |id| self.categorias.get(id))
+        }
 
-                // Si el inicio supera la cantidad de usuarios, la página no existe
-                if inicio >= total_usuarios {
-                    return Vec::new();
-                }
+        /// Devuelve la cuenta owner del contrato.
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
 
-                // Calculamos el final respetando el tope de usuarios totales
-                fin = core::cmp::min(inicio.saturating_add(tamano_pagina), total_usuarios);
-            }
+        /// Devuelve la cuenta propuesta como próximo owner, si hay una transferencia pendiente.
+        #[ink(message)]
+        pub fn get_owner_propuesto(&self) -> Option<AccountId> {
+            self.owner_propuesto
+        }
 
-            let mut resultado = Vec::new();
-            for i in inicio..fin {
-                if let Some(account_id) = self.v_usuarios.get(i) {
-                    if let Some(usuario) = self.m_usuarios.get(account_id) {
-                        resultado.push(usuario);
-                    }
-                }
-            }
-            resultado
+        /// Indica si la cuenta tiene permisos de administrador. El owner siempre los tiene.
+        #[ink(message)]
+        pub fn es_admin(&self, id: AccountId) -> bool {
+            self._es_admin(id)
         }
 
-        /// Verifica si ya existe un usuario con el mail dado
-        fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato> {
-            for i in 0..self.v_usuarios.len() {
-                let account_id = self
-                    .v_usuarios
-                    .get(i)
-                    .ok_or(ErroresContrato::IndiceInvalido)?;
-
-                let usuario = self
-                    .m_usuarios
-                    .get(account_id)
-                    .ok_or(ErroresContrato::AccountIdInvalida)?;
-                if usuario.mail == mail {
-                    return Ok(usuario);
-                };
-            }
-            Err(ErroresContrato::MailInexistente)
+        /// Otorga el permiso de administrador a una cuenta.
+        ///
+        /// # Parámetros
+        /// - `id`: Cuenta a la que se le otorga el permiso.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsOwner` si el caller no es el owner.
+        /// - `YaEsAdmin` si la cuenta ya es administradora.
+        #[ink(message)]
+        pub fn agregar_admin(&mut self, id: AccountId) -> Result<(), ErroresContrato> {
+            self._agregar_admin(self.env().caller(), id)
         }
 
-        /// Verifica si ya existe un usuario con un nombre de usuario dado
-        fn get_usuario_by_username(&self, name: &str) -> Result<Usuario, ErroresContrato> {
-            for i in 0..self.v_usuarios.len() {
-                let account_id = self
-                    .v_usuarios
-                    .get(i)
-                    .ok_or(ErroresContrato::IndiceInvalido)?;
-
-                let usuario = self
-                    .m_usuarios
-                    .get(account_id)
-                    .ok_or(ErroresContrato::AccountIdInvalida)?;
-                if usuario.nombre == name {
-                    return Ok(usuario);
-                };
-            }
-            Err(ErroresContrato::UsuarioNoExiste)
+        /// Revoca el permiso de administrador de una cuenta.
+        ///
+        /// # Parámetros
+        /// - `id`: Cuenta a la que se le revoca el permiso.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsOwner` si el caller no es el owner.
+        /// - `NoEsAdmin` si la cuenta no era administradora.
+        #[ink(message)]
+        pub fn quitar_admin(&mut self, id: AccountId) -> Result<(), ErroresContrato> {
+            self._quitar_admin(self.env().caller(), id)
         }
 
-        fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<String, ErroresContrato> {
-            let mut usuario = self.get_user(&id)?;
-            if rol == Rol::Ambos {
-                let rol1: Result<String, ErroresContrato> = self._asignar_rol(id, Rol::Vendedor);
-                let rol2: Result<String, ErroresContrato> = self._asignar_rol(id, Rol::Comprador);
-                if rol1.is_ok() || rol2.is_ok() {
-                    return Ok(String::from("roles agregados correctamente"));
-                } else {
-                    return Err(ErroresContrato::AlreadyHasRol);
-                }
-            } else if usuario.has_role(rol.clone()) {
-                return Err(ErroresContrato::AlreadyHasRol);
-            }
-            usuario.roles.push(rol);
-            self.m_usuarios.insert(id, &usuario);
-            Ok(String::from("rol agregado correctamente"))
+        /// Primer paso de la transferencia del contrato: propone a una cuenta como nuevo owner.
+        ///
+        /// La transferencia no se concreta hasta que la cuenta propuesta la acepte con
+        /// `aceptar_owner`. Volver a proponer reemplaza la propuesta anterior.
+        ///
+        /// # Parámetros
+        /// - `nuevo`: Cuenta propuesta como nuevo owner.
+        ///
+        /// # Errores
+        /// - `NoEsOwner` si el caller no es el owner.
+        #[ink(message)]
+        pub fn proponer_owner(&mut self, nuevo: AccountId) -> Result<(), ErroresContrato> {
+            self._proponer_owner(self.env().caller(), nuevo)
         }
-    }
 
-    impl GestionOrden for Sistema {
-        fn _crear_orden(
-            &mut self,
-            id_pub: u32,
-            id_comprador: AccountId,
-            cantidad: u32,
-        ) -> Result<u32, ErroresContrato> {
-            let id_orden = self.ordenes.len();
-            let comprador = self.get_user(&id_comprador)?;
-            let id_vendedor = self.get_id_vendedor(id_pub)?;
-            let vendedor = self.get_user(&id_vendedor)?;
-            let precio_producto = self.get_precio_unitario(id_pub)?;
-            let precio_total = precio_producto
-                .checked_mul(cantidad as u128)
-                .ok_or(ErroresContrato::ErrorMultiplicacion)?;
-            if comprador.has_role(COMPRADOR) && vendedor.has_role(VENDEDOR) {
-                if cantidad != 0 {
-                    //Obtengo publicacion original y descuento la cantidad necesaria del stock
-                    let mut publicacion = self
-                        .publicaciones
-                        .get(id_pub)
-                        .ok_or(ErroresContrato::PublicacionNoExiste)?;
-                    publicacion.descontar_stock(cantidad)?;
-                    self.publicaciones.set(id_pub, &publicacion);
+        /// Segundo paso de la transferencia del contrato: la cuenta propuesta acepta ser el owner.
+        ///
+        /// # Errores
+        /// - `SinTransferenciaPendiente` si no hay ninguna cuenta propuesta.
+        /// - `NoEsOwnerPropuesto` si el caller no es la cuenta propuesta.
+        #[ink(message)]
+        pub fn aceptar_owner(&mut self) -> Result<(), ErroresContrato> {
+            self._aceptar_owner(self.env().caller())
+        }
 
-                    let orden = Orden::new(
-                        id_orden,
-                        id_pub,
-                        id_vendedor,
-                        id_comprador,
-                        cantidad,
-                        precio_total,
-                    );
-                    self.ordenes.push(&orden);
-                    Ok(id_orden)
-                } else {
-                    return Err(ErroresContrato::CantidadEnCarritoMenorAUno);
-                }
-            } else {
-                return Err(ErroresContrato::RolNoApropiado);
+        fn _usuario_con_rol(&self, rol: Rol) -> Result<(), ErroresContrato> {
+            let caller = self.env().caller();
+            let usuario = self
+                .m_usuarios
+                .get(caller)
+                .ok_or(ErroresContrato::CuentaNoRegistrada)?;
+            if usuario.has_role(rol) {
+                return Ok(());
             }
+            Err(ErroresContrato::RolNoApropiado)
         }
 
-        fn _listar_ordenes(&self) -> Vec<Orden> {
-            let mut resultado = Vec::new();
-            for i in 0..self.ordenes.len() {
-                if let Some(orden) = self.ordenes.get(i) {
-                    resultado.push(orden);
-                }
-            }
-            resultado
+        /// Registra una cuenta como árbitro de disputas.
+        ///
+        /// # Parámetros
+        /// - `id`: Cuenta a registrar como árbitro.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `YaEsArbitro` si la cuenta ya es árbitro.
+        #[ink(message)]
+        pub fn registrar_arbitro(&mut self, id: AccountId) -> Result<(), ErroresContrato> {
+            self._registrar_arbitro(self.env().caller(), id)
         }
 
-        fn _enviar_orden(
-            &mut self,
-            id_orden: u32,
-            id_vendedor: AccountId,
-        ) -> Result<(), ErroresContrato> {
-            let mut orden = self
-                .ordenes
-                .get(id_orden)
-                .ok_or(ErroresContrato::OrdenInexistente)?;
+        /// Quita a una cuenta del registro de árbitros.
+        ///
+        /// # Parámetros
+        /// - `id`: Cuenta a quitar.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `NoEsArbitro` si la cuenta no era árbitro.
+        #[ink(message)]
+        pub fn quitar_arbitro(&mut self, id: AccountId) -> Result<(), ErroresContrato> {
+            self._quitar_arbitro(self.env().caller(), id)
+        }
 
-            if id_vendedor != orden.id_vendedor {
-                return Err(ErroresContrato::NoEsVendedorOriginal);
-            }
+        /// Indica si la cuenta está registrada como árbitro.
+        #[ink(message)]
+        pub fn es_arbitro(&self, id: AccountId) -> bool {
+            self.arbitros.contains(id)
+        }
 
-            match orden.status {
-                EstadoOrden::Pendiente => {
-                    orden.status = EstadoOrden::Enviada;
-                    self.ordenes.set(id_orden, &orden);
-                    Ok(())
-                }
-                _ => {
-                    Err(ErroresContrato::OrdenNoPendiente)
-                },
-            }
+        /// Abre una disputa sobre una orden, que pasa al estado `EnDisputa`.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden en conflicto.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el comprador o el vendedor de la orden.
+        /// - La orden debe estar en estado `Enviada` o `PreCancelada`.
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `UsuarioNoCorresponde` si el caller no es parte de la orden.
+        /// - `OrdenNoDisputable` si la orden no está en un estado que admita disputa.
+        #[ink(message)]
+        pub fn abrir_disputa(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+            self._abrir_disputa(id_orden, self.env().caller())
         }
 
-        fn _recibir_orden(
-            &mut self,
-            id_orden: u32,
-            id_comprador: AccountId,
-        ) -> Result<(), ErroresContrato> {
-            let mut orden = self
-                .ordenes
-                .get(id_orden)
-                .ok_or(ErroresContrato::OrdenInexistente)?;
-            if id_comprador != orden.id_comprador {
-                return Err(ErroresContrato::NoEsCompradorOriginal);
-            }
+        /// Adjunta el hash de una evidencia a una disputa abierta.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden en disputa.
+        /// - `evidencia`: Hash del documento que respalda la posición del caller.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el comprador o el vendedor de la orden.
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `OrdenNoEnDisputa` si la orden no tiene una disputa abierta.
+        /// - `UsuarioNoCorresponde` si el caller no es parte de la orden.
+        /// - `MaxEvidenciasAlcanzado` si el caller ya aportó la cantidad máxima de evidencias.
+        #[ink(message)]
+        pub fn aportar_evidencia(&mut self, id_orden: u32, evidencia: Hash) -> Result<(), ErroresContrato> {
+            self._aportar_evidencia(id_orden, self.env().caller(), evidencia)
+        }
 
-            match orden.status {
-                EstadoOrden::Enviada => {
-                    orden.status = EstadoOrden::Recibida;
-                    self.ordenes.set(id_orden, &orden);
-                    Ok(())
-                }
-                _ => {
-                    Err(ErroresContrato::OrdenNoEnviada)
-                },
-            }
+        /// Resuelve una disputa abierta.
+        ///
+        /// Si el fallo favorece al comprador la orden se cancela, se devuelve el stock a la
+        /// publicación y se reembolsa el pago. Si favorece al vendedor la orden se da por
+        /// `Recibida` y se le libera el pago.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden en disputa.
+        /// - `fallo`: Parte a favor de la cual se resuelve.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser un árbitro registrado que no sea parte de la orden.
+        ///
+        /// # Errores
+        /// - `NoEsArbitro` si el caller no es árbitro.
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `ArbitroEsParte` si el árbitro es el comprador o el vendedor de la orden.
+        /// - `OrdenNoEnDisputa` si la orden no tiene una disputa abierta.
+        #[ink(message)]
+        pub fn resolver_disputa(&mut self, id_orden: u32, fallo: FalloDisputa) -> Result<(), ErroresContrato> {
+            self._resolver_disputa(id_orden, self.env().caller(), fallo)
         }
 
-        fn _cancelar_orden(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<String, ErroresContrato> {
-            let mut orden = self
-                .ordenes
-                .get(id_orden)
-                .ok_or(ErroresContrato::OrdenInexistente)?;
-            let usuario = self
-                .m_usuarios
-                .get(id_usuario)
-                .ok_or(ErroresContrato::CuentaNoRegistrada)?;
-            if id_usuario == orden.id_comprador && usuario.has_role(COMPRADOR){
-                match orden.status {
-                    EstadoOrden::Pendiente => {    
-                        orden.status = EstadoOrden::PreCancelada;
-                        self.ordenes.set(id_orden, &orden);
-                        Ok(String::from("La cancelación fue iniciada y se espera confirmación del vendedor"))
-                    }
-                    EstadoOrden::Cancelada => Err(ErroresContrato::OrdenYaCancelada),
-                    _ => Err(ErroresContrato::OrdenNoPendiente),
-                }
-            } else if id_usuario == orden.id_vendedor && usuario.has_role(VENDEDOR){
-                match orden.status {
-                    EstadoOrden::PreCancelada => {
-                        orden.status = EstadoOrden::Cancelada;
-                        let mut publi = self
-                            .publicaciones
-                            .get(orden.id_publicacion)
-                            .ok_or(ErroresContrato::PublicacionNoExiste)?;
-                        let mut cantidad = publi.get_cantidad();
-                        cantidad = cantidad.checked_add(orden.cantidad).ok_or(ErroresContrato::ErrorSuma)?;
-                        publi.set_cantidad(cantidad);
-                        self.publicaciones.set(orden.id_publicacion, &publi);
-                        self.ordenes.set(id_orden, &orden);
-                        Ok(String::from("La cancelación de la orden fue confirmada"))
-                    }
-                    EstadoOrden::Cancelada => Err(ErroresContrato::OrdenYaCancelada),
-                    _ => Err(ErroresContrato::CancelacionDeOrdenSinConsenso),
-                }
-            } else {
-                Err(ErroresContrato::UsuarioNoCorresponde)
+        /// Resuelve una orden cuyo plazo venció. Puede llamarlo cualquier cuenta.
+        ///
+        /// - Una orden `Pendiente` que no se envió dentro del plazo de envío se cancela,
+        ///   devolviendo el stock a la publicación y el pago al comprador.
+        /// - Una orden `Enviada` que el comprador no confirmó dentro del plazo de confirmación
+        ///   se da por `Recibida` y se libera el pago al vendedor.
+        ///
+        /// # Parámetros
+        /// - `id_orden`: ID de la orden vencida.
+        ///
+        /// Retorna el nuevo estado de la orden.
+        ///
+        /// # Errores
+        /// - `OrdenInexistente` si la orden no existe.
+        /// - `OrdenSinPlazo` si la orden no está `Pendiente` ni `Enviada`.
+        /// - `PlazoNoVencido` si todavía no pasó el plazo correspondiente.
+        #[ink(message)]
+        pub fn procesar_vencimiento(&mut self, id_orden: u32) -> Result<EstadoOrden, ErroresContrato> {
+            self._procesar_vencimiento(id_orden, self.env().caller())
+        }
+
+        /// Configura los plazos, en milisegundos, que se usan para vencer órdenes.
+        ///
+        /// # Parámetros
+        /// - `plazo_envio`: Tiempo que tiene el vendedor para enviar una orden `Pendiente`.
+        /// - `plazo_confirmacion`: Tiempo que tiene el comprador para confirmar una orden `Enviada`.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `PlazoInvalido` si alguno de los plazos es 0.
+        #[ink(message)]
+        pub fn configurar_plazos(
+            &mut self,
+            plazo_envio: Timestamp,
+            plazo_confirmacion: Timestamp,
+        ) -> Result<(), ErroresContrato> {
+            self._solo_admin(self.env().caller())?;
+            if plazo_envio == 0 || plazo_confirmacion == 0 {
+                return Err(ErroresContrato::PlazoInvalido);
             }
+            self.plazo_envio = plazo_envio;
+            self.plazo_confirmacion = plazo_confirmacion;
+            Ok(())
         }
 
-        fn _calificar_orden(
+        /// Devuelve los plazos de envío y de confirmación vigentes, en milisegundos.
+        #[ink(message)]
+        pub fn get_plazos(&self) -> (Timestamp, Timestamp) {
+            (self.plazo_envio, self.plazo_confirmacion)
+        }
+
+        /// Configura cuántas calificaciones puede dejar un usuario a una misma contraparte
+        /// dentro de una ventana de tiempo, para frenar reputación inflada entre cuentas vinculadas.
+        ///
+        /// # Parámetros
+        /// - `maximo`: Cantidad máxima de calificaciones del mismo calificador al mismo calificado.
+        /// - `ventana`: Duración de la ventana en milisegundos.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `LimiteCalificacionesInvalido` si `maximo` o `ventana` son 0.
+        #[ink(message)]
+        pub fn configurar_limite_calificaciones(
             &mut self,
-            id_orden: u32,
-            id: AccountId,
-            puntaje: u8,
+            maximo: u32,
+            ventana: Timestamp,
         ) -> Result<(), ErroresContrato> {
-            if puntaje < 1 || puntaje > 5 {
-                return Err(ErroresContrato::PuntajeInvalido);
+            self._solo_admin(self.env().caller())?;
+            if maximo == 0 || ventana == 0 {
+                return Err(ErroresContrato::LimiteCalificacionesInvalido);
+            }
+            self.max_calificaciones_por_par = maximo;
+            self.ventana_calificaciones = ventana;
+            Ok(())
+        }
+
+        /// Devuelve el máximo de calificaciones por par de usuarios y la ventana vigente, en milisegundos.
+        #[ink(message)]
+        pub fn get_limite_calificaciones(&self) -> (u32, Timestamp) {
+            (self.max_calificaciones_por_par, self.ventana_calificaciones)
+        }
+
+        /// Configura el precio total mínimo que debe tener una orden para que sus calificaciones
+        /// sumen a la reputación. Las calificaciones de órdenes más baratas se guardan en la orden
+        /// pero no cuentan en el `Rating`.
+        ///
+        /// # Parámetros
+        /// - `minimo`: Precio total mínimo de la orden. Con 0 cuentan todas las calificaciones.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        #[ink(message)]
+        pub fn configurar_valor_minimo_calificacion(&mut self, minimo: Balance) -> Result<(), ErroresContrato> {
+            self._solo_admin(self.env().caller())?;
+            self.valor_minimo_calificacion = minimo;
+            Ok(())
+        }
+
+        /// Devuelve el precio total mínimo que debe tener una orden para que sus calificaciones cuenten.
+        #[ink(message)]
+        pub fn get_valor_minimo_calificacion(&self) -> Balance {
+            self.valor_minimo_calificacion
+        }
+
+        /// Configura la vida media, en bloques, del promedio reciente de la reputación: una
+        /// calificación pesa la mitad en ese promedio cada vez que pasa esa cantidad de bloques.
+        ///
+        /// # Parámetros
+        /// - `bloques`: Vida media en bloques.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `VidaMediaInvalida` si `bloques` es 0.
+        #[ink(message)]
+        pub fn configurar_vida_media_reputacion(&mut self, bloques: BlockNumber) -> Result<(), ErroresContrato> {
+            self._solo_admin(self.env().caller())?;
+            if bloques == 0 {
+                return Err(ErroresContrato::VidaMediaInvalida);
             }
+            self.vida_media_reputacion = bloques;
+            Ok(())
+        }
+
+        /// Devuelve la vida media, en bloques, del promedio reciente de la reputación.
+        #[ink(message)]
+        pub fn get_vida_media_reputacion(&self) -> BlockNumber {
+            self.vida_media_reputacion
+        }
+
+        /// Guarda en la orden las calificaciones ocultas, las suma al `Rating` de cada parte
+        /// y emite un `CalificacionRegistrada` por cada una
+        fn _aplicar_calificaciones(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+            let mut ocultas = self
+                .calificaciones_ocultas
+                .get(id_orden)
+                .ok_or(ErroresContrato::SinCalificacionesPendientes)?;
             let mut orden = self
                 .ordenes
                 .get(id_orden)
                 .ok_or(ErroresContrato::OrdenInexistente)?;
-            if orden.status != EstadoOrden::Recibida {
-                return Err(ErroresContrato::OrdenNoRecibida);
-            }
+            let bloque = self.env().block_number();
 
-            match id {
-                id if id == orden.id_comprador => {
-                    // El Comprador califica al Vendedor
-                    if orden.cal_vendedor.is_some() {
-                        return Err(ErroresContrato::YaCalificado);
-                    }
-                    orden.cal_vendedor = Some(puntaje);
+            if let Some(puntaje) = ocultas.cal_vendedor {
+                if ocultas.cuenta {
                     let mut vendedor = self.get_user(&orden.id_vendedor)?;
-                    vendedor.rating.agregar_calificacion_vendedor(puntaje);
+                    vendedor
+                        .rating
+                        .agregar_calificacion_vendedor(puntaje, orden.precio_total);
+                    vendedor
+                        .rating
+                        .reciente_vendedor
+                        .agregar(puntaje, bloque, self.vida_media_reputacion);
                     // guardar los datos para tener consistencia en blockchain
                     self.m_usuarios.insert(orden.id_vendedor, &vendedor);
+                    self._reubicar_usuario(orden.id_vendedor, VENDEDOR);
                 }
-
-                id if id == orden.id_vendedor => {
-                    if orden.cal_comprador.is_some() {
-                        return Err(ErroresContrato::YaCalificado);
-                    }
-                    orden.cal_comprador = Some(puntaje);
+                self._acumular_calificacion(&orden, puntaje);
+                self.env().emit_event(CalificacionRegistrada {
+                    id_orden,
+                    calificador: orden.id_comprador,
+                    calificado: orden.id_vendedor,
+                    puntaje,
+                });
+            }
+            if let Some(puntaje) = ocultas.cal_comprador {
+                if ocultas.cuenta {
                     let mut comprador = self.get_user(&orden.id_comprador)?;
-                    comprador.rating.agregar_calificacion_comprador(puntaje);
-
-                    // Guardar los cambios en la blockchain
+                    comprador
+                        .rating
+                        .agregar_calificacion_comprador(puntaje, orden.precio_total);
+                    comprador
+                        .rating
+                        .reciente_comprador
+                        .agregar(puntaje, bloque, self.vida_media_reputacion);
                     self.m_usuarios.insert(orden.id_comprador, &comprador);
+                    self._reubicar_usuario(orden.id_comprador, COMPRADOR);
                 }
-
-                _ => {
-                    return Err(ErroresContrato::UsuarioNoCorresponde)
-                }
+                self.env().emit_event(CalificacionRegistrada {
+                    id_orden,
+                    calificador: orden.id_vendedor,
+                    calificado: orden.id_comprador,
+                    puntaje,
+                });
             }
+
+            orden.cal_vendedor = ocultas.cal_vendedor;
+            orden.cal_comprador = ocultas.cal_comprador;
             self.ordenes.set(id_orden, &orden);
+            ocultas.revelada = true;
+            self.calificaciones_ocultas.insert(id_orden, &ocultas);
             Ok(())
         }
-    }
 
-    impl GestionPublicacion for Sistema {
-        fn _crear_publicacion(
-            &mut self,
-            id_producto: u32,
-            id_usuario: AccountId,
-            stock: u32,
-            precio: Balance,
-        ) -> Result<u32, ErroresContrato> {
-            // 1. valido que el stock no sea 0 unidades
-            if stock == 0 {
-                return Err(ErroresContrato::StockInvalido);
-            }
-            // 2. valido que el precio no sea $0
-            if precio == 0 {
-                return Err(ErroresContrato::PrecioInvalido);
+        /// Indica si las calificaciones de una orden ya son públicas
+        fn _calificaciones_reveladas(&self, id_orden: u32) -> bool {
+            self.calificaciones_ocultas
+                .get(id_orden)
+                .is_some_and(|ocultas| ocultas.revelada)
+        }
+
+        /// Configura el plazo, en milisegundos desde la primera calificación de una orden,
+        /// después del cual se pueden revelar sus calificaciones aunque falte la de la otra parte.
+        ///
+        /// # Parámetros
+        /// - `plazo`: Plazo de revelación en milisegundos.
+        ///
+        /// # Requisitos
+        /// - El caller debe ser el owner o un administrador del contrato.
+        ///
+        /// # Errores
+        /// - `NoEsAdmin` si el caller no es owner ni administrador.
+        /// - `PlazoInvalido` si el plazo es 0.
+        #[ink(message)]
+        pub fn configurar_plazo_revelacion(&mut self, plazo: Timestamp) -> Result<(), ErroresContrato> {
+            self._solo_admin(self.env().caller())?;
+            if plazo == 0 {
+                return Err(ErroresContrato::PlazoInvalido);
             }
+            self.plazo_revelacion = plazo;
+            Ok(())
+        }
 
-            let id = self.publicaciones.len();
-            let usuario = self.get_user(&id_usuario)?;
-            if usuario.has_role(VENDEDOR) {
-                // Obtengo el producto original y decuento la cantidad de su stock actual
-                let mut producto = self
-                    .productos
-                    .get(id_producto)
-                    .ok_or(ErroresContrato::ProductoInexistente)?;
-                producto.descontar_stock(stock)?;
-                self.productos.set(id_producto, &producto);
+        /// Devuelve el plazo de revelación de calificaciones vigente, en milisegundos.
+        #[ink(message)]
+        pub fn get_plazo_revelacion(&self) -> Timestamp {
+            self.plazo_revelacion
+        }
 
-                let p = Publicacion::new(id, id_producto, id_usuario, stock, precio); // precio o precio unitario?
-                self.publicaciones.push(&p);
-                Ok(id)
-            } else {
-                Err(ErroresContrato::RolNoApropiado)
+        /// Cuenta una calificación de `calificador` a `calificado` si no superan el máximo
+        /// dentro de la ventana. Las calificaciones que quedaron fuera de la ventana se descartan
+        fn _registrar_calificacion_par(
+            &mut self,
+            calificador: AccountId,
+            calificado: AccountId,
+        ) -> Result<(), ErroresContrato> {
+            let ahora = self.env().block_timestamp();
+            let mut recientes = self
+                .calificaciones_por_par
+                .get((calificador, calificado))
+                .unwrap_or_default();
+            recientes.retain(|momento| ahora.saturating_sub(*momento) < self.ventana_calificaciones);
+            if recientes.len() as u32 >= self.max_calificaciones_por_par {
+                return Err(ErroresContrato::LimiteCalificacionesAlcanzado);
             }
+            recientes.push(ahora);
+            self.calificaciones_por_par.insert((calificador, calificado), &recientes);
+            Ok(())
         }
 
-        fn _listar_publicaciones(&self) -> Vec<Publicacion> {
-            let mut resultado = Vec::new();
-            for i in 0..self.publicaciones.len() {
-                if let Some(publi) = self.publicaciones.get(i) {
-                    resultado.push(publi);
-                }
+        /// Verifica que la publicación tenga al menos `cantidad` unidades disponibles
+        fn _chequear_stock_publicacion(&self, id_pub: u32, cantidad: u32) -> Result<(), ErroresContrato> {
+            self.publicaciones
+                .get(id_pub)
+                .ok_or(ErroresContrato::PublicacionNoExiste)?
+                .chequear_stock_disponible(cantidad)
+        }
+
+        /// Verifica que la cuenta sea el owner o un administrador del contrato
+        fn _solo_admin(&self, id: AccountId) -> Result<(), ErroresContrato> {
+            if !self._es_admin(id) {
+                return Err(ErroresContrato::NoEsAdmin);
             }
-            resultado
+            Ok(())
+        }
+
+        /// Transfiere fondos retenidos por el contrato a la cuenta indicada
+        fn _transferir(&self, destino: AccountId, monto: Balance) -> Result<(), ErroresContrato> {
+            self.env()
+                .transfer(destino, monto)
+                .map_err(|_| ErroresContrato::ErrorTransferencia)
+        }
+
+        /// Agrega la orden a los índices de compras y ventas y suma uno a los contadores de cada parte.
+        /// Los contadores son también el largo de cada índice, así que la orden ocupa esa posición
+        fn _indexar_orden(&mut self, id_orden: u32, id_comprador: AccountId, id_vendedor: AccountId) {
+            let mut cantidad = self.cantidad_ordenes.get(id_comprador).unwrap_or_default();
+            self.ordenes_por_comprador
+                .insert((id_comprador, cantidad.como_comprador), &id_orden);
+            cantidad.como_comprador = cantidad.como_comprador.saturating_add(1);
+            self.cantidad_ordenes.insert(id_comprador, &cantidad);
+
+            let mut cantidad = self.cantidad_ordenes.get(id_vendedor).unwrap_or_default();
+            self.ordenes_por_vendedor
+                .insert((id_vendedor, cantidad.como_vendedor), &id_orden);
+            cantidad.como_vendedor = cantidad.como_vendedor.saturating_add(1);
+            self.cantidad_ordenes.insert(id_vendedor, &cantidad);
+        }
+
+        /// Pagina las posiciones `[desde, desde + tamano_pagina)` de un índice de órdenes, donde
+        /// `id_en` devuelve el id de la orden en cada posición.
+        /// El filtro de estado se aplica dentro de esa ventana, por lo que la página puede traer menos elementos
+        fn _paginar_ordenes(
+            &self,
+            total: u32,
+            id_en: impl Fn(u32) -> Option<u32>,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato> {
+            paginar_indices(total, desde, tamano_pagina, |i| {
+                self.ordenes
+                    .get(id_en(i)?)
+                    .filter(|orden| estado.is_none() || estado.as_ref() == Some(&orden.status))
+            })
         }
 
-        fn _listar_publicaciones_propias(&self, id_usuario: AccountId) -> Vec<Publicacion> {
-            let mut resultado = Vec::new();
-            for i in 0..self.publicaciones.len() {
-                if let Some(publi) = self.publicaciones.get(i) {
-                    if publi.id_user == id_usuario {
-                        resultado.push(publi);
+        /// Devuelve a las publicaciones el stock de todas las líneas de una orden cancelada
+        /// y reembolsa al comprador el pago retenido.
+        fn _reintegrar_orden(&mut self, orden: &Orden) -> Result<(), ErroresContrato> {
+            for linea in orden.lineas.iter() {
+                let mut publi = self
+                    .publicaciones
+                    .get(linea.id_publicacion)
+                    .ok_or(ErroresContrato::PublicacionNoExiste)?;
+                if publi.cerrada {
+                    // La publicación ya no existe para la venta: las unidades vuelven al
+                    // producto si es del mismo vendedor. Si el producto es de otro vendedor
+                    // (catálogo compartido) quedan en la publicación, que sigue cerrada
+                    let mut producto = self
+                        .productos
+                        .get(publi.id_prod)
+                        .ok_or(ErroresContrato::ProductoInexistente)?;
+                    if producto.id_vendedor == publi.id_user {
+                        let cantidad = producto
+                            .get_cantidad()
+                            .checked_add(linea.cantidad)
+                            .ok_or(ErroresContrato::ErrorSuma)?;
+                        producto.set_cantidad(cantidad);
+                        self.productos.set(publi.id_prod, &producto);
+                        continue;
                     }
                 }
+                let cantidad = publi
+                    .get_cantidad()
+                    .checked_add(linea.cantidad)
+                    .ok_or(ErroresContrato::ErrorSuma)?;
+                publi.set_cantidad(cantidad);
+                self.publicaciones.set(linea.id_publicacion, &publi);
             }
-            resultado
+            // Reembolso el pago retenido al comprador
+            self._transferir(orden.id_comprador, orden.precio_total)
         }
 
-        /// Recibe un ID de una publicacion y devuelve AccountId del vendedor asociado o un Error
-        fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato> {
-            if let Some(publicacion) = self.publicaciones.get(id_pub) {
-                Ok(publicacion.id_user)
-            } else {
-                Err(ErroresContrato::PublicacionNoExiste)
-            }
+        /// Promedio de las calificaciones recibidas como vendedor, con 2 decimales.
+        /// `None` si el usuario no existe o todavía no fue calificado
+        fn _promedio_vendedor(&self, id: AccountId) -> Option<u64> {
+            let (suma, cantidad) = self.m_usuarios.get(id)?.get_calificacion_vendedor();
+            (suma as u64).saturating_mul(100).checked_div(cantidad as u64)
         }
 
-        /// Recibe un ID de una publicacion y devuelve el precio unitario
-        fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, ErroresContrato> {
-            if let Some(publicacion) = self.publicaciones.get(id_pub) {
-                Ok(publicacion.precio_unitario)
-            } else {
-                Err(ErroresContrato::PublicacionNoExiste)
+        /// Devuelve un producto que el vendedor puede modificar: debe existir, ser suyo y no
+        /// estar retirado
+        fn _producto_propio(&self, id_producto: u32, id_vendedor: AccountId) -> Result<Producto, ErroresContrato> {
+            let producto = self
+                .productos
+                .get(id_producto)
+                .ok_or(ErroresContrato::ProductoInexistente)?;
+            if producto.id_vendedor != id_vendedor {
+                return Err(ErroresContrato::ProductoAjeno);
             }
-        }
-    }
-    impl GestionCategoria for Sistema {
-        fn _registrar_categoria(&mut self, nombre: String) -> Result<String, ErroresContrato> {
-            if self.get_categoria_by_name(&nombre).is_ok() {
-                return Err(ErroresContrato::CategoriaYaExistente);
+            if producto.retirado {
+                return Err(ErroresContrato::ProductoRetirado);
             }
+            Ok(producto)
+        }
 
-            // Agregar categoria
-            if self.categorias.len() == u32::MAX {
-                return Err(ErroresContrato::MaxCategoriasAlcanzado);
+        /// Devuelve una publicación que el usuario puede modificar: debe existir, ser suya y no
+        /// estar cerrada
+        fn _publicacion_editable(&self, id_pub: u32, id_usuario: AccountId) -> Result<Publicacion, ErroresContrato> {
+            let publi = self
+                .publicaciones
+                .get(id_pub)
+                .ok_or(ErroresContrato::PublicacionNoExiste)?;
+            if publi.id_user != id_usuario {
+                return Err(ErroresContrato::PublicacionAjena);
             }
-            let id = self.categorias.len();
-            let nueva_categoria = Categoria::new(id, self.clean_cat_name(&nombre)?);
-            self.categorias.push(&nueva_categoria);
+            if publi.cerrada {
+                return Err(ErroresContrato::PublicacionCerrada);
+            }
+            Ok(publi)
+        }
 
-            Ok(String::from("la categoria fue registrada correctamente"))
+        /// Persiste una publicación modificada por su vendedor y emite el evento correspondiente
+        fn _guardar_publicacion(&mut self, publi: &Publicacion) {
+            self.publicaciones.set(publi.id, publi);
+            self.env().emit_event(PublicacionActualizada {
+                id: publi.id,
+                id_vendedor: publi.id_user,
+                stock: publi.stock,
+                precio_unitario: publi.precio_unitario,
+                activa: publi.activa,
+                pausada: publi.pausada,
+                cerrada: publi.cerrada,
+            });
         }
 
-        fn _listar_categorias(&self) -> Vec<Categoria> {
-            let mut resultado = Vec::new();
-            for i in 0..self.categorias.len() {
-                if let Some(categoria) = self.categorias.get(i) {
-                    resultado.push(categoria);
+        /// Suma o resta una orden abierta a cada producto de sus líneas, contando una vez
+        /// por orden los productos que aparecen en más de una línea
+        fn _contar_orden_abierta(&mut self, orden: &Orden, abierta: bool) {
+            let mut productos: Vec<u32> = orden
+                .lineas
+                .iter()
+                .filter_map(|linea| self.publicaciones.get(linea.id_publicacion))
+                .map(|publi| publi.id_prod)
+                .collect();
+            productos.sort_unstable();
+            productos.dedup();
+            for id_producto in productos {
+                let abiertas = self.ordenes_abiertas_por_producto.get(id_producto).unwrap_or(0);
+                let abiertas = if abierta {
+                    abiertas.saturating_add(1)
+                } else {
+                    abiertas.saturating_sub(1)
+                };
+                self.ordenes_abiertas_por_producto.insert(id_producto, &abiertas);
+            }
+        }
+
+        /// Productos y categorias distintos de las líneas de una orden
+        fn _productos_y_categorias(&self, orden: &Orden) -> (Vec<u32>, Vec<u32>) {
+            let mut productos: Vec<u32> = Vec::new();
+            let mut categorias: Vec<u32> = Vec::new();
+            for linea in orden.lineas.iter() {
+                let Some(publi) = self.publicaciones.get(linea.id_publicacion) else {
+                    continue;
+                };
+                let Some(producto) = self.productos.get(publi.id_prod) else {
+                    continue;
+                };
+                if !productos.contains(&producto.id) {
+                    productos.push(producto.id);
+                }
+                if !categorias.contains(&producto.categoria) {
+                    categorias.push(producto.categoria);
                 }
             }
-            resultado
+            (productos, categorias)
         }
 
-        fn get_categoria_by_name(&self, nombre: &String) -> Result<u32, ErroresContrato> {
-            let nombre_limpio = self.clean_cat_name(nombre)?;
-            for i in 0..self.categorias.len() {
-                if let Some(categoria) = self.categorias.get(i) {
-                    if categoria.nombre == nombre_limpio {
-                        return Ok(i);
-                    }
+        /// Suma una orden recibida a las ventas acumuladas de sus productos y categorias y
+        /// reubica sus productos en el ranking de más vendidos
+        fn _acumular_venta(&mut self, orden: &Orden) {
+            // una orden con varias líneas del mismo producto o categoria cuenta una sola vez
+            let mut productos: Vec<u32> = Vec::new();
+            let mut categorias: Vec<u32> = Vec::new();
+            for linea in orden.lineas.iter() {
+                let Some(publi) = self.publicaciones.get(linea.id_publicacion) else {
+                    continue;
+                };
+                let Some(producto) = self.productos.get(publi.id_prod) else {
+                    continue;
+                };
+                let monto = linea.get_subtotal().unwrap_or(Balance::MAX);
+
+                let nueva_orden = !productos.contains(&producto.id);
+                let mut ventas = self.ventas_por_producto.get(producto.id).unwrap_or_default();
+                ventas.sumar_venta(linea.cantidad, monto, nueva_orden);
+                self.ventas_por_producto.insert(producto.id, &ventas);
+                if nueva_orden {
+                    productos.push(producto.id);
+                }
+
+                let nueva_orden = !categorias.contains(&producto.categoria);
+                let mut ventas = self.ventas_por_categoria.get(producto.categoria).unwrap_or_default();
+                ventas.sumar_venta(linea.cantidad, monto, nueva_orden);
+                self.ventas_por_categoria.insert(producto.categoria, &ventas);
+                if nueva_orden {
+                    categorias.push(producto.categoria);
                 }
             }
-            Err(ErroresContrato::CategoriaInexistente)
+
+            let ventas_por_producto = &self.ventas_por_producto;
+            for id_producto in productos {
+                reubicar_en_ranking(
+                    &mut self.ranking_productos,
+                    &mut self.posicion_ranking_productos,
+                    id_producto,
+                    |id| {
+                        let ventas = ventas_por_producto.get(id).unwrap_or_default();
+                        (ventas.unidades, ventas.ordenes)
+                    },
+                );
+            }
         }
 
-        fn clean_cat_name(&self, nombre: &String) -> Result<String, ErroresContrato> {
-            let mut limpio = String::from(nombre.to_lowercase().trim());
-            limpio.truncate(100);
-            if !limpio.is_empty() {
-                Ok(limpio)
+        /// Suma la calificación que recibió el vendedor de una orden a las ventas acumuladas de
+        /// sus productos y categorias
+        fn _acumular_calificacion(&mut self, orden: &Orden, puntaje: u8) {
+            let (productos, categorias) = self._productos_y_categorias(orden);
+            for id_producto in productos {
+                let mut ventas = self.ventas_por_producto.get(id_producto).unwrap_or_default();
+                ventas.sumar_calificacion(puntaje);
+                self.ventas_por_producto.insert(id_producto, &ventas);
+            }
+            for id_categoria in categorias {
+                let mut ventas = self.ventas_por_categoria.get(id_categoria).unwrap_or_default();
+                ventas.sumar_calificacion(puntaje);
+                self.ventas_por_categoria.insert(id_categoria, &ventas);
+            }
+        }
+
+        /// Reubica a un usuario en el ranking de su rol después de que cambió su reputación
+        fn _reubicar_usuario(&mut self, id: AccountId, rol: Rol) {
+            let usuarios = &self.m_usuarios;
+            let clave = |id: &AccountId| {
+                let (suma, cantidad) = match usuarios.get(id) {
+                    Some(usuario) if rol == COMPRADOR => usuario.get_calificacion_comprador(),
+                    Some(usuario) => usuario.get_calificacion_vendedor(),
+                    None => (0, 0),
+                };
+                let promedio = (suma as u64).saturating_mul(100).checked_div(cantidad as u64);
+                (promedio, cantidad)
+            };
+            if rol == COMPRADOR {
+                reubicar_en_ranking(&mut self.ranking_compradores, &mut self.posicion_ranking_compradores, id, clave);
             } else {
-                Err(ErroresContrato::NombreCategoriaVacio)
+                reubicar_en_ranking(&mut self.ranking_vendedores, &mut self.posicion_ranking_vendedores, id, clave);
             }
         }
-    }
 
-    /// Estructuras relacionadas a Usuario
+        /// Cambia el estado de una orden, agrega la transición a su historial y emite el evento
+        /// correspondiente. No persiste la orden, eso queda a cargo de quien la modifica.
+        fn _actualizar_estado(&mut self, orden: &mut Orden, nuevo: EstadoOrden, actor: AccountId) {
+            let anterior = orden.status;
+            orden.status = nuevo;
+            let mut historial = self.historial_ordenes.get(orden.id).unwrap_or_default();
+            historial.push(TransicionOrden {
+                anterior,
+                nuevo,
+                actor,
+                bloque: self.env().block_number(),
+                timestamp: self.env().block_timestamp(),
+            });
+            self.historial_ordenes.insert(orden.id, &historial);
+            let terminado = |estado| matches!(estado, EstadoOrden::Recibida | EstadoOrden::Cancelada);
+            if !terminado(anterior) && terminado(nuevo) {
+                self._contar_orden_abierta(orden, false);
+            }
+            if anterior != EstadoOrden::Recibida && nuevo == EstadoOrden::Recibida {
+                self._acumular_venta(orden);
+            }
+            self.env().emit_event(EstadoOrdenActualizado {
+                id_orden: orden.id,
+                actor,
+                anterior,
+                nuevo,
+            });
+        }
+    }
 
-    /// Roles existentes
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[cfg_attr(feature = "std", derive(StorageLayout))]
-    #[derive(PartialEq, Clone)]
-    pub enum Rol {
-        Comprador,
-        Vendedor,
-        Ambos,
-    }
-
-    /// Estructura que define al Usuario
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    pub struct Usuario {
-        id: AccountId,
-        nombre: String,
-        mail: String,
-        rating: Rating,
-        roles: Vec<Rol>,
-    }
-
-    impl Usuario {
-        ///Crea un nuevo Usuario
-        pub fn new(id: AccountId, nombre: String, mail: String) -> Usuario {
-            Usuario {
-                id,
-                nombre,
-                mail,
-                rating: Rating::new(),
-                roles: Vec::new(),
+    impl GestionProducto for Sistema {
+        fn _crear_producto(
+            &mut self,
+            id_vendedor: AccountId,
+            nombre: String,
+            descripcion: String,
+            categoria: String,
+            stock: u32,
+        ) -> Result<u32, ErroresContrato> {
+            // 1. valido que el nombre y descripcion no sean vacias
+            if nombre.trim().is_empty() || descripcion.trim().is_empty() {
+                return Err(ErroresContrato::DatosInvalidos);
+            }
+            // 2. valido que el stock no sea 0
+            if stock == 0 {
+                return Err(ErroresContrato::StockInvalido);
             }
-        }
 
-        /// Devuelve true si el usuario contiene el rol pasado por parametro
-        pub fn has_role(&self, rol: Rol) -> bool {
-            self.roles.contains(&rol)
+            let id = self.productos.len();
+            let usuario = self.get_user(&id_vendedor)?;
+            if usuario.has_role(VENDEDOR) {
+                let id_cat = self.get_categoria_by_name(&categoria)?;
+                let producto = Producto::new(id, id_vendedor, nombre, descripcion, id_cat, stock);
+                if !self.producto_existe(&producto) {
+                    self.productos.push(&producto);
+                    self.productos_por_clave.insert(
+                        (id_vendedor, normalizar_clave(&producto.nombre), id_cat),
+                        &id,
+                    );
+                    let propios = self
+                        .cantidad_productos_por_vendedor
+                        .get(id_vendedor)
+                        .unwrap_or(0);
+                    self.productos_por_vendedor.insert((id_vendedor, propios), &id);
+                    self.cantidad_productos_por_vendedor
+                        .insert(id_vendedor, &propios.saturating_add(1));
+                    self.env().emit_event(ProductoCreado {
+                        id,
+                        id_vendedor,
+                        categoria: id_cat,
+                    });
+                    Ok(id)
+                } else {
+                    Err(ErroresContrato::ProductoYaExistente)
+                }
+            } else {
+                Err(ErroresContrato::UsuarioNoEsVendedor)
+            }
         }
 
-        /// Devuelve el nombre del usuario
-        pub fn get_name(&self) -> String {
-            self.nombre.clone()
+        /// Verifica si el vendedor del producto ya tiene uno con el mismo nombre y categoría
+        fn producto_existe(&self, p: &Producto) -> bool {
+            self.productos_por_clave
+                .contains((p.id_vendedor, normalizar_clave(&p.nombre), p.categoria))
         }
 
-        /// Devuelve el email del usuario
-        pub fn get_mail(&self) -> String {
-            self.mail.clone()
+        fn _listar_productos(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Producto>, ErroresContrato> {
+            paginar_indices(self.productos.len(), desde, tamano_pagina, |i| self.productos.get(i))
         }
 
-        /// Devuelve el AccountId del usuario
-        pub fn get_id(&self) -> AccountId {
-            self.id.clone()
+        fn _listar_productos_vendedor(
+            &self,
+            id_vendedor: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Producto>, ErroresContrato> {
+            let total = self
+                .cantidad_productos_por_vendedor
+                .get(id_vendedor)
+                .unwrap_or(0);
+            paginar_indices(total, desde, tamano_pagina, |i| {
+                self.productos
+                    .get(self.productos_por_vendedor.get((id_vendedor, i))?)
+                    .filter(|producto| !producto.retirado)
+            })
         }
 
-        /// Devuelve la reputación como Vendedor de un usuario
-        pub fn mostrar_calificacion_vendedor(&self) -> Result<String, ErroresContrato> {
-            self.rating.display_vendedor()
+        fn _compartir_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            compartido: bool,
+        ) -> Result<(), ErroresContrato> {
+            let mut producto = self._producto_propio(id_producto, id_vendedor)?;
+            producto.compartido = compartido;
+            self.productos.set(id_producto, &producto);
+            self.env().emit_event(ProductoCompartido {
+                id: id_producto,
+                id_vendedor,
+                compartido,
+            });
+            Ok(())
         }
 
-        /// Devuelve la reputación como Comprador de un usuario
-        pub fn mostrar_calificacion_comprador(&self) -> Result<String, ErroresContrato> {
-            self.rating.display_comprador()
-        }
+        fn _asignar_sku(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            sku: String,
+        ) -> Result<(), ErroresContrato> {
+            let sku = String::from(sku.trim());
+            if sku.is_empty() || sku.len() > MAX_LARGO_SKU {
+                return Err(ErroresContrato::SkuInvalido);
+            }
+            let mut producto = self._producto_propio(id_producto, id_vendedor)?;
+            let clave = normalizar_clave(&sku);
+            if let Some(otro) = self.productos_por_sku.get(&clave) {
+                if otro != id_producto {
+                    return Err(ErroresContrato::SkuYaExistente);
+                }
+            }
 
-        pub fn get_calificacion_comprador(&mut self) -> (u32, u32) {
-            self.rating.get_calificacion_comprador()
+            // Si el producto ya tenía otro SKU, lo libero
+            if let Some(anterior) = &producto.sku {
+                self.productos_por_sku.remove(normalizar_clave(anterior));
+            }
+            self.productos_por_sku.insert(clave, &id_producto);
+
+            // Un producto con SKU forma parte del catálogo compartido
+            producto.sku = Some(sku.clone());
+            producto.compartido = true;
+            self.productos.set(id_producto, &producto);
+            self.env().emit_event(ProductoCatalogado {
+                id: id_producto,
+                id_vendedor,
+                sku,
+            });
+            Ok(())
         }
 
-        pub fn get_calificacion_vendedor(&mut self) -> (u32, u32) {
-            self.rating.get_calificacion_vendedor()
+        /// Busca un producto del catálogo compartido por su SKU/GTIN
+        fn get_producto_by_sku(&self, sku: &str) -> Result<Producto, ErroresContrato> {
+            let id = self
+                .productos_por_sku
+                .get(normalizar_clave(sku))
+                .ok_or(ErroresContrato::SkuInexistente)?;
+            self.productos
+                .get(id)
+                .ok_or(ErroresContrato::ProductoInexistente)
         }
-    }
-
-    /// Estructura correspondiente al rating de un usuario
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    pub struct Rating {
-        calificacion_comprador: (u32, u32), //valor cumulativo de todas las calificaciones, cant de compras
-        calificacion_vendedor: (u32, u32),
-    }
 
-    ///Métodos de usuario
-    impl Rating {
-        ///crea un rating
-        fn new() -> Rating {
-            Rating {
-                calificacion_comprador: (0, 0),
-                calificacion_vendedor: (0, 0),
+        fn _editar_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            nombre: String,
+            descripcion: String,
+            categoria: String,
+        ) -> Result<(), ErroresContrato> {
+            if nombre.trim().is_empty() || descripcion.trim().is_empty() {
+                return Err(ErroresContrato::DatosInvalidos);
+            }
+            let mut producto = self._producto_propio(id_producto, id_vendedor)?;
+            let id_cat = self.get_categoria_by_name(&categoria)?;
+
+            // Reindexo el producto si cambia su nombre o su categoría
+            let clave_anterior = (id_vendedor, normalizar_clave(&producto.nombre), producto.categoria);
+            let clave_nueva = (id_vendedor, normalizar_clave(&nombre), id_cat);
+            if clave_nueva != clave_anterior {
+                if self.productos_por_clave.contains(&clave_nueva) {
+                    return Err(ErroresContrato::ProductoYaExistente);
+                }
+                self.productos_por_clave.remove(&clave_anterior);
+                self.productos_por_clave.insert(&clave_nueva, &id_producto);
             }
-        }
-
-        fn agregar_calificacion_comprador(&mut self, puntaje: u8) {
-            self.calificacion_comprador.0 =
-                self.calificacion_comprador.0.saturating_add(puntaje as u32); //deja de sumar al llegar al limite de enteros (de u32 en este caso)
-            self.calificacion_comprador.1 = self.calificacion_comprador.1.saturating_add(1);
-        }
 
-        fn agregar_calificacion_vendedor(&mut self, puntaje: u8) {
-            self.calificacion_vendedor.0 =
-                self.calificacion_vendedor.0.saturating_add(puntaje as u32);
-            self.calificacion_vendedor.1 = self.calificacion_vendedor.1.saturating_add(1);
+            producto.nombre = nombre;
+            producto.descripcion = descripcion;
+            producto.categoria = id_cat;
+            self.productos.set(id_producto, &producto);
+            self.env().emit_event(ProductoActualizado {
+                id: id_producto,
+                id_vendedor,
+                categoria: id_cat,
+                stock: producto.stock,
+            });
+            Ok(())
         }
 
-        pub fn get_calificacion_comprador(&mut self) -> (u32, u32) {
-            self.calificacion_comprador.clone()
+        fn _ajustar_stock_producto(
+            &mut self,
+            id_producto: u32,
+            id_vendedor: AccountId,
+            stock: u32,
+        ) -> Result<(), ErroresContrato> {
+            let mut producto = self._producto_propio(id_producto, id_vendedor)?;
+            producto.set_cantidad(stock);
+            self.productos.set(id_producto, &producto);
+            self.env().emit_event(ProductoActualizado {
+                id: id_producto,
+                id_vendedor,
+                categoria: producto.categoria,
+                stock,
+            });
+            Ok(())
         }
 
-        pub fn get_calificacion_vendedor(&mut self) -> (u32, u32) {
-            self.calificacion_vendedor.clone()
-        }
+        fn _retirar_producto(&mut self, id_producto: u32, id_vendedor: AccountId) -> Result<(), ErroresContrato> {
+            let mut producto = self._producto_propio(id_producto, id_vendedor)?;
+
+            // Ninguna publicación del producto puede seguir abierta, sea del dueño o de
+            // otro vendedor del catálogo compartido. El índice por producto solo guarda las no cerradas
+            let ids_publicaciones = self
+                .publicaciones_por_producto
+                .get(id_producto)
+                .unwrap_or_default();
+            if !ids_publicaciones.is_empty() {
+                return Err(ErroresContrato::ProductoConPublicacionesActivas);
+            }
 
-        fn display_comprador(&self) -> Result<String, ErroresContrato> {
-            if self.calificacion_comprador.1 == 0 {
-                return Err(ErroresContrato::NoTieneCalificaciones);
+            // Tampoco puede haber órdenes sin terminar sobre alguna de sus publicaciones
+            if self.ordenes_abiertas_por_producto.get(id_producto).unwrap_or(0) > 0 {
+                return Err(ErroresContrato::ProductoConOrdenesAbiertas);
             }
-            //se toma el valor cumulativo y se corre la coma para tener un digito menos significativo extra, el cual se usa para simular un decimal
-            let cal_c: u32 = (self
-                .calificacion_comprador
-                .0
-                .checked_mul(10)
-                .ok_or(ErroresContrato::ErrorMultiplicacion)?)
-            .checked_div(self.calificacion_comprador.1) //se divide el valor cumulativo por la cantidad de calificaciones
-            .ok_or(ErroresContrato::ErrorMultiplicacion)?;
 
-            Ok(format!(
-                "Calificacion como comprador: {entero},{decimal}",
-                entero = cal_c.div(10), //se compensa el corrimiento de la coma en la parte entera
-                decimal = cal_c.rem(10) //se toma el digito menos significativo como valor decimal
-            ))
+            // Libero el nombre y el SKU para que puedan volver a usarse
+            self.productos_por_clave
+                .remove((id_vendedor, normalizar_clave(&producto.nombre), producto.categoria));
+            if let Some(sku) = &producto.sku {
+                self.productos_por_sku.remove(normalizar_clave(sku));
+            }
+            producto.retirado = true;
+            producto.compartido = false;
+            self.productos.set(id_producto, &producto);
+            self.env().emit_event(ProductoDadoDeBaja {
+                id: id_producto,
+                id_vendedor,
+            });
+            Ok(())
         }
+    }
 
-        fn display_vendedor(&self) -> Result<String, ErroresContrato> {
-            if self.calificacion_vendedor.1 == 0 {
-                return Err(ErroresContrato::NoTieneCalificaciones);
+    impl GestionUsuario for Sistema {
+        fn _registrar_usuario(
+            &mut self,
+            id: AccountId,
+            nombre: String,
+            mail: String,
+            rol: Rol,
+        ) -> Result<(), ErroresContrato> {
+            //Verifico que el usuario, y mail no esten vacios
+            if nombre.is_empty() {
+                return Err(ErroresContrato::NombreUsuarioVacio);
+            }
+            if mail.is_empty() {
+                return Err(ErroresContrato::MailUsuarioVacio);
             }
-            let cal_v: u32 = (self
-                .calificacion_vendedor
-                .0
-                .checked_mul(10)
-                .ok_or(ErroresContrato::ErrorMultiplicacion)?)
-            .checked_div(self.calificacion_vendedor.1)
-            .ok_or(ErroresContrato::ErrorMultiplicacion)?;
 
-            Ok(format!(
-                "Calificacion como vendedor: {entero},{decimal}",
-                entero = cal_v.div(10),
-                decimal = cal_v.rem(10)
-            ))
-        }
-    }
+            // Verifico que el email no exista
+            if self.get_usuario_by_mail(&mail).is_ok() {
+                return Err(ErroresContrato::MailYaExistente);
+            };
 
-    /// Estructuras relacionadas a producto
+            // Verifico que el usuario no exista
+            if self.get_usuario_by_username(&nombre).is_ok() {
+                return Err(ErroresContrato::UsuarioYaExistente);
+            };
 
-    /// Categorias
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub struct Categoria {
-        id: u32,
-        nombre: String,
-    }
+            // Verifico que el AccountId no esté registrado
+            if self.get_user(&id).is_ok() {
+                return Err(ErroresContrato::UsuarioYaExistente);
+            }
 
-    impl Categoria {
-        pub fn new(id: u32, nombre: String) -> Self {
-            Self { id, nombre }
-        }
+            // Instancio nuevo usuario
+            let usuario = Usuario::new(id, nombre.clone(), mail);
 
-        pub fn get_id(&self) -> u32 {
-            self.id.clone()
+            // Inserto el usuario tanto en el Mapping como en el Vec
+            self.m_usuarios.insert(id, &usuario);
+            self.v_usuarios.push(&id);
+            self.usuarios_por_mail
+                .insert(normalizar_clave(&usuario.mail), &id);
+            self.usuarios_por_nombre
+                .insert(normalizar_clave(&usuario.nombre), &id);
+            self.env().emit_event(UsuarioRegistrado { id, nombre });
+            self._asignar_rol(id, rol)
         }
 
-        pub fn get_nombre(&self) -> String {
-            self.nombre.clone()
+        ///Devuelve el usuario segun el AccountId provisto
+        fn get_user(&mut self, id: &AccountId) -> Result<Usuario, ErroresContrato> {
+            self.m_usuarios
+                .get(id)
+                .ok_or(ErroresContrato::UsuarioNoExiste)
         }
-    }
-
-    ///Estructura de un producto
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(PartialEq, Debug)]
-    pub struct Producto {
-        id: u32,
-        id_vendedor: AccountId,
-        nombre: String,
-        descripcion: String,
-        categoria: u32,
-        stock: u32,
-    }
 
-    impl Producto {
-        ///Crea un producto nuevo dado los parametros
-        pub fn new(
-            id: u32,
-            id_vendedor: AccountId,
-            nombre: String,
-            descripcion: String,
-            categoria: u32,
-            stock: u32,
-        ) -> Producto {
-            Producto {
-                id,
-                id_vendedor,
-                nombre,
-                descripcion,
-                categoria,
-                stock,
-            }
+        fn _get_cantidad_usuarios(&self) -> u32 {
+            self.v_usuarios.len()
         }
 
-        ///Compara un producto self con un producto pasado por parametro
-        pub fn eq(&self, p: &Producto) -> bool {
-            if self.nombre == p.nombre && self.categoria == p.categoria {
-                return true;
-            }
-            false
+        fn _listar_usuarios(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Usuario>, ErroresContrato> {
+            paginar_indices(self.v_usuarios.len(), desde, tamano_pagina, |i| {
+                self.m_usuarios.get(self.v_usuarios.get(i)?)
+            })
         }
 
-        pub fn get_id(&self) -> u32 {
-            self.id.clone()
+        /// Verifica si ya existe un usuario con el mail dado
+        fn get_usuario_by_mail(&self, mail: &str) -> Result<Usuario, ErroresContrato> {
+            let account_id = self
+                .usuarios_por_mail
+                .get(normalizar_clave(mail))
+                .ok_or(ErroresContrato::MailInexistente)?;
+            self.m_usuarios
+                .get(account_id)
+                .ok_or(ErroresContrato::AccountIdInvalida)
         }
 
-        pub fn get_id_categoria(&self) -> u32 {
-            self.categoria.clone()
+        /// Verifica si ya existe un usuario con un nombre de usuario dado
+        fn get_usuario_by_username(&self, name: &str) -> Result<Usuario, ErroresContrato> {
+            let account_id = self
+                .usuarios_por_nombre
+                .get(normalizar_clave(name))
+                .ok_or(ErroresContrato::UsuarioNoExiste)?;
+            self.m_usuarios
+                .get(account_id)
+                .ok_or(ErroresContrato::AccountIdInvalida)
         }
 
-        pub fn get_nombre(&self) -> String {
-            self.nombre.clone()
+        fn _asignar_rol(&mut self, id: AccountId, rol: Rol) -> Result<(), ErroresContrato> {
+            let mut usuario = self.get_user(&id)?;
+            if rol == Rol::Ambos {
+                let rol1: Result<(), ErroresContrato> = self._asignar_rol(id, Rol::Vendedor);
+                let rol2: Result<(), ErroresContrato> = self._asignar_rol(id, Rol::Comprador);
+                if rol1.is_ok() || rol2.is_ok() {
+                    return Ok(());
+                } else {
+                    return Err(ErroresContrato::AlreadyHasRol);
+                }
+            } else if usuario.has_role(rol.clone()) {
+                return Err(ErroresContrato::AlreadyHasRol);
+            }
+            usuario.roles.push(rol.clone());
+            self.m_usuarios.insert(id, &usuario);
+            self.env().emit_event(RolAsignado { id, rol });
+            Ok(())
         }
-    }
 
-    impl ControlStock for Producto {
-        fn get_cantidad(&self) -> u32 {
-            self.stock
-        }
+        fn _actualizar_perfil(
+            &mut self,
+            id: AccountId,
+            nombre: String,
+            mail: String,
+        ) -> Result<(), ErroresContrato> {
+            let mut usuario = self.get_user(&id)?;
+            if nombre.is_empty() {
+                return Err(ErroresContrato::NombreUsuarioVacio);
+            }
+            if mail.is_empty() {
+                return Err(ErroresContrato::MailUsuarioVacio);
+            }
 
-        fn set_cantidad(&mut self, nueva: u32) {
-            self.stock = nueva;
+            // El mail y el nombre pueden repetirse solo si son los del propio usuario
+            if let Ok(otro) = self.get_usuario_by_mail(&mail) {
+                if otro.id != id {
+                    return Err(ErroresContrato::MailYaExistente);
+                }
+            }
+            if let Ok(otro) = self.get_usuario_by_username(&nombre) {
+                if otro.id != id {
+                    return Err(ErroresContrato::UsuarioYaExistente);
+                }
+            }
+
+            // Libero las claves anteriores antes de indexar las nuevas
+            self.usuarios_por_mail
+                .remove(normalizar_clave(&usuario.mail));
+            self.usuarios_por_nombre
+                .remove(normalizar_clave(&usuario.nombre));
+            self.usuarios_por_mail.insert(normalizar_clave(&mail), &id);
+            self.usuarios_por_nombre
+                .insert(normalizar_clave(&nombre), &id);
+
+            usuario.nombre = nombre.clone();
+            usuario.mail = mail;
+            self.m_usuarios.insert(id, &usuario);
+            self.env().emit_event(PerfilActualizado { id, nombre });
+            Ok(())
         }
     }
 
-    ///LOGICA DE PUBLICACION
+    impl GestionOrden for Sistema {
+        fn _crear_orden(
+            &mut self,
+            id_pub: u32,
+            id_comprador: AccountId,
+            cantidad: u32,
+            pago: Balance,
+        ) -> Result<u32, ErroresContrato> {
+            self._crear_orden_con_items(id_comprador, &[ItemCarrito::new(id_pub, cantidad)], pago)
+        }
 
-    ///Estructura de publicacion
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(PartialEq, Debug, Clone)]
-    pub struct Publicacion {
-        id: u32,
-        id_prod: u32,       //id del producto que contiene
-        id_user: AccountId, //id del user que publica
-        stock: u32,
-        precio_unitario: Balance,
-        activa: bool,
-    }
+        fn _crear_orden_con_items(
+            &mut self,
+            id_comprador: AccountId,
+            items: &[ItemCarrito],
+            pago: Balance,
+        ) -> Result<u32, ErroresContrato> {
+            let id_orden = self.ordenes.len();
+            let comprador = self.get_user(&id_comprador)?;
 
-    impl Publicacion {
-        pub fn stock(&self) -> u32 {
-            self.stock
-        }
+            // Armo las líneas con el precio vigente de cada publicación
+            let mut id_vendedor = None;
+            let mut lineas = Vec::new();
+            let mut precio_total: Balance = 0;
+            for item in items {
+                let vendedor_item = self.get_id_vendedor(item.id_publicacion)?;
+                if id_vendedor.is_some_and(|id| id != vendedor_item) {
+                    return Err(ErroresContrato::VendedorDistintoEnCarrito);
+                }
+                id_vendedor = Some(vendedor_item);
+                let linea = LineaOrden::new(
+                    item.id_publicacion,
+                    item.cantidad,
+                    self.get_precio_unitario(item.id_publicacion)?,
+                );
+                precio_total = precio_total
+                    .checked_add(linea.get_subtotal()?)
+                    .ok_or(ErroresContrato::ErrorSuma)?;
+                lineas.push(linea);
+            }
+            let id_vendedor = id_vendedor.ok_or(ErroresContrato::ListaSinProductos)?;
+            if id_vendedor == id_comprador {
+                return Err(ErroresContrato::CompraPropia);
+            }
+            let vendedor = self.get_user(&id_vendedor)?;
 
-        pub fn new(
-            id: u32,
-            id_producto: u32,
-            id_user: AccountId,
-            stock: u32,
-            precio_unitario: Balance,
-        ) -> Publicacion {
-            Publicacion {
-                id,
-                id_prod: id_producto,
-                id_user,
-                stock,
-                precio_unitario,
-                activa: true,
+            if comprador.has_role(COMPRADOR) && vendedor.has_role(VENDEDOR) {
+                if lineas.iter().all(|linea| linea.cantidad != 0) {
+                    // El pago debe cubrir exactamente el precio total, queda retenido en el contrato
+                    if pago < precio_total {
+                        return Err(ErroresContrato::PagoInsuficiente);
+                    }
+                    if pago > precio_total {
+                        return Err(ErroresContrato::PagoExcedente);
+                    }
+
+                    // Descuento el stock de todas las líneas antes de guardar ninguna,
+                    // así si una falla no queda stock descontado a medias
+                    let mut publicaciones = Vec::new();
+                    for linea in lineas.iter() {
+                        let mut publicacion = self
+                            .publicaciones
+                            .get(linea.id_publicacion)
+                            .ok_or(ErroresContrato::PublicacionNoExiste)?;
+                        publicacion.descontar_stock(linea.cantidad)?;
+                        publicaciones.push(publicacion);
+                    }
+                    for (linea, publicacion) in lineas.iter().zip(publicaciones.iter()) {
+                        self.publicaciones.set(linea.id_publicacion, publicacion);
+                    }
+
+                    let orden = Orden::new(
+                        id_orden,
+                        lineas.clone(),
+                        id_vendedor,
+                        id_comprador,
+                        precio_total,
+                        self.env().block_timestamp(),
+                    );
+                    self.ordenes.push(&orden);
+                    self._contar_orden_abierta(&orden, true);
+                    self._indexar_orden(id_orden, id_comprador, id_vendedor);
+                    self.env().emit_event(OrdenCreada {
+                        id_orden,
+                        id_comprador,
+                        id_vendedor,
+                        lineas,
+                        precio_total,
+                    });
+                    Ok(id_orden)
+                } else {
+                    Err(ErroresContrato::CantidadEnCarritoMenorAUno)
+                }
+            } else {
+                Err(ErroresContrato::RolNoApropiado)
             }
         }
 
-        pub fn get_id(&self) -> u32 {
-            self.id.clone()
+        fn _listar_ordenes(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Orden>, ErroresContrato> {
+            paginar_indices(self.ordenes.len(), desde, tamano_pagina, |i| self.ordenes.get(i))
         }
 
-        pub fn get_id_producto(&self) -> u32 {
-            self.id_prod.clone()
+        fn _listar_compras(
+            &self,
+            id_comprador: AccountId,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato> {
+            let total = self._get_cantidad_ordenes(id_comprador).como_comprador;
+            let id_en = |i| self.ordenes_por_comprador.get((id_comprador, i));
+            self._paginar_ordenes(total, id_en, estado, desde, tamano_pagina)
         }
-    }
 
-    impl ControlStock for Publicacion {
-        fn get_cantidad(&self) -> u32 {
-            self.stock
+        fn _listar_ventas(
+            &self,
+            id_vendedor: AccountId,
+            estado: Option<EstadoOrden>,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Orden>, ErroresContrato> {
+            let total = self._get_cantidad_ordenes(id_vendedor).como_vendedor;
+            let id_en = |i| self.ordenes_por_vendedor.get((id_vendedor, i));
+            self._paginar_ordenes(total, id_en, estado, desde, tamano_pagina)
         }
 
-        fn set_cantidad(&mut self, nueva: u32) {
-            self.stock = nueva;
+        fn _get_cantidad_ordenes(&self, id: AccountId) -> CantidadOrdenes {
+            self.cantidad_ordenes.get(id).unwrap_or_default()
         }
 
-        //override
-        fn descontar_stock(&mut self, cantidad_a_descontar: u32) -> Result<(), ErroresContrato> {
-            self.chequear_stock_disponible(cantidad_a_descontar)?;
-            let nueva_cantidad = self
-                .get_cantidad()
-                .checked_sub(cantidad_a_descontar)
-                .ok_or(ErroresContrato::StockInsuficiente)?;
-            if nueva_cantidad == 0 {
-                self.activa = false
+        fn _get_historial_orden(&self, id_orden: u32) -> Result<Vec<TransicionOrden>, ErroresContrato> {
+            if id_orden >= self.ordenes.len() {
+                return Err(ErroresContrato::OrdenInexistente);
             }
-            self.set_cantidad(nueva_cantidad);
-            Ok(())
+            Ok(self.historial_ordenes.get(id_orden).unwrap_or_default())
         }
-    }
 
-    ///Estructuras y logica de Orden
-    ///Posibles estados de una Ordem
-    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub enum EstadoOrden {
-        Pendiente,
-        Enviada,      //solo lo puede modificar el vendedor
-        Recibida,     //solo lo puede modificar el comprador
-        PreCancelada, //solo lo puede modificar el comprador
-        Cancelada,    //solo lo puede modificar el vendedor y tiene que estar en estado PreCancelada
-    }
+        fn _procesar_vencimiento(&mut self, id_orden: u32, actor: AccountId) -> Result<EstadoOrden, ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            let ahora = self.env().block_timestamp();
 
-    ///Estructura de orden
-    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
-    #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    #[derive(Clone)]
-    pub struct Orden {
-        id: u32,
-        id_publicacion: u32,
-        id_vendedor: AccountId,
-        id_comprador: AccountId,
-        status: EstadoOrden,
-        cantidad: u32,
-        precio_total: Balance,
-        cal_vendedor: Option<u8>,  //calificacion que recibe el vendedor
-        cal_comprador: Option<u8>, //calificacion que recibe el comprador
-    }
+            match orden.status {
+                EstadoOrden::Pendiente => {
+                    if ahora < orden.creada_en.saturating_add(self.plazo_envio) {
+                        return Err(ErroresContrato::PlazoNoVencido);
+                    }
+                    // El vendedor no envió a tiempo: se cancela y se devuelve stock y pago
+                    self._actualizar_estado(&mut orden, EstadoOrden::Cancelada, actor);
+                    self.ordenes.set(id_orden, &orden);
+                    self._reintegrar_orden(&orden)?;
+                }
+                EstadoOrden::Enviada => {
+                    let enviada_en = orden.enviada_en.unwrap_or(orden.creada_en);
+                    if ahora < enviada_en.saturating_add(self.plazo_confirmacion) {
+                        return Err(ErroresContrato::PlazoNoVencido);
+                    }
+                    // El comprador no confirmó a tiempo: se da por recibida y se libera el pago
+                    self._actualizar_estado(&mut orden, EstadoOrden::Recibida, actor);
+                    self.ordenes.set(id_orden, &orden);
+                    self._transferir(orden.id_vendedor, orden.precio_total)?;
+                }
+                _ => return Err(ErroresContrato::OrdenSinPlazo),
+            }
+            Ok(orden.status)
+        }
 
-    impl Orden {
-        ///crea una nueva orden
-        pub fn new(
-            id: u32,
-            id_publicacion: u32,
+        fn _enviar_orden(
+            &mut self,
+            id_orden: u32,
             id_vendedor: AccountId,
-            id_comprador: AccountId,
-            cantidad: u32,
-            precio_total: Balance,
-        ) -> Orden {
-            Orden {
-                id,
-                id_publicacion,
-                id_vendedor,
-                id_comprador,
-                status: EstadoOrden::Pendiente,
-                cantidad,
-                precio_total,
-                cal_vendedor: None,
-                cal_comprador: None,
+        ) -> Result<(), ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+
+            if id_vendedor != orden.id_vendedor {
+                return Err(ErroresContrato::NoEsVendedorOriginal);
             }
-        }
-        pub fn get_cantidad(&self) -> u32 {
-            self.cantidad
-        }
 
-        pub fn get_status(&self) -> EstadoOrden {
-            self.status
+            match orden.status {
+                EstadoOrden::Pendiente => {
+                    self._actualizar_estado(&mut orden, EstadoOrden::Enviada, id_vendedor);
+                    orden.enviada_en = Some(self.env().block_timestamp());
+                    self.ordenes.set(id_orden, &orden);
+                    Ok(())
+                }
+                _ => {
+                    Err(ErroresContrato::OrdenNoPendiente)
+                },
+            }
         }
 
-        pub fn get_id_comprador(&self) -> AccountId {
-            self.id_comprador.clone()
-        }
+        fn _recibir_orden(
+            &mut self,
+            id_orden: u32,
+            id_comprador: AccountId,
+        ) -> Result<(), ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if id_comprador != orden.id_comprador {
+                return Err(ErroresContrato::NoEsCompradorOriginal);
+            }
 
-        pub fn get_id_vendedor(&self) -> AccountId {
-            self.id_vendedor.clone()
+            match orden.status {
+                EstadoOrden::Enviada => {
+                    self._actualizar_estado(&mut orden, EstadoOrden::Recibida, id_comprador);
+                    self.ordenes.set(id_orden, &orden);
+                    // Libero el pago retenido al vendedor
+                    self._transferir(orden.id_vendedor, orden.precio_total)
+                }
+                _ => {
+                    Err(ErroresContrato::OrdenNoEnviada)
+                },
+            }
         }
 
-        pub fn get_id_publicacion(&self) -> u32 {
-            self.id_publicacion.clone()
+        fn _cancelar_orden(
+            &mut self,
+            id_orden: u32,
+            id_usuario: AccountId,
+            motivo: MotivoCancelacion,
+        ) -> Result<ResultadoCancelacion, ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            let usuario = self
+                .m_usuarios
+                .get(id_usuario)
+                .ok_or(ErroresContrato::CuentaNoRegistrada)?;
+            let es_comprador = id_usuario == orden.id_comprador && usuario.has_role(COMPRADOR);
+            let es_vendedor = id_usuario == orden.id_vendedor && usuario.has_role(VENDEDOR);
+            if !es_comprador && !es_vendedor {
+                return Err(ErroresContrato::UsuarioNoCorresponde);
+            }
+
+            match orden.status {
+                EstadoOrden::Pendiente => {
+                    orden.cancelacion = Some(SolicitudCancelacion::new(id_usuario, motivo, orden.status));
+                    self._actualizar_estado(&mut orden, EstadoOrden::PreCancelada, id_usuario);
+                    self.ordenes.set(id_orden, &orden);
+                    self.env().emit_event(CancelacionPropuesta {
+                        id_orden,
+                        propuesta_por: id_usuario,
+                        motivo,
+                    });
+                    Ok(ResultadoCancelacion::CancelacionIniciada)
+                }
+                EstadoOrden::PreCancelada => {
+                    let solicitud = orden
+                        .cancelacion
+                        .as_ref()
+                        .ok_or(ErroresContrato::SinCancelacionPendiente)?;
+                    // Quien propuso la cancelación no puede confirmarla
+                    if solicitud.propuesta_por == id_usuario {
+                        return Err(ErroresContrato::CancelacionDeOrdenSinConsenso);
+                    }
+                    self._actualizar_estado(&mut orden, EstadoOrden::Cancelada, id_usuario);
+                    self.ordenes.set(id_orden, &orden);
+                    self._reintegrar_orden(&orden)?;
+                    Ok(ResultadoCancelacion::CancelacionConfirmada)
+                }
+                EstadoOrden::Cancelada => Err(ErroresContrato::OrdenYaCancelada),
+                _ => Err(ErroresContrato::OrdenNoPendiente),
+            }
         }
 
-        pub fn get_calificacion_vendedor(&self) -> Option<u8> {
-            self.cal_vendedor
+        fn _rechazar_cancelacion(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<(), ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if orden.status != EstadoOrden::PreCancelada {
+                return Err(ErroresContrato::SinCancelacionPendiente);
+            }
+            if id_usuario != orden.id_comprador && id_usuario != orden.id_vendedor {
+                return Err(ErroresContrato::UsuarioNoCorresponde);
+            }
+            let solicitud = orden
+                .cancelacion
+                .take()
+                .ok_or(ErroresContrato::SinCancelacionPendiente)?;
+            if solicitud.propuesta_por == id_usuario {
+                return Err(ErroresContrato::CancelacionPropia);
+            }
+            self._actualizar_estado(&mut orden, solicitud.estado_previo, id_usuario);
+            self.ordenes.set(id_orden, &orden);
+            self.env().emit_event(CancelacionRechazada {
+                id_orden,
+                rechazada_por: id_usuario,
+            });
+            Ok(())
         }
 
-        pub fn get_calificacion_comprador(&self) -> Option<u8> {
-            self.cal_comprador
+        fn _calificar_orden(
+            &mut self,
+            id_orden: u32,
+            id: AccountId,
+            puntaje: u8,
+        ) -> Result<(), ErroresContrato> {
+            if !(1..=5).contains(&puntaje) {
+                return Err(ErroresContrato::PuntajeInvalido);
+            }
+            let orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if orden.status != EstadoOrden::Recibida {
+                return Err(ErroresContrato::OrdenNoRecibida);
+            }
+
+            // Si la orden es más barata que el valor mínimo, sus calificaciones
+            // quedan en la orden pero no suman a la reputación
+            let mut ocultas = self.calificaciones_ocultas.get(id_orden).unwrap_or(CalificacionesOcultas {
+                cal_vendedor: None,
+                cal_comprador: None,
+                primera_en: self.env().block_timestamp(),
+                cuenta: orden.precio_total >= self.valor_minimo_calificacion,
+                revelada: false,
+            });
+            let revelada = ocultas.revelada;
+            let (calificado, calificacion) = match id {
+                // El Comprador califica al Vendedor
+                id if id == orden.id_comprador => (orden.id_vendedor, &mut ocultas.cal_vendedor),
+                id if id == orden.id_vendedor => (orden.id_comprador, &mut ocultas.cal_comprador),
+                _ => return Err(ErroresContrato::UsuarioNoCorresponde),
+            };
+            if calificacion.is_some() {
+                return Err(ErroresContrato::YaCalificado);
+            }
+            if revelada {
+                return Err(ErroresContrato::CalificacionCerrada);
+            }
+            *calificacion = Some(puntaje);
+            if ocultas.cuenta {
+                self._registrar_calificacion_par(id, calificado)?;
+            }
+            self.calificaciones_ocultas.insert(id_orden, &ocultas);
+            self.env().emit_event(CalificacionPendiente {
+                id_orden,
+                calificador: id,
+            });
+
+            // Con las dos calificaciones ya no hay nada que ocultar
+            if ocultas.cal_vendedor.is_some() && ocultas.cal_comprador.is_some() {
+                self._aplicar_calificaciones(id_orden)?;
+            }
+            Ok(())
+        }
+
+        fn _revelar_calificaciones(&mut self, id_orden: u32) -> Result<(), ErroresContrato> {
+            if id_orden >= self.ordenes.len() {
+                return Err(ErroresContrato::OrdenInexistente);
+            }
+            let ocultas = self
+                .calificaciones_ocultas
+                .get(id_orden)
+                .filter(|ocultas| !ocultas.revelada)
+                .ok_or(ErroresContrato::SinCalificacionesPendientes)?;
+            if self.env().block_timestamp() < ocultas.primera_en.saturating_add(self.plazo_revelacion) {
+                return Err(ErroresContrato::PlazoNoVencido);
+            }
+            self._aplicar_calificaciones(id_orden)
+        }
+    }
+
+    impl GestionPublicacion for Sistema {
+        fn _crear_publicacion(
+            &mut self,
+            id_producto: u32,
+            id_usuario: AccountId,
+            stock: u32,
+            precio: Balance,
+        ) -> Result<u32, ErroresContrato> {
+            // 1. valido que el stock no sea 0 unidades
+            if stock == 0 {
+                return Err(ErroresContrato::StockInvalido);
+            }
+            // 2. valido que el precio no sea $0
+            if precio == 0 {
+                return Err(ErroresContrato::PrecioInvalido);
+            }
+
+            let id = self.publicaciones.len();
+            let usuario = self.get_user(&id_usuario)?;
+            if usuario.has_role(VENDEDOR) {
+                // Obtengo el producto original y decuento la cantidad de su stock actual
+                let mut producto = self
+                    .productos
+                    .get(id_producto)
+                    .ok_or(ErroresContrato::ProductoInexistente)?;
+                if producto.retirado {
+                    return Err(ErroresContrato::ProductoRetirado);
+                }
+                // Publicar un producto ajeno requiere que su dueño lo haya compartido.
+                // En ese caso el stock es del vendedor que publica, no del depósito del dueño
+                if producto.id_vendedor != id_usuario && !producto.compartido {
+                    return Err(ErroresContrato::ProductoAjeno);
+                }
+                // Un vendedor tiene a lo sumo una publicación sin cerrar de cada producto
+                if self.publicacion_abierta.contains((id_usuario, id_producto)) {
+                    return Err(ErroresContrato::PublicacionYaAbierta);
+                }
+                if producto.id_vendedor == id_usuario {
+                    producto.descontar_stock(stock)?;
+                    self.productos.set(id_producto, &producto);
+                }
+
+                let p = Publicacion::new(id, id_producto, id_usuario, stock, precio); // precio o precio unitario?
+                self.publicaciones.push(&p);
+                // El índice de ofertas del producto solo guarda publicaciones sin cerrar
+                let mut ofertas = self
+                    .publicaciones_por_producto
+                    .get(id_producto)
+                    .unwrap_or_default();
+                ofertas.push(id);
+                self.publicaciones_por_producto.insert(id_producto, &ofertas);
+                self.publicacion_abierta.insert((id_usuario, id_producto), &id);
+                let propias = self
+                    .cantidad_publicaciones_por_vendedor
+                    .get(id_usuario)
+                    .unwrap_or(0);
+                self.publicaciones_por_vendedor.insert((id_usuario, propias), &id);
+                self.cantidad_publicaciones_por_vendedor
+                    .insert(id_usuario, &propias.saturating_add(1));
+                self.env().emit_event(PublicacionCreada {
+                    id,
+                    id_producto,
+                    id_vendedor: id_usuario,
+                    stock,
+                    precio_unitario: precio,
+                });
+                Ok(id)
+            } else {
+                Err(ErroresContrato::RolNoApropiado)
+            }
+        }
+
+        /// Con `solo_activas` la página recorre la misma ventana de publicaciones almacenadas
+        /// y omite las inactivas, por lo que puede traer menos de `tamano_pagina` elementos
+        fn _listar_publicaciones(
+            &self,
+            solo_activas: bool,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            paginar_indices(self.publicaciones.len(), desde, tamano_pagina, |i| {
+                self.publicaciones
+                    .get(i)
+                    .filter(|publi| !solo_activas || publi.activa)
+            })
+        }
+
+        fn _listar_publicaciones_propias(
+            &self,
+            id_usuario: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            let total = self
+                .cantidad_publicaciones_por_vendedor
+                .get(id_usuario)
+                .unwrap_or(0);
+            paginar_indices(total, desde, tamano_pagina, |i| {
+                self.publicaciones
+                    .get(self.publicaciones_por_vendedor.get((id_usuario, i))?)
+            })
+        }
+
+        /// Devuelve las publicaciones activas de un producto, ordenadas de menor a mayor precio.
+        /// A igual precio primero va la del vendedor con mejor reputación y al final las de
+        /// vendedores sin calificaciones
+        fn _listar_ofertas(&self, id_producto: u32) -> Vec<Publicacion> {
+            let mut ofertas: Vec<(Publicacion, Option<u64>)> = self
+                .publicaciones_por_producto
+                .get(id_producto)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.publicaciones.get(id))
+                .filter(|publi| publi.activa && publi.stock > 0)
+                .map(|publi| {
+                    let reputacion = self._promedio_vendedor(publi.id_user);
+                    (publi, reputacion)
+                })
+                .collect();
+
+            // None es menor que cualquier Some, así que de mayor a menor los no calificados quedan últimos
+            ofertas.sort_by(|(a, reputacion_a), (b, reputacion_b)| {
+                a.precio_unitario
+                    .cmp(&b.precio_unitario)
+                    .then_with(|| reputacion_b.cmp(reputacion_a))
+            });
+            ofertas.into_iter().map(|(publi, _)| publi).collect()
+        }
+
+        /// Devuelve la mejor oferta de un producto que tenga stock para la cantidad pedida,
+        /// sin contar las publicaciones del propio comprador
+        fn _mejor_oferta(
+            &self,
+            id_producto: u32,
+            id_comprador: AccountId,
+            cantidad: u32,
+        ) -> Result<Publicacion, ErroresContrato> {
+            self._listar_ofertas(id_producto)
+                .into_iter()
+                .find(|publi| publi.id_user != id_comprador && publi.stock >= cantidad)
+                .ok_or(ErroresContrato::SinOfertas)
+        }
+
+        fn _actualizar_precio(
+            &mut self,
+            id_pub: u32,
+            id_usuario: AccountId,
+            precio: Balance,
+        ) -> Result<(), ErroresContrato> {
+            if precio == 0 {
+                return Err(ErroresContrato::PrecioInvalido);
+            }
+            let mut publi = self._publicacion_editable(id_pub, id_usuario)?;
+            publi.precio_unitario = precio;
+            self._guardar_publicacion(&publi);
+            Ok(())
+        }
+
+        fn _reponer_stock(
+            &mut self,
+            id_pub: u32,
+            id_usuario: AccountId,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato> {
+            if cantidad == 0 {
+                return Err(ErroresContrato::StockInvalido);
+            }
+            let mut publi = self._publicacion_editable(id_pub, id_usuario)?;
+
+            // Si el producto es del vendedor, las unidades salen de su stock.
+            // En una publicación de un producto compartido el stock es propio de quien publica
+            let mut producto = self
+                .productos
+                .get(publi.id_prod)
+                .ok_or(ErroresContrato::ProductoInexistente)?;
+            if producto.id_vendedor == id_usuario {
+                producto.descontar_stock(cantidad)?;
+                self.productos.set(publi.id_prod, &producto);
+            }
+
+            let nuevo_stock = publi
+                .stock
+                .checked_add(cantidad)
+                .ok_or(ErroresContrato::ErrorSuma)?;
+            publi.set_cantidad(nuevo_stock);
+            self._guardar_publicacion(&publi);
+            Ok(())
+        }
+
+        fn _pausar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato> {
+            let mut publi = self._publicacion_editable(id_pub, id_usuario)?;
+            if publi.pausada {
+                return Err(ErroresContrato::PublicacionYaPausada);
+            }
+            publi.pausada = true;
+            publi.actualizar_actividad();
+            self._guardar_publicacion(&publi);
+            Ok(())
+        }
+
+        fn _reanudar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato> {
+            let mut publi = self._publicacion_editable(id_pub, id_usuario)?;
+            if !publi.pausada {
+                return Err(ErroresContrato::PublicacionNoPausada);
+            }
+            publi.pausada = false;
+            publi.actualizar_actividad();
+            self._guardar_publicacion(&publi);
+            Ok(())
+        }
+
+        fn _cerrar_publicacion(&mut self, id_pub: u32, id_usuario: AccountId) -> Result<(), ErroresContrato> {
+            let mut publi = self._publicacion_editable(id_pub, id_usuario)?;
+
+            // Las unidades sin vender vuelven al producto, si es del mismo vendedor
+            let mut producto = self
+                .productos
+                .get(publi.id_prod)
+                .ok_or(ErroresContrato::ProductoInexistente)?;
+            if producto.id_vendedor == id_usuario {
+                let stock_producto = producto
+                    .get_cantidad()
+                    .checked_add(publi.stock)
+                    .ok_or(ErroresContrato::ErrorSuma)?;
+                producto.set_cantidad(stock_producto);
+                self.productos.set(publi.id_prod, &producto);
+            }
+
+            publi.set_cantidad(0);
+            publi.cerrada = true;
+            publi.actualizar_actividad();
+            self._guardar_publicacion(&publi);
+
+            // Una publicación cerrada deja de ser oferta y libera su lugar en el índice del producto
+            let mut ofertas = self
+                .publicaciones_por_producto
+                .get(publi.id_prod)
+                .unwrap_or_default();
+            ofertas.retain(|id| *id != id_pub);
+            self.publicaciones_por_producto.insert(publi.id_prod, &ofertas);
+            self.publicacion_abierta.remove((id_usuario, publi.id_prod));
+            Ok(())
+        }
+
+        fn _buscar_publicaciones(
+            &self,
+            filtro: &FiltroPublicaciones,
+            orden: OrdenBusqueda,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Publicacion>, ErroresContrato> {
+            let id_categoria = match &filtro.categoria {
+                Some(nombre) => Some(self.get_categoria_by_name(nombre)?),
+                None => None,
+            };
+
+            if tamano_pagina == 0 || tamano_pagina > MAX_TAMANO_PAGINA {
+                return Err(ErroresContrato::TamanoPaginaInvalido);
+            }
+
+            // Se revisa desde `desde` hasta llenar la página o alcanzar el límite de revisadas;
+            // con `MasRecientes` las posiciones se cuentan desde la última publicada
+            let total = self.publicaciones.len();
+            let limite = core::cmp::min(desde.saturating_add(MAX_REVISADAS_POR_BUSQUEDA), total);
+            let mut resultados: Vec<(Publicacion, Option<u64>)> = Vec::new();
+            let mut siguiente = desde;
+            while siguiente < limite && resultados.len() < tamano_pagina as usize {
+                let posicion = match orden {
+                    OrdenBusqueda::MasRecientes => total - 1 - siguiente,
+                    _ => siguiente,
+                };
+                siguiente += 1;
+                let Some(publi) = self.publicaciones.get(posicion) else {
+                    continue;
+                };
+                if (filtro.solo_activas && !publi.activa)
+                    || filtro.id_vendedor.is_some_and(|id| id != publi.id_user)
+                    || filtro.precio_min.is_some_and(|min| publi.precio_unitario < min)
+                    || filtro.precio_max.is_some_and(|max| publi.precio_unitario > max)
+                    || filtro.stock_min.is_some_and(|min| publi.stock < min)
+                {
+                    continue;
+                }
+                if let Some(id_cat) = id_categoria {
+                    let categoria = self.productos.get(publi.id_prod).map(|p| p.categoria);
+                    if categoria != Some(id_cat) {
+                        continue;
+                    }
+                }
+                let reputacion = self._promedio_vendedor(publi.id_user);
+                if let Some(min) = filtro.reputacion_min {
+                    match reputacion {
+                        Some(promedio) if promedio >= min => {}
+                        _ => continue,
+                    }
+                }
+                resultados.push((publi, reputacion));
+            }
+
+            match orden {
+                OrdenBusqueda::Precio => resultados.sort_by_key(|(publi, _)| publi.precio_unitario),
+                OrdenBusqueda::MasRecientes => {}
+                // Los vendedores sin calificaciones quedan al final
+                OrdenBusqueda::ReputacionVendedor => resultados.sort_by(|(_, a), (_, b)| b.cmp(a)),
+            }
+            Ok(Pagina {
+                elementos: resultados.into_iter().map(|(publi, _)| publi).collect(),
+                total,
+                siguiente: if siguiente < total { Some(siguiente) } else { None },
+            })
+        }
+
+        /// Recibe un ID de una publicacion y devuelve AccountId del vendedor asociado o un Error
+        fn get_id_vendedor(&self, id_pub: u32) -> Result<AccountId, ErroresContrato> {
+            if let Some(publicacion) = self.publicaciones.get(id_pub) {
+                Ok(publicacion.id_user)
+            } else {
+                Err(ErroresContrato::PublicacionNoExiste)
+            }
+        }
+
+        /// Recibe un ID de una publicacion y devuelve el precio unitario
+        fn get_precio_unitario(&self, id_pub: u32) -> Result<Balance, ErroresContrato> {
+            if let Some(publicacion) = self.publicaciones.get(id_pub) {
+                Ok(publicacion.precio_unitario)
+            } else {
+                Err(ErroresContrato::PublicacionNoExiste)
+            }
+        }
+    }
+    impl GestionCategoria for Sistema {
+        fn _registrar_categoria(&mut self, nombre: String) -> Result<u32, ErroresContrato> {
+            if self.get_categoria_by_name(&nombre).is_ok() {
+                return Err(ErroresContrato::CategoriaYaExistente);
+            }
+
+            // Agregar categoria
+            if self.categorias.len() == u32::MAX {
+                return Err(ErroresContrato::MaxCategoriasAlcanzado);
+            }
+            let id = self.categorias.len();
+            let nombre_limpio = self.clean_cat_name(&nombre)?;
+            let nueva_categoria = Categoria::new(id, nombre_limpio.clone());
+            self.categorias.push(&nueva_categoria);
+            self.env().emit_event(CategoriaRegistrada {
+                id,
+                nombre: nombre_limpio,
+            });
+
+            Ok(id)
+        }
+
+        fn _listar_categorias(&self, desde: u32, tamano_pagina: u32) -> Result<Pagina<Categoria>, ErroresContrato> {
+            paginar_indices(self.categorias.len(), desde, tamano_pagina, |i| self.categorias.get(i))
+        }
+
+        fn get_categoria_by_name(&self, nombre: &str) -> Result<u32, ErroresContrato> {
+            let nombre_limpio = self.clean_cat_name(nombre)?;
+            for i in 0..self.categorias.len() {
+                if let Some(categoria) = self.categorias.get(i) {
+                    if categoria.nombre == nombre_limpio {
+                        return Ok(i);
+                    }
+                }
+            }
+            Err(ErroresContrato::CategoriaInexistente)
+        }
+
+        fn clean_cat_name(&self, nombre: &str) -> Result<String, ErroresContrato> {
+            let mut limpio = String::from(nombre.to_lowercase().trim());
+            limpio.truncate(100);
+            if !limpio.is_empty() {
+                Ok(limpio)
+            } else {
+                Err(ErroresContrato::NombreCategoriaVacio)
+            }
+        }
+    }
+
+    impl GestionCarrito for Sistema {
+        fn _agregar_al_carrito(
+            &mut self,
+            id_comprador: AccountId,
+            id_pub: u32,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato> {
+            let comprador = self
+                .m_usuarios
+                .get(id_comprador)
+                .ok_or(ErroresContrato::CuentaNoRegistrada)?;
+            if !comprador.has_role(COMPRADOR) {
+                return Err(ErroresContrato::RolNoApropiado);
+            }
+            if cantidad == 0 {
+                return Err(ErroresContrato::CantidadEnCarritoMenorAUno);
+            }
+            let id_vendedor = self.get_id_vendedor(id_pub)?;
+            let mut carrito = self._ver_carrito(id_comprador);
+            if let Some(item) = carrito.first() {
+                if self.get_id_vendedor(item.id_publicacion)? != id_vendedor {
+                    return Err(ErroresContrato::VendedorDistintoEnCarrito);
+                }
+            }
+
+            match carrito.iter_mut().find(|item| item.id_publicacion == id_pub) {
+                Some(item) => {
+                    item.cantidad = item
+                        .cantidad
+                        .checked_add(cantidad)
+                        .ok_or(ErroresContrato::ErrorSuma)?;
+                    self._chequear_stock_publicacion(id_pub, item.cantidad)?;
+                }
+                None => {
+                    if carrito.len() >= MAX_ITEMS_CARRITO {
+                        return Err(ErroresContrato::CarritoLleno);
+                    }
+                    self._chequear_stock_publicacion(id_pub, cantidad)?;
+                    carrito.push(ItemCarrito::new(id_pub, cantidad));
+                }
+            }
+            self.carritos.insert(id_comprador, &carrito);
+            Ok(())
+        }
+
+        fn _actualizar_carrito(
+            &mut self,
+            id_comprador: AccountId,
+            id_pub: u32,
+            cantidad: u32,
+        ) -> Result<(), ErroresContrato> {
+            if cantidad == 0 {
+                return Err(ErroresContrato::CantidadEnCarritoMenorAUno);
+            }
+            let mut carrito = self._ver_carrito(id_comprador);
+            let item = carrito
+                .iter_mut()
+                .find(|item| item.id_publicacion == id_pub)
+                .ok_or(ErroresContrato::PublicacionNoEnCarrito)?;
+            self._chequear_stock_publicacion(id_pub, cantidad)?;
+            item.cantidad = cantidad;
+            self.carritos.insert(id_comprador, &carrito);
+            Ok(())
+        }
+
+        fn _quitar_del_carrito(&mut self, id_comprador: AccountId, id_pub: u32) -> Result<(), ErroresContrato> {
+            let mut carrito = self._ver_carrito(id_comprador);
+            let largo = carrito.len();
+            carrito.retain(|item| item.id_publicacion != id_pub);
+            if carrito.len() == largo {
+                return Err(ErroresContrato::PublicacionNoEnCarrito);
+            }
+            if carrito.is_empty() {
+                self.carritos.remove(id_comprador);
+            } else {
+                self.carritos.insert(id_comprador, &carrito);
+            }
+            Ok(())
+        }
+
+        fn _ver_carrito(&self, id_comprador: AccountId) -> Vec<ItemCarrito> {
+            self.carritos.get(id_comprador).unwrap_or_default()
+        }
+
+        fn _confirmar_carrito(&mut self, id_comprador: AccountId, pago: Balance) -> Result<u32, ErroresContrato> {
+            let carrito = self._ver_carrito(id_comprador);
+            if carrito.is_empty() {
+                return Err(ErroresContrato::ListaSinProductos);
+            }
+            let id_orden = self._crear_orden_con_items(id_comprador, &carrito, pago)?;
+            self.carritos.remove(id_comprador);
+            Ok(id_orden)
+        }
+    }
+
+    impl GestionDisputa for Sistema {
+        fn _registrar_arbitro(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato> {
+            self._solo_admin(caller)?;
+            if self.arbitros.contains(id) {
+                return Err(ErroresContrato::YaEsArbitro);
+            }
+            self.arbitros.insert(id, &());
+            Ok(())
+        }
+
+        fn _quitar_arbitro(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato> {
+            self._solo_admin(caller)?;
+            if !self.arbitros.contains(id) {
+                return Err(ErroresContrato::NoEsArbitro);
+            }
+            self.arbitros.remove(id);
+            Ok(())
         }
+
+        fn _abrir_disputa(&mut self, id_orden: u32, id_usuario: AccountId) -> Result<(), ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if id_usuario != orden.id_comprador && id_usuario != orden.id_vendedor {
+                return Err(ErroresContrato::UsuarioNoCorresponde);
+            }
+            match orden.status {
+                EstadoOrden::Enviada | EstadoOrden::PreCancelada => {
+                    orden.disputa = Some(Disputa::new(id_usuario, orden.status));
+                    self._actualizar_estado(&mut orden, EstadoOrden::EnDisputa, id_usuario);
+                    self.ordenes.set(id_orden, &orden);
+                    self.env().emit_event(DisputaAbierta {
+                        id_orden,
+                        abierta_por: id_usuario,
+                    });
+                    Ok(())
+                }
+                _ => Err(ErroresContrato::OrdenNoDisputable),
+            }
+        }
+
+        fn _aportar_evidencia(
+            &mut self,
+            id_orden: u32,
+            id_usuario: AccountId,
+            evidencia: Hash,
+        ) -> Result<(), ErroresContrato> {
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if orden.status != EstadoOrden::EnDisputa {
+                return Err(ErroresContrato::OrdenNoEnDisputa);
+            }
+            let es_comprador = id_usuario == orden.id_comprador;
+            if !es_comprador && id_usuario != orden.id_vendedor {
+                return Err(ErroresContrato::UsuarioNoCorresponde);
+            }
+            let disputa = orden
+                .disputa
+                .as_mut()
+                .ok_or(ErroresContrato::OrdenNoEnDisputa)?;
+            let evidencias = if es_comprador {
+                &mut disputa.evidencia_comprador
+            } else {
+                &mut disputa.evidencia_vendedor
+            };
+            if evidencias.len() >= MAX_EVIDENCIAS {
+                return Err(ErroresContrato::MaxEvidenciasAlcanzado);
+            }
+            evidencias.push(evidencia);
+            self.ordenes.set(id_orden, &orden);
+            self.env().emit_event(EvidenciaAportada {
+                id_orden,
+                autor: id_usuario,
+                evidencia,
+            });
+            Ok(())
+        }
+
+        fn _resolver_disputa(
+            &mut self,
+            id_orden: u32,
+            id_arbitro: AccountId,
+            fallo: FalloDisputa,
+        ) -> Result<(), ErroresContrato> {
+            if !self.arbitros.contains(id_arbitro) {
+                return Err(ErroresContrato::NoEsArbitro);
+            }
+            let mut orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            if id_arbitro == orden.id_comprador || id_arbitro == orden.id_vendedor {
+                return Err(ErroresContrato::ArbitroEsParte);
+            }
+            if orden.status != EstadoOrden::EnDisputa {
+                return Err(ErroresContrato::OrdenNoEnDisputa);
+            }
+            let disputa = orden
+                .disputa
+                .as_mut()
+                .ok_or(ErroresContrato::OrdenNoEnDisputa)?;
+            disputa.fallo = Some(fallo);
+            disputa.arbitro = Some(id_arbitro);
+
+            match fallo {
+                FalloDisputa::Comprador => {
+                    self._actualizar_estado(&mut orden, EstadoOrden::Cancelada, id_arbitro);
+                    self.ordenes.set(id_orden, &orden);
+                    self._reintegrar_orden(&orden)?;
+                }
+                FalloDisputa::Vendedor => {
+                    self._actualizar_estado(&mut orden, EstadoOrden::Recibida, id_arbitro);
+                    self.ordenes.set(id_orden, &orden);
+                    // Libero el pago retenido al vendedor
+                    self._transferir(orden.id_vendedor, orden.precio_total)?;
+                }
+            }
+            self.env().emit_event(DisputaResuelta {
+                id_orden,
+                arbitro: id_arbitro,
+                fallo,
+            });
+            Ok(())
+        }
+    }
+
+    impl GestionResena for Sistema {
+        fn _resenar_orden(
+            &mut self,
+            id_orden: u32,
+            autor: AccountId,
+            puntaje: u8,
+            texto: String,
+        ) -> Result<u32, ErroresContrato> {
+            let texto = validar_texto_resena(texto)?;
+            // La reseña sigue la regla de la calificación: una por parte y por orden
+            self._calificar_orden(id_orden, autor, puntaje)?;
+            let orden = self
+                .ordenes
+                .get(id_orden)
+                .ok_or(ErroresContrato::OrdenInexistente)?;
+            let destinatario = if autor == orden.id_comprador {
+                orden.id_vendedor
+            } else {
+                orden.id_comprador
+            };
+
+            let id_resena = self.resenas.len();
+            self.resenas.push(&Resena {
+                id: id_resena,
+                id_orden,
+                autor,
+                destinatario,
+                puntaje,
+                texto,
+                respuesta: None,
+                creada_en: self.env().block_timestamp(),
+            });
+            let recibidas = self
+                .cantidad_resenas_por_usuario
+                .get(destinatario)
+                .unwrap_or(0);
+            self.resenas_por_usuario.insert((destinatario, recibidas), &id_resena);
+            self.cantidad_resenas_por_usuario
+                .insert(destinatario, &recibidas.saturating_add(1));
+            self.env().emit_event(ResenaPublicada {
+                id_resena,
+                id_orden,
+                autor,
+                destinatario,
+            });
+            Ok(id_resena)
+        }
+
+        fn _responder_resena(
+            &mut self,
+            id_resena: u32,
+            id_usuario: AccountId,
+            texto: String,
+        ) -> Result<(), ErroresContrato> {
+            let texto = validar_texto_resena(texto)?;
+            let mut resena = self
+                .resenas
+                .get(id_resena)
+                .filter(|resena| self._calificaciones_reveladas(resena.id_orden))
+                .ok_or(ErroresContrato::ResenaInexistente)?;
+            if resena.destinatario != id_usuario {
+                return Err(ErroresContrato::NoEsDestinatarioResena);
+            }
+            if resena.respuesta.is_some() {
+                return Err(ErroresContrato::ResenaYaRespondida);
+            }
+            resena.respuesta = Some(texto);
+            self.resenas.set(id_resena, &resena);
+            self.env().emit_event(ResenaRespondida {
+                id_resena,
+                destinatario: id_usuario,
+            });
+            Ok(())
+        }
+
+        fn _listar_resenas(
+            &self,
+            id_usuario: AccountId,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<Resena>, ErroresContrato> {
+            // Las reseñas de órdenes con calificaciones ocultas no se muestran todavía
+            let total = self
+                .cantidad_resenas_por_usuario
+                .get(id_usuario)
+                .unwrap_or(0);
+            paginar_indices(total, desde, tamano_pagina, |i| {
+                self.resenas
+                    .get(self.resenas_por_usuario.get((id_usuario, i))?)
+                    .filter(|resena| self._calificaciones_reveladas(resena.id_orden))
+            })
+        }
+    }
+
+    impl GestionAdmin for Sistema {
+        fn _es_admin(&self, id: AccountId) -> bool {
+            id == self.owner || self.admins.contains(id)
+        }
+
+        fn _agregar_admin(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato> {
+            if caller != self.owner {
+                return Err(ErroresContrato::NoEsOwner);
+            }
+            if self._es_admin(id) {
+                return Err(ErroresContrato::YaEsAdmin);
+            }
+            self.admins.insert(id, &());
+            self.env().emit_event(AdminAgregado { id });
+            Ok(())
+        }
+
+        fn _quitar_admin(&mut self, caller: AccountId, id: AccountId) -> Result<(), ErroresContrato> {
+            if caller != self.owner {
+                return Err(ErroresContrato::NoEsOwner);
+            }
+            if !self.admins.contains(id) {
+                return Err(ErroresContrato::NoEsAdmin);
+            }
+            self.admins.remove(id);
+            self.env().emit_event(AdminQuitado { id });
+            Ok(())
+        }
+
+        fn _proponer_owner(&mut self, caller: AccountId, nuevo: AccountId) -> Result<(), ErroresContrato> {
+            if caller != self.owner {
+                return Err(ErroresContrato::NoEsOwner);
+            }
+            self.owner_propuesto = Some(nuevo);
+            self.env().emit_event(TransferenciaOwnerPropuesta {
+                owner: caller,
+                propuesto: nuevo,
+            });
+            Ok(())
+        }
+
+        fn _aceptar_owner(&mut self, caller: AccountId) -> Result<(), ErroresContrato> {
+            let propuesto = self
+                .owner_propuesto
+                .ok_or(ErroresContrato::SinTransferenciaPendiente)?;
+            if caller != propuesto {
+                return Err(ErroresContrato::NoEsOwnerPropuesto);
+            }
+            let anterior = self.owner;
+            self.owner = propuesto;
+            self.owner_propuesto = None;
+            self.env().emit_event(OwnerTransferido {
+                anterior,
+                nuevo: propuesto,
+            });
+            Ok(())
+        }
+    }
+
+    // Estructuras relacionadas a Usuario
+
+    /// Roles existentes
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(StorageLayout))]
+    #[derive(PartialEq, Clone)]
+    pub enum Rol {
+        Comprador,
+        Vendedor,
+        Ambos,
+    }
+
+    /// Estructura que define al Usuario
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Clone)]
+    pub struct Usuario {
+        id: AccountId,
+        nombre: String,
+        mail: String,
+        rating: Rating,
+        roles: Vec<Rol>,
+    }
+
+    impl Usuario {
+        ///Crea un nuevo Usuario
+        pub fn new(id: AccountId, nombre: String, mail: String) -> Usuario {
+            Usuario {
+                id,
+                nombre,
+                mail,
+                rating: Rating::new(),
+                roles: Vec::new(),
+            }
+        }
+
+        /// Devuelve true si el usuario contiene el rol pasado por parametro
+        pub fn has_role(&self, rol: Rol) -> bool {
+            self.roles.contains(&rol)
+        }
+
+        /// Devuelve el nombre del usuario
+        pub fn get_name(&self) -> String {
+            self.nombre.clone()
+        }
+
+        /// Devuelve el email del usuario
+        pub fn get_mail(&self) -> String {
+            self.mail.clone()
+        }
+
+        /// Devuelve el AccountId del usuario
+        pub fn get_id(&self) -> AccountId {
+            self.id
+        }
+
+        /// Devuelve la reputación como Vendedor de un usuario, con el promedio expresado
+        /// con `decimales` dígitos decimales
+        pub fn get_reputacion_vendedor(&self, decimales: u8) -> Result<Reputacion, ErroresContrato> {
+            Reputacion::new(
+                self.rating.calificacion_vendedor,
+                self.rating.ponderada_vendedor,
+                &self.rating.reciente_vendedor,
+                self.rating.estrellas_vendedor,
+                decimales,
+            )
+        }
+
+        /// Devuelve la reputación como Comprador de un usuario, con el promedio expresado
+        /// con `decimales` dígitos decimales
+        pub fn get_reputacion_comprador(&self, decimales: u8) -> Result<Reputacion, ErroresContrato> {
+            Reputacion::new(
+                self.rating.calificacion_comprador,
+                self.rating.ponderada_comprador,
+                &self.rating.reciente_comprador,
+                self.rating.estrellas_comprador,
+                decimales,
+            )
+        }
+
+        pub fn get_calificacion_comprador(&self) -> (u32, u32) {
+            self.rating.get_calificacion_comprador()
+        }
+
+        pub fn get_calificacion_vendedor(&self) -> (u32, u32) {
+            self.rating.get_calificacion_vendedor()
+        }
+
+        pub fn get_calificacion_ponderada_comprador(&self) -> (Balance, Balance) {
+            self.rating.get_calificacion_ponderada_comprador()
+        }
+
+        pub fn get_calificacion_ponderada_vendedor(&self) -> (Balance, Balance) {
+            self.rating.get_calificacion_ponderada_vendedor()
+        }
+    }
+
+    /// Estructura correspondiente al rating de un usuario
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Clone)]
+    pub struct Rating {
+        calificacion_comprador: (u32, u32), //valor cumulativo de todas las calificaciones, cant de compras
+        calificacion_vendedor: (u32, u32),
+        ponderada_comprador: (Balance, Balance), //suma de puntaje * precio total de la orden, suma de precios totales
+        ponderada_vendedor: (Balance, Balance),
+        reciente_comprador: PuntajeReciente, //calificaciones con decaimiento en el tiempo
+        reciente_vendedor: PuntajeReciente,
+        estrellas_comprador: [u32; 5], //cantidad de calificaciones recibidas de 1 a 5 estrellas
+        estrellas_vendedor: [u32; 5],
+    }
+
+    ///Métodos de usuario
+    impl Rating {
+        ///crea un rating
+        fn new() -> Rating {
+            Rating {
+                calificacion_comprador: (0, 0),
+                calificacion_vendedor: (0, 0),
+                ponderada_comprador: (0, 0),
+                ponderada_vendedor: (0, 0),
+                reciente_comprador: PuntajeReciente::default(),
+                reciente_vendedor: PuntajeReciente::default(),
+                estrellas_comprador: [0; 5],
+                estrellas_vendedor: [0; 5],
+            }
+        }
+
+        /// Suma una calificación al comprador. `peso` es el precio total de la orden calificada
+        fn agregar_calificacion_comprador(&mut self, puntaje: u8, peso: Balance) {
+            self.calificacion_comprador.0 =
+                self.calificacion_comprador.0.saturating_add(puntaje as u32); //deja de sumar al llegar al limite de enteros (de u32 en este caso)
+            self.calificacion_comprador.1 = self.calificacion_comprador.1.saturating_add(1);
+            Self::sumar_ponderada(&mut self.ponderada_comprador, puntaje, peso);
+            Self::sumar_estrella(&mut self.estrellas_comprador, puntaje);
+        }
+
+        /// Suma una calificación al vendedor. `peso` es el precio total de la orden calificada
+        fn agregar_calificacion_vendedor(&mut self, puntaje: u8, peso: Balance) {
+            self.calificacion_vendedor.0 =
+                self.calificacion_vendedor.0.saturating_add(puntaje as u32);
+            self.calificacion_vendedor.1 = self.calificacion_vendedor.1.saturating_add(1);
+            Self::sumar_ponderada(&mut self.ponderada_vendedor, puntaje, peso);
+            Self::sumar_estrella(&mut self.estrellas_vendedor, puntaje);
+        }
+
+        fn sumar_ponderada(ponderada: &mut (Balance, Balance), puntaje: u8, peso: Balance) {
+            ponderada.0 = ponderada.0.saturating_add(peso.saturating_mul(puntaje as Balance));
+            ponderada.1 = ponderada.1.saturating_add(peso);
+        }
+
+        fn sumar_estrella(estrellas: &mut [u32; 5], puntaje: u8) {
+            if let Some(cantidad) = estrellas.get_mut((puntaje as usize).wrapping_sub(1)) {
+                *cantidad = cantidad.saturating_add(1);
+            }
+        }
+
+        pub fn get_calificacion_comprador(&self) -> (u32, u32) {
+            self.calificacion_comprador
+        }
+
+        pub fn get_calificacion_vendedor(&self) -> (u32, u32) {
+            self.calificacion_vendedor
+        }
+
+        pub fn get_calificacion_ponderada_comprador(&self) -> (Balance, Balance) {
+            self.ponderada_comprador
+        }
+
+        pub fn get_calificacion_ponderada_vendedor(&self) -> (Balance, Balance) {
+            self.ponderada_vendedor
+        }
+    }
+
+    /// Vida media por defecto del promedio reciente: 30 días de bloques de 6 segundos
+    pub const VIDA_MEDIA_REPUTACION_DEFAULT: BlockNumber = 30 * 24 * 60 * 10;
+
+    /// Peso de una calificación recién hecha en `PuntajeReciente`, para no perder precisión al decaer
+    const PESO_CALIFICACION_RECIENTE: u128 = 1_000_000;
+
+    /// Suma y peso de calificaciones que pierden peso con el tiempo. Se actualizan solo al agregar
+    /// una calificación: primero se decae lo acumulado por los bloques transcurridos y después se suma.
+    /// Como suma y peso decaen igual, el promedio no cambia entre calificaciones
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Clone, Default)]
+    pub struct PuntajeReciente {
+        suma: u128,
+        peso: u128,
+        ultimo_bloque: BlockNumber,
+    }
+
+    impl PuntajeReciente {
+        fn agregar(&mut self, puntaje: u8, bloque: BlockNumber, vida_media: BlockNumber) {
+            let transcurridos = bloque.saturating_sub(self.ultimo_bloque);
+            self.suma = Self::decaer(self.suma, transcurridos, vida_media);
+            self.peso = Self::decaer(self.peso, transcurridos, vida_media);
+            self.suma = self
+                .suma
+                .saturating_add(PESO_CALIFICACION_RECIENTE.saturating_mul(puntaje as u128));
+            self.peso = self.peso.saturating_add(PESO_CALIFICACION_RECIENTE);
+            self.ultimo_bloque = bloque;
+        }
+
+        /// Divide `valor` por 2 por cada vida media transcurrida. El resto de bloques que no
+        /// llega a una vida media se aproxima linealmente entre 1 y 1/2
+        fn decaer(valor: u128, transcurridos: BlockNumber, vida_media: BlockNumber) -> u128 {
+            if vida_media == 0 {
+                return valor;
+            }
+            let mitades = transcurridos / vida_media;
+            let resto = transcurridos % vida_media;
+            let valor = valor.checked_shr(mitades).unwrap_or(0);
+            let descuento = valor.saturating_mul(resto as u128) / (2 * vida_media as u128);
+            valor.saturating_sub(descuento)
+        }
+
+        fn get_suma_y_peso(&self) -> (u128, u128) {
+            (self.suma, self.peso)
+        }
+    }
+
+    /// Cantidad máxima de decimales con que se puede pedir el promedio de una reputación
+    pub const MAX_DECIMALES_PROMEDIO: u8 = 6;
+
+    /// Reputación de un usuario en uno de sus roles
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Reputacion {
+        pub suma: u32,
+        pub cantidad: u32,
+        pub promedio: Option<u64>, //promedio multiplicado por 10^decimales, None si no tiene calificaciones
+        pub suma_ponderada: Balance, //suma de cada puntaje por el precio total de su orden
+        pub peso_total: Balance,     //suma de los precios totales de las órdenes calificadas
+        pub promedio_ponderado: Option<u64>, //promedio ponderado por precio, con la misma escala que `promedio`
+        pub promedio_reciente: Option<u64>, //promedio que pesa más las calificaciones recientes, misma escala
+        pub decimales: u8,
+        pub estrellas: [u32; 5],   //cantidad de calificaciones de 1 a 5 estrellas
+    }
+
+    impl Reputacion {
+        fn new(
+            (suma, cantidad): (u32, u32),
+            (suma_ponderada, peso_total): (Balance, Balance),
+            reciente: &PuntajeReciente,
+            estrellas: [u32; 5],
+            decimales: u8,
+        ) -> Result<Reputacion, ErroresContrato> {
+            if decimales > MAX_DECIMALES_PROMEDIO {
+                return Err(ErroresContrato::PrecisionInvalida);
+            }
+            let escala = 10u64.pow(decimales as u32);
+            let promedio = (suma as u64)
+                .checked_mul(escala)
+                .ok_or(ErroresContrato::ErrorMultiplicacion)?
+                .checked_div(cantidad as u64);
+            // El promedio ponderado es a lo sumo 5 * escala, así que entra en u64
+            let promedio_ponderado = suma_ponderada
+                .checked_mul(escala as Balance)
+                .ok_or(ErroresContrato::ErrorMultiplicacion)?
+                .checked_div(peso_total)
+                .map(|promedio| promedio as u64);
+            let (suma_reciente, peso_reciente) = reciente.get_suma_y_peso();
+            let promedio_reciente = suma_reciente
+                .checked_mul(escala as u128)
+                .ok_or(ErroresContrato::ErrorMultiplicacion)?
+                .checked_div(peso_reciente)
+                .map(|promedio| promedio as u64);
+            Ok(Reputacion {
+                suma,
+                cantidad,
+                promedio,
+                suma_ponderada,
+                peso_total,
+                promedio_ponderado,
+                promedio_reciente,
+                decimales,
+                estrellas,
+            })
+        }
+    }
+
+    /// Reputación de un usuario como comprador y como vendedor
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct ReputacionUsuario {
+        pub id: AccountId,
+        pub como_comprador: Reputacion,
+        pub como_vendedor: Reputacion,
+    }
+
+    /// Cantidad de órdenes de un usuario en cada rol
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+    pub struct CantidadOrdenes {
+        pub como_comprador: u32,
+        pub como_vendedor: u32,
+    }
+
+    /// Ventas acumuladas de un producto o una categoria. Cuentan las órdenes recibidas
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+    pub struct VentasAcumuladas {
+        pub unidades: u32,
+        pub ordenes: u32,
+        pub monto: Balance,
+        pub calificaciones: u32, //calificaciones que recibieron los vendedores en esas órdenes
+        pub suma_calificaciones: u32,
+    }
+
+    impl VentasAcumuladas {
+        fn sumar_venta(&mut self, unidades: u32, monto: Balance, nueva_orden: bool) {
+            self.unidades = self.unidades.saturating_add(unidades);
+            self.monto = self.monto.saturating_add(monto);
+            if nueva_orden {
+                self.ordenes = self.ordenes.saturating_add(1);
+            }
+        }
+
+        fn sumar_calificacion(&mut self, puntaje: u8) {
+            self.calificaciones = self.calificaciones.saturating_add(1);
+            self.suma_calificaciones = self.suma_calificaciones.saturating_add(puntaje as u32);
+        }
+    }
+
+    // Estructuras relacionadas a producto
+
+    /// Categorias
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct Categoria {
+        id: u32,
+        nombre: String,
+    }
+
+    impl Categoria {
+        pub fn new(id: u32, nombre: String) -> Self {
+            Self { id, nombre }
+        }
+
+        pub fn get_id(&self) -> u32 {
+            self.id
+        }
+
+        pub fn get_nombre(&self) -> String {
+            self.nombre.clone()
+        }
+    }
+
+    /// Largo máximo de un SKU/GTIN
+    pub const MAX_LARGO_SKU: usize = 64;
+
+    /// Clave única de un producto dentro del catálogo de su vendedor: (vendedor, nombre normalizado, categoría)
+    pub type ClaveProducto = (AccountId, String, u32);
+
+    /// Normaliza un texto (mail, nombre de usuario o de producto) para usarlo como clave de índice
+    fn normalizar_clave(valor: &str) -> String {
+        valor.trim().to_lowercase()
+    }
+
+    ///Estructura de un producto
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(PartialEq, Debug)]
+    pub struct Producto {
+        id: u32,
+        id_vendedor: AccountId,
+        nombre: String,
+        descripcion: String,
+        categoria: u32,
+        stock: u32,
+        compartido: bool,
+        sku: Option<String>, //SKU/GTIN, solo para productos del catálogo compartido
+        retirado: bool,
+    }
+
+    impl Producto {
+        ///Crea un producto nuevo dado los parametros
+        pub fn new(
+            id: u32,
+            id_vendedor: AccountId,
+            nombre: String,
+            descripcion: String,
+            categoria: u32,
+            stock: u32,
+        ) -> Producto {
+            Producto {
+                id,
+                id_vendedor,
+                nombre,
+                descripcion,
+                categoria,
+                stock,
+                compartido: false,
+                sku: None,
+                retirado: false,
+            }
+        }
+
+        ///Compara un producto self con un producto pasado por parametro.
+        ///Solo coinciden productos del mismo vendedor, con igual nombre normalizado y categoría
+        pub fn coincide_con(&self, p: &Producto) -> bool {
+            self.id_vendedor == p.id_vendedor
+                && self.categoria == p.categoria
+                && normalizar_clave(&self.nombre) == normalizar_clave(&p.nombre)
+        }
+
+        pub fn get_id_vendedor(&self) -> AccountId {
+            self.id_vendedor
+        }
+
+        /// Indica si otros vendedores pueden publicar este producto
+        pub fn es_compartido(&self) -> bool {
+            self.compartido
+        }
+
+        pub fn get_sku(&self) -> Option<String> {
+            self.sku.clone()
+        }
+
+        pub fn get_descripcion(&self) -> String {
+            self.descripcion.clone()
+        }
+
+        /// Indica si el vendedor retiró el producto del catálogo
+        pub fn esta_retirado(&self) -> bool {
+            self.retirado
+        }
+
+        pub fn get_id(&self) -> u32 {
+            self.id
+        }
+
+        pub fn get_id_categoria(&self) -> u32 {
+            self.categoria
+        }
+
+        pub fn get_nombre(&self) -> String {
+            self.nombre.clone()
+        }
+    }
+
+    impl ControlStock for Producto {
+        fn get_cantidad(&self) -> u32 {
+            self.stock
+        }
+
+        fn set_cantidad(&mut self, nueva: u32) {
+            self.stock = nueva;
+        }
+    }
+
+    //LOGICA DE PUBLICACION
+
+    ///Estructura de publicacion
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Publicacion {
+        id: u32,
+        id_prod: u32,       //id del producto que contiene
+        id_user: AccountId, //id del user que publica
+        stock: u32,
+        precio_unitario: Balance,
+        activa: bool,
+        pausada: bool, //pausada por el vendedor, no se reactiva sola al reponer stock
+        cerrada: bool, //cerrada definitivamente, no admite más cambios
+    }
+
+    impl Publicacion {
+        pub fn stock(&self) -> u32 {
+            self.stock
+        }
+
+        pub fn new(
+            id: u32,
+            id_producto: u32,
+            id_user: AccountId,
+            stock: u32,
+            precio_unitario: Balance,
+        ) -> Publicacion {
+            Publicacion {
+                id,
+                id_prod: id_producto,
+                id_user,
+                stock,
+                precio_unitario,
+                activa: true,
+                pausada: false,
+                cerrada: false,
+            }
+        }
+
+        pub fn get_id(&self) -> u32 {
+            self.id
+        }
+
+        pub fn get_id_producto(&self) -> u32 {
+            self.id_prod
+        }
+
+        pub fn get_id_vendedor(&self) -> AccountId {
+            self.id_user
+        }
+
+        pub fn get_precio_unitario(&self) -> Balance {
+            self.precio_unitario
+        }
+
+        pub fn esta_activa(&self) -> bool {
+            self.activa
+        }
+
+        pub fn esta_pausada(&self) -> bool {
+            self.pausada
+        }
+
+        pub fn esta_cerrada(&self) -> bool {
+            self.cerrada
+        }
+
+        /// Recalcula si la publicación acepta compras: tiene stock y no está pausada ni cerrada
+        fn actualizar_actividad(&mut self) {
+            self.activa = self.stock > 0 && !self.pausada && !self.cerrada;
+        }
+    }
+
+    impl ControlStock for Publicacion {
+        fn get_cantidad(&self) -> u32 {
+            self.stock
+        }
+
+        /// Todo cambio de stock recalcula si la publicación está activa: se desactiva al
+        /// agotarse y se reactiva sola cuando vuelve a tener unidades
+        fn set_cantidad(&mut self, nueva: u32) {
+            self.stock = nueva;
+            self.actualizar_actividad();
+        }
+
+        //override: además del stock, solo se puede comprar en una publicación activa
+        fn chequear_stock_disponible(&self, cantidad_a_descontar: u32) -> Result<(), ErroresContrato> {
+            if self.stock < cantidad_a_descontar {
+                return Err(ErroresContrato::StockInsuficiente);
+            }
+            if !self.activa {
+                return Err(ErroresContrato::PublicacionInactiva);
+            }
+            Ok(())
+        }
+    }
+
+    /// Tamaño máximo de página que aceptan los mensajes de consulta paginados
+    pub const MAX_TAMANO_PAGINA: u32 = 50;
+
+    /// Cantidad máxima de publicaciones que revisa `buscar_publicaciones` para llenar una página
+    pub const MAX_REVISADAS_POR_BUSQUEDA: u32 = 4 * MAX_TAMANO_PAGINA;
+
+    /// Página de resultados de una consulta
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Pagina<T> {
+        pub elementos: Vec<T>,
+        pub total: u32,             //cantidad total de resultados de la consulta
+        pub siguiente: Option<u32>, //posición donde empieza la página siguiente, None si es la última
+    }
+
+    /// Arma la página de `total` elementos que empieza en `desde`, leyendo solo las posiciones
+    /// de la página con `obtener`. Las posiciones para las que `obtener` devuelve `None` se omiten
+    fn paginar_indices<T>(
+        total: u32,
+        desde: u32,
+        tamano_pagina: u32,
+        obtener: impl FnMut(u32) -> Option<T>,
+    ) -> Result<Pagina<T>, ErroresContrato> {
+        if tamano_pagina == 0 || tamano_pagina > MAX_TAMANO_PAGINA {
+            return Err(ErroresContrato::TamanoPaginaInvalido);
+        }
+        let fin = core::cmp::min(desde.saturating_add(tamano_pagina), total);
+        Ok(Pagina {
+            elementos: (desde..fin).filter_map(obtener).collect(),
+            total,
+            siguiente: if fin < total { Some(fin) } else { None },
+        })
+    }
+
+    /// Lee con `obtener` los elementos de los ids indicados, en el mismo orden. Se aceptan a lo
+    /// sumo `MAX_TAMANO_PAGINA` ids, igual que el tamaño de una página
+    fn obtener_por_ids<T>(
+        ids: Vec<u32>,
+        inexistente: ErroresContrato,
+        obtener: impl Fn(u32) -> Option<T>,
+    ) -> Result<Vec<T>, ErroresContrato> {
+        if ids.len() > MAX_TAMANO_PAGINA as usize {
+            return Err(ErroresContrato::TamanoPaginaInvalido);
+        }
+        let mut elementos = Vec::with_capacity(ids.len());
+        for id in ids {
+            match obtener(id) {
+                Some(elemento) => elementos.push(elemento),
+                None => return Err(inexistente),
+            }
+        }
+        Ok(elementos)
+    }
+
+    /// Ubica `id` en un ranking ordenado de mayor a menor `clave`, agregándolo al final si no
+    /// estaba. Se lo intercambia con sus vecinos hasta que queda en orden, así que el costo
+    /// depende de cuántos puestos se mueve y no del largo del ranking. A igual clave queda
+    /// primero el que llegó antes
+    fn reubicar_en_ranking<K: Packed + ink::scale::EncodeLike, C: Ord>(
+        ranking: &mut StorageVec<K, impl StorageKey>,
+        posiciones: &mut Mapping<K, u32, impl StorageKey>,
+        id: K,
+        clave: impl Fn(&K) -> C,
+    ) {
+        let mut posicion = match posiciones.get(&id) {
+            Some(posicion) => posicion,
+            None => {
+                let posicion = ranking.len();
+                ranking.push(&id);
+                posicion
+            }
+        };
+        let valor = clave(&id);
+        while let Some(anterior) = posicion.checked_sub(1).and_then(|i| ranking.get(i)) {
+            if clave(&anterior) >= valor {
+                break;
+            }
+            ranking.set(posicion, &anterior);
+            posiciones.insert(&anterior, &posicion);
+            posicion -= 1;
+        }
+        while let Some(siguiente) = ranking.get(posicion.saturating_add(1)) {
+            if clave(&siguiente) <= valor {
+                break;
+            }
+            ranking.set(posicion, &siguiente);
+            posiciones.insert(&siguiente, &posicion);
+            posicion += 1;
+        }
+        ranking.set(posicion, &id);
+        posiciones.insert(&id, &posicion);
+    }
+
+    /// Arma la página que empieza en `desde` a partir de todos los resultados de una consulta
+    fn paginar<T>(resultados: Vec<T>, desde: u32, tamano_pagina: u32) -> Result<Pagina<T>, ErroresContrato> {
+        let total = resultados.len() as u32;
+        let mut restantes = resultados.into_iter().skip(desde as usize);
+        paginar_indices(total, desde, tamano_pagina, |_| restantes.next())
+    }
+
+    /// Criterios para buscar publicaciones. Los campos en `None` no filtran
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone, Default)]
+    pub struct FiltroPublicaciones {
+        pub categoria: Option<String>,
+        pub id_vendedor: Option<AccountId>,
+        pub precio_min: Option<Balance>,
+        pub precio_max: Option<Balance>,
+        pub stock_min: Option<u32>,
+        pub solo_activas: bool,
+        pub reputacion_min: Option<u64>, //promedio como vendedor con 2 decimales (450 = 4,5 estrellas)
+    }
+
+    /// Orden de los resultados dentro de cada página de una búsqueda de publicaciones
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum OrdenBusqueda {
+        Precio,             //menor precio primero
+        MasRecientes,       //última publicada primero
+        ReputacionVendedor, //mejor reputación del vendedor primero
+    }
+
+    ///Estructuras y logica de Orden
+    ///Posibles estados de una Ordem
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum EstadoOrden {
+        Pendiente,
+        Enviada,      //solo lo puede modificar el vendedor
+        Recibida,     //solo lo puede modificar el comprador
+        PreCancelada, //cualquiera de las partes la propone desde Pendiente; la otra la confirma, la rechaza o abre una disputa
+        Cancelada,    //la confirma la parte que no propuso la cancelación, desde PreCancelada
+        EnDisputa,    //la abre cualquiera de las partes y solo la resuelve un árbitro
+    }
+
+    /// Motivos por los que una de las partes puede proponer cancelar una orden
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum MotivoCancelacion {
+        SinStock,
+        PedidoDelComprador,
+        ProblemaDePago,
+        DemoraEnEnvio,
+        Otro,
+    }
+
+    /// Resultado de llamar a `cancelar_orden`
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ResultadoCancelacion {
+        CancelacionIniciada,   //se propuso la cancelación, falta la otra parte
+        CancelacionConfirmada, //la orden quedó cancelada
+    }
+
+    /// Cambio de estado de una orden, tal como queda en su historial
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct TransicionOrden {
+        pub anterior: EstadoOrden,
+        pub nuevo: EstadoOrden,
+        pub actor: AccountId,    //quien hizo el cambio
+        pub bloque: BlockNumber, //bloque en que se hizo el cambio
+        pub timestamp: Timestamp,
+    }
+
+    ///Propuesta de cancelación de una orden, a la espera de la otra parte
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct SolicitudCancelacion {
+        propuesta_por: AccountId,
+        motivo: MotivoCancelacion,
+        estado_previo: EstadoOrden, //estado al que vuelve la orden si se rechaza
+    }
+
+    impl SolicitudCancelacion {
+        pub fn new(
+            propuesta_por: AccountId,
+            motivo: MotivoCancelacion,
+            estado_previo: EstadoOrden,
+        ) -> SolicitudCancelacion {
+            SolicitudCancelacion {
+                propuesta_por,
+                motivo,
+                estado_previo,
+            }
+        }
+
+        pub fn get_propuesta_por(&self) -> AccountId {
+            self.propuesta_por
+        }
+
+        pub fn get_motivo(&self) -> MotivoCancelacion {
+            self.motivo
+        }
+
+        pub fn get_estado_previo(&self) -> EstadoOrden {
+            self.estado_previo
+        }
+    }
+
+    /// Cantidad máxima de evidencias que puede aportar cada parte de una disputa
+    pub const MAX_EVIDENCIAS: usize = 10;
+
+    /// Parte a favor de la cual se resuelve una disputa
+    #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum FalloDisputa {
+        Comprador, //se cancela la orden y se reembolsa el pago
+        Vendedor,  //se da la orden por recibida y se libera el pago
+    }
+
+    ///Estructura de una disputa sobre una orden
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Disputa {
+        abierta_por: AccountId,
+        estado_previo: EstadoOrden, //estado de la orden al abrir la disputa
+        evidencia_comprador: Vec<Hash>,
+        evidencia_vendedor: Vec<Hash>,
+        fallo: Option<FalloDisputa>,
+        arbitro: Option<AccountId>,
+    }
+
+    impl Disputa {
+        pub fn new(abierta_por: AccountId, estado_previo: EstadoOrden) -> Disputa {
+            Disputa {
+                abierta_por,
+                estado_previo,
+                evidencia_comprador: Vec::new(),
+                evidencia_vendedor: Vec::new(),
+                fallo: None,
+                arbitro: None,
+            }
+        }
+
+        pub fn get_abierta_por(&self) -> AccountId {
+            self.abierta_por
+        }
+
+        pub fn get_estado_previo(&self) -> EstadoOrden {
+            self.estado_previo
+        }
+
+        pub fn get_evidencia_comprador(&self) -> Vec<Hash> {
+            self.evidencia_comprador.clone()
+        }
+
+        pub fn get_evidencia_vendedor(&self) -> Vec<Hash> {
+            self.evidencia_vendedor.clone()
+        }
+
+        pub fn get_fallo(&self) -> Option<FalloDisputa> {
+            self.fallo
+        }
+
+        pub fn get_arbitro(&self) -> Option<AccountId> {
+            self.arbitro
+        }
+    }
+
+    /// Largo máximo en bytes del texto de una reseña y de su respuesta
+    pub const MAX_LARGO_RESENA: usize = 280;
+
+    /// Quita los espacios de los extremos y verifica que el texto no quede vacío ni supere `MAX_LARGO_RESENA`
+    fn validar_texto_resena(texto: String) -> Result<String, ErroresContrato> {
+        let texto = texto.trim();
+        if texto.is_empty() || texto.len() > MAX_LARGO_RESENA {
+            return Err(ErroresContrato::ResenaInvalida);
+        }
+        Ok(String::from(texto))
+    }
+
+    ///Reseña escrita que deja una de las partes de una orden al calificarla
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Resena {
+        id: u32,
+        id_orden: u32,
+        autor: AccountId,
+        destinatario: AccountId, //usuario calificado, el único que puede responder
+        puntaje: u8,
+        texto: String,
+        respuesta: Option<String>,
+        creada_en: Timestamp,
+    }
+
+    impl Resena {
+        pub fn get_id(&self) -> u32 {
+            self.id
+        }
+
+        pub fn get_id_orden(&self) -> u32 {
+            self.id_orden
+        }
+
+        pub fn get_autor(&self) -> AccountId {
+            self.autor
+        }
+
+        pub fn get_destinatario(&self) -> AccountId {
+            self.destinatario
+        }
+
+        pub fn get_puntaje(&self) -> u8 {
+            self.puntaje
+        }
+
+        pub fn get_texto(&self) -> String {
+            self.texto.clone()
+        }
+
+        pub fn get_respuesta(&self) -> Option<String> {
+            self.respuesta.clone()
+        }
+
+        pub fn get_creada_en(&self) -> Timestamp {
+            self.creada_en
+        }
+    }
+
+    /// Plazo por defecto para revelar calificaciones desde la primera: 7 días en milisegundos
+    pub const PLAZO_REVELACION_DEFAULT: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+    ///Calificaciones de una orden que todavía no se hicieron públicas. Se guardan aparte de la
+    ///orden para que no se puedan leer con los mensajes de consulta antes de revelarse
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct CalificacionesOcultas {
+        cal_vendedor: Option<u8>,
+        cal_comprador: Option<u8>,
+        primera_en: Timestamp, //timestamp de la primera calificación, desde el que corre el plazo
+        cuenta: bool,          //si la orden supera el valor mínimo y suma a la reputación
+        revelada: bool,
+    }
+
+    /// Cantidad máxima de líneas que puede tener un carrito
+    pub const MAX_ITEMS_CARRITO: usize = 20;
+
+    ///Línea del carrito de un comprador, el precio se fija recién al confirmar
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct ItemCarrito {
+        id_publicacion: u32,
+        cantidad: u32,
+    }
+
+    impl ItemCarrito {
+        pub fn new(id_publicacion: u32, cantidad: u32) -> ItemCarrito {
+            ItemCarrito {
+                id_publicacion,
+                cantidad,
+            }
+        }
+
+        pub fn get_id_publicacion(&self) -> u32 {
+            self.id_publicacion
+        }
+
+        pub fn get_cantidad(&self) -> u32 {
+            self.cantidad
+        }
+    }
+
+    ///Línea de una orden, con el precio unitario vigente al momento de la compra
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct LineaOrden {
+        id_publicacion: u32,
+        cantidad: u32,
+        precio_unitario: Balance,
+    }
+
+    impl LineaOrden {
+        pub fn new(id_publicacion: u32, cantidad: u32, precio_unitario: Balance) -> LineaOrden {
+            LineaOrden {
+                id_publicacion,
+                cantidad,
+                precio_unitario,
+            }
+        }
+
+        pub fn get_id_publicacion(&self) -> u32 {
+            self.id_publicacion
+        }
+
+        pub fn get_cantidad(&self) -> u32 {
+            self.cantidad
+        }
+
+        pub fn get_precio_unitario(&self) -> Balance {
+            self.precio_unitario
+        }
+
+        /// Devuelve el precio unitario multiplicado por la cantidad de la línea
+        pub fn get_subtotal(&self) -> Result<Balance, ErroresContrato> {
+            self.precio_unitario
+                .checked_mul(self.cantidad as u128)
+                .ok_or(ErroresContrato::ErrorMultiplicacion)
+        }
+    }
+
+    /// Plazo por defecto para enviar una orden pendiente: 7 días en milisegundos
+    pub const PLAZO_ENVIO_DEFAULT: Timestamp = 7 * 24 * 60 * 60 * 1000;
+
+    /// Plazo por defecto para confirmar la recepción de una orden enviada: 14 días en milisegundos
+    pub const PLAZO_CONFIRMACION_DEFAULT: Timestamp = 14 * 24 * 60 * 60 * 1000;
+
+    /// Máximo por defecto de calificaciones de un usuario a una misma contraparte dentro de la ventana
+    pub const MAX_CALIFICACIONES_POR_PAR_DEFAULT: u32 = 3;
+
+    /// Ventana por defecto para el máximo de calificaciones por par: 30 días en milisegundos
+    pub const VENTANA_CALIFICACIONES_DEFAULT: Timestamp = 30 * 24 * 60 * 60 * 1000;
+
+    /// Par de usuarios de una calificación: (calificador, calificado)
+    pub type ParCalificacion = (AccountId, AccountId);
+
+    /// Posición dentro de un índice por usuario: (usuario, posición)
+    pub type PosicionUsuario = (AccountId, u32);
+
+    ///Estructura de orden
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Clone)]
+    pub struct Orden {
+        id: u32,
+        lineas: Vec<LineaOrden>,
+        id_vendedor: AccountId,
+        id_comprador: AccountId,
+        status: EstadoOrden,
+        precio_total: Balance,
+        cal_vendedor: Option<u8>,  //calificacion que recibe el vendedor
+        cal_comprador: Option<u8>, //calificacion que recibe el comprador
+        disputa: Option<Disputa>,
+        cancelacion: Option<SolicitudCancelacion>,
+        creada_en: Timestamp,          //timestamp del bloque en que se creó
+        enviada_en: Option<Timestamp>, //timestamp del bloque en que se envió
+    }
+
+    impl Orden {
+        ///crea una nueva orden
+        pub fn new(
+            id: u32,
+            lineas: Vec<LineaOrden>,
+            id_vendedor: AccountId,
+            id_comprador: AccountId,
+            precio_total: Balance,
+            creada_en: Timestamp,
+        ) -> Orden {
+            Orden {
+                id,
+                lineas,
+                id_vendedor,
+                id_comprador,
+                status: EstadoOrden::Pendiente,
+                precio_total,
+                cal_vendedor: None,
+                cal_comprador: None,
+                disputa: None,
+                cancelacion: None,
+                creada_en,
+                enviada_en: None,
+            }
+        }
+
+        pub fn get_id(&self) -> u32 {
+            self.id
+        }
+
+        /// Devuelve la cantidad total de unidades sumando todas las líneas
+        pub fn get_cantidad(&self) -> u32 {
+            self.lineas
+                .iter()
+                .fold(0u32, |total, linea| total.saturating_add(linea.cantidad))
+        }
+
+        pub fn get_lineas(&self) -> Vec<LineaOrden> {
+            self.lineas.clone()
+        }
+
+        pub fn get_status(&self) -> EstadoOrden {
+            self.status
+        }
+
+        pub fn get_id_comprador(&self) -> AccountId {
+            self.id_comprador
+        }
+
+        pub fn get_id_vendedor(&self) -> AccountId {
+            self.id_vendedor
+        }
+
+        pub fn get_precio_total(&self) -> Balance {
+            self.precio_total
+        }
+
+        pub fn get_calificacion_vendedor(&self) -> Option<u8> {
+            self.cal_vendedor
+        }
+
+        pub fn get_calificacion_comprador(&self) -> Option<u8> {
+            self.cal_comprador
+        }
+
+        pub fn get_disputa(&self) -> Option<Disputa> {
+            self.disputa.clone()
+        }
+
+        pub fn get_cancelacion(&self) -> Option<SolicitudCancelacion> {
+            self.cancelacion.clone()
+        }
+
+        pub fn get_creada_en(&self) -> Timestamp {
+            self.creada_en
+        }
+
+        pub fn get_enviada_en(&self) -> Option<Timestamp> {
+            self.enviada_en
+        }
+    }
+}
+
+pub mod prelude {
+    pub use super::contract::{
+        Sistema, SistemaRef, Categoria, Orden, Usuario, Rating, Producto, Publicacion, EstadoOrden,
+        Rol, ErroresContrato, Disputa, FalloDisputa, ItemCarrito, LineaOrden, MotivoCancelacion, SolicitudCancelacion,
+        ResultadoCancelacion, Reputacion, ReputacionUsuario, Pagina, FiltroPublicaciones, OrdenBusqueda,
+        CantidadOrdenes, TransicionOrden, Resena, VentasAcumuladas, MAX_TAMANO_PAGINA, MAX_REVISADAS_POR_BUSQUEDA,
+        PLAZO_REVELACION_DEFAULT
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::*;
+
+    use ink::{
+        env::DefaultEnvironment,
+        primitives::{AccountId, Hash},
+    };
+    use ink_e2e::{account_id, AccountKeyring};
+
+    fn setup_sistema() -> Sistema {
+        Sistema::new()
+    }
+
+    fn id_comprador() -> <DefaultEnvironment as ink::env::Environment>::AccountId {
+        account_id(AccountKeyring::Alice)
+    }
+
+    fn id_vendedor() -> <DefaultEnvironment as ink::env::Environment>::AccountId {
+        account_id(AccountKeyring::Bob)
+    }
+
+    fn set_caller(caller: AccountId) {
+        ink::env::test::set_caller::<DefaultEnvironment>(caller);
+    }
+
+    /// Simula que el caller actual envía `monto` al contrato junto con la próxima llamada
+    fn pagar(monto: u128) {
+        // El entorno off-chain exige un saldo mínimo (depósito existencial) al fondear cuentas
+        const SALDO_MINIMO: u128 = 1_000_000;
+        let caller = ink::env::caller::<DefaultEnvironment>();
+        let saldo_caller = saldo(caller).max(SALDO_MINIMO);
+        ink::env::test::set_account_balance::<DefaultEnvironment>(caller, saldo_caller + monto);
+        ink::env::test::transfer_in::<DefaultEnvironment>(monto);
+    }
+
+    fn saldo(cuenta: AccountId) -> u128 {
+        ink::env::test::get_account_balance::<DefaultEnvironment>(cuenta).unwrap_or_default()
+    }
+
+    fn saldo_contrato() -> u128 {
+        saldo(ink::env::test::callee::<DefaultEnvironment>())
+    }
+
+    /// Devuelve, en orden, los eventos de tipo `E` registrados en el entorno de test
+    fn eventos_emitidos<E: ink::env::Event + scale::Decode>() -> Vec<E> {
+        ink::env::test::recorded_events()
+            .filter(|evento| {
+                evento.topics.first().map(|t| t.as_slice())
+                    == E::SIGNATURE_TOPIC.as_ref().map(|t| t.as_slice())
+            })
+            .map(|evento| E::decode(&mut &evento.data[..]).expect("evento mal codificado"))
+            .collect()
+    }
+
+    /// Codifica un valor como lo hace ink! al usarlo de topic (rellenado a 32 bytes)
+    fn topic<T: scale::Encode>(valor: &T) -> Vec<u8> {
+        let mut resultado = valor.encode();
+        resultado.resize(32, 0);
+        resultado
+    }
+
+    fn build_testing_accounts() -> (AccountId, AccountId) {
+        let id_comprador = id_comprador();
+        let id_vendedor = id_vendedor();
+        (id_comprador, id_vendedor)
+    }
+
+    fn build_testing_setup() -> (Sistema, AccountId, AccountId) {
+        let mut app = setup_sistema();
+        let (user_1, user_2) = build_testing_accounts();
+
+        app._registrar_usuario(
+            user_1,
+            "user_name_1".to_string(),
+            "user_email_1".to_string(),
+            Rol::Comprador,
+        )
+        .expect("No se pudo registrar el usuario");
+        app._registrar_usuario(
+            user_2,
+            "user_name_2".to_string(),
+            "user_email_2".to_string(),
+            Rol::Vendedor,
+        )
+        .expect("No se pudo registrar el usuario");
+
+        (app, user_1, user_2)
+    }
+
+    //fn de test de agus olthoff
+
+    fn registrar_comprador(
+        sistema: &mut Sistema,
+        id: <DefaultEnvironment as ink::env::Environment>::AccountId,
+    ) {
+        sistema
+            ._registrar_usuario(
+                id,
+                "Comprador".into(),
+                "comprador@gmail.com".into(),
+                Rol::Comprador,
+            )
+            .unwrap();
+    }
+    fn registrar_vendedor(
+        sistema: &mut Sistema,
+        id: <DefaultEnvironment as ink::env::Environment>::AccountId,
+    ) {
+        sistema
+            ._registrar_usuario(
+                id,
+                "Vendedor".into(),
+                "vendedor@gmail.com".into(),
+                Rol::Vendedor,
+            )
+            .unwrap();
+    }
+
+    fn agregar_categoria(sistema: &mut Sistema, nombre: &str) {
+        sistema._registrar_categoria(nombre.into()).unwrap();
+    }
+
+    fn contrato_con_categorias_cargada() -> Sistema {
+        let mut sist = Sistema::new();
+        for i in 0..10 {
+            let _ = sist._registrar_categoria(format!("categoria {}", i));
+        }
+        sist
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_exito() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Zapatillas".into(), "desc".into(), "Ropa".into(), 10)
+            .unwrap();
+
+        let esperado = Publicacion::new(0, 0, id, 5, 1000);
+        let res = sistema._crear_publicacion(0, id, 5, 1000);
+
+        assert!(res.is_ok());
+
+        let retorno = sistema._listar_publicaciones_propias(id, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(esperado, retorno);
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_falla_sin_rol_vendedor() {
+        let mut sistema = setup_sistema();
+        let id: AccountId = id_vendedor();
+        let id2: AccountId = id_comprador();
+
+        registrar_vendedor(&mut sistema, id);
+        registrar_comprador(&mut sistema, id2);
+
+        set_caller(id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Remera".into(), "desc".into(), "Ropa".into(), 10)
+            .unwrap();
+
+        set_caller(id2);
+        let res = sistema._crear_publicacion(0, id2, 5, 200);
+        assert!(matches!(res, Err(ErroresContrato::RolNoApropiado)));
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_falla_producto_inexistente() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+
+        let res = sistema._crear_publicacion(99, id, 3, 500); // id_producto inválido
+        assert!(matches!(res, Err(ErroresContrato::ProductoInexistente)));
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_falla_stock_insuficiente() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Pantalon".into(), "desc".into(), "Ropa".into(), 2)
+            .unwrap();
+
+        let res = sistema._crear_publicacion(0, id, 5, 300); // pide más stock del disponible
+        assert!(matches!(res, Err(ErroresContrato::StockInsuficiente)));
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_falla_usuario_inexistente() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor(); // Nunca lo registramos
+
+        let res = sistema._crear_publicacion(0, id, 5, 1000);
+        assert!(matches!(res, Err(ErroresContrato::UsuarioNoExiste)));
+    }
+
+    #[ink::test]
+    fn test_descontar_stock_publicacion_exito() {
+        let mut sistema = setup_sistema();
+        let id_vendedor = id_vendedor();
+        let id_comprador = id_comprador();
+        set_caller(id_vendedor);
+
+        registrar_vendedor(&mut sistema, id_vendedor);
+        registrar_comprador(&mut sistema, id_comprador);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(
+                id_vendedor,
+                "Zapatillas".into(),
+                "desc".into(),
+                "Ropa".into(),
+                10,
+            )
+            .unwrap();
+        sistema._crear_publicacion(0, id_vendedor, 5, 1000).unwrap();
+
+        // El descuento se hace automáticamente al crear la orden
+        let res = sistema._crear_orden(0, id_comprador, 2, 2000);
+        assert!(res.is_ok());
+
+        // Verificar que el stock de la publicación se redujo
+        let publicaciones = sistema._listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(publicaciones[0].stock(), 3);
+    }
+
+    #[ink::test]
+    fn test_descontar_stock_publicacion_falla_publicacion_inexistente() {
+        let mut sistema = setup_sistema();
+        let id_vendedor = id_vendedor();
+        let id_comprador = id_comprador();
+
+        registrar_vendedor(&mut sistema, id_vendedor);
+        registrar_comprador(&mut sistema, id_comprador);
+
+        // Intentar crear orden con publicación inexistente
+        let res = sistema._crear_orden(99, id_comprador, 1, 0);
+        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
+    }
+
+    #[ink::test]
+    fn test_descontar_stock_publicacion_falla_stock_insuficiente() {
+        let mut sistema = setup_sistema();
+        let id_vendedor = id_vendedor();
+        let id_comprador = id_comprador();
+        set_caller(id_vendedor);
+
+        registrar_vendedor(&mut sistema, id_vendedor);
+        registrar_comprador(&mut sistema, id_comprador);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(
+                id_vendedor,
+                "Zapatillas".into(),
+                "desc".into(),
+                "Ropa".into(),
+                10,
+            )
+            .unwrap();
+        sistema._crear_publicacion(0, id_vendedor, 3, 1000).unwrap();
+
+        // Intentar crear orden con más cantidad de la disponible en la publicación
+        let res = sistema._crear_orden(0, id_comprador, 5, 5000);
+        assert!(matches!(res, Err(ErroresContrato::StockInsuficiente)));
+    }
+
+    #[ink::test]
+    fn test_get_precio_unitario_ok() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        set_caller(id);
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Campera".into(), "desc".into(), "Ropa".into(), 10)
+            .unwrap();
+        sistema._crear_publicacion(0, id, 4, 1234).unwrap();
+
+        let res = sistema.get_precio_unitario(0);
+        assert!(matches!(res, Ok(1234)));
+    }
+
+    #[ink::test]
+    fn test_get_precio_unitario_falla_publicacion_inexistente() {
+        let sistema = setup_sistema(); // no hace falta crear nada
+
+        let res = sistema.get_precio_unitario(42);
+        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
+    }
+
+    #[ink::test]
+    fn test_get_id_vendedor_ok() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        set_caller(id);
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Pantalón".into(), "desc".into(), "Ropa".into(), 8)
+            .unwrap();
+        sistema._crear_publicacion(0, id, 5, 999).unwrap();
+
+        let res = sistema.get_id_vendedor(0);
+        assert!(matches!(res, Ok(valor) if valor == id));
+    }
+
+    #[ink::test]
+    fn test_get_id_vendedor_falla_publicacion_inexistente() {
+        let sistema = setup_sistema();
+
+        let res = sistema.get_id_vendedor(42);
+        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
+    }
+
+    #[ink::test]
+    fn test_listar_productos_vacio() {
+        let sistema = setup_sistema();
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(productos.len(), 0);
+    }
+
+    #[ink::test]
+    fn test_listar_productos_uno() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        set_caller(id);
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+        sistema
+            ._crear_producto(id, "Buzo".into(), "desc".into(), "Ropa".into(), 5)
+            .unwrap();
+
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(productos.len(), 1);
+
+        // Si `Producto` implementa PartialEq:
+        let esperado = Producto::new(0, id, "Buzo".into(), "desc".into(), 0, 5);
+        assert_eq!(productos[0], esperado);
+    }
+
+    #[ink::test]
+    fn test_listar_productos_varios() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        set_caller(id);
+
+        registrar_vendedor(&mut sistema, id);
+        sistema._registrar_categoria("Ropa".into()).unwrap();
+
+        sistema
+            ._crear_producto(id, "Buzo".into(), "desc".into(), "Ropa".into(), 5)
+            .unwrap();
+        sistema
+            ._crear_producto(id, "Campera".into(), "desc".into(), "Ropa".into(), 8)
+            .unwrap();
+
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(productos.len(), 2);
+    }
+
+    #[ink::test]
+    fn test_categoria_agregar_nueva() {
+        let mut sist = setup_sistema();
+
+        assert!(sist._listar_categorias(0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+
+        let result = sist._registrar_categoria("Limpieza".to_string());
+
+        assert_eq!(result, Ok(0));
+        assert_eq!(sist._listar_categorias(0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 1);
+    }
+
+    #[ink::test]
+    fn test_categoria_agregar_duplicada() {
+        let mut sist = contrato_con_categorias_cargada();
+
+        assert!(!sist._listar_categorias(0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+        let result = sist._registrar_categoria("categoria 1".to_string());
+        assert_eq!(
+            result,
+            Err(ErroresContrato::CategoriaYaExistente),
+            "deberia tirar error que ya existe la categoria"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_formateo_nombre() {
+        let mut sist = contrato_con_categorias_cargada();
+
+        //nombre similar
+        let result = sist._registrar_categoria("CaTEgORia 1".to_string());
+        assert_eq!(
+            result,
+            Err(ErroresContrato::CategoriaYaExistente),
+            "deberia devolver que ya existe la categoria"
+        );
+
+        //nombre vacio
+        let result = sist._registrar_categoria(String::new());
+        assert_eq!(
+            result,
+            Err(ErroresContrato::NombreCategoriaVacio),
+            "deberia devolver que el nombre de la categoria esta vacia"
+        );
+
+        //nombres con unicode
+        let result = sist._registrar_categoria("не ваше дела идите на хуй".to_string());
+        assert_eq!(
+            result,
+            Ok(10),
+            "deberia poder manejar alfabeto cirilico"
+        );
+        let result = sist._registrar_categoria("የክፋት እቅድ".to_string());
+        assert_eq!(
+            result,
+            Ok(11),
+            "deberia poder manejar alfabeto amharico"
+        );
+        let result = sist._registrar_categoria("プログラミングが好きです".to_string());
+        assert_eq!(
+            result,
+            Ok(12),
+            "deberia poder manejar kanji, katakana e hiragana"
+        );
+        let result = sist._registrar_categoria("사랑해요".to_string());
+        assert_eq!(
+            result,
+            Ok(13),
+            "deberia poder manejar hangul"
+        );
+
+        //nombre con leading y trailing whitespace
+        let result = sist._registrar_categoria(
+            "          alguna categoria                                                "
+                .to_string(),
+        );
+        assert_eq!(
+            result,
+            Ok(14),
+            "deberia eliminar espacios en blanco al principio y final del string"
+        );
+
+        //nombre truncado
+
+        let result = sist._registrar_categoria(
+            "You know what they call a  Quarter Pounder with Cheese in Paris?
+
+            [JULES]
+            They don't call it a Quarter Pounder with Cheese?
+
+            [VINCENT]
+            No, they got the metric system there, they wouldn't know what the fuck a Quarter Pounder is.
+
+            [JULES]
+            Then what do they call it?
+
+            [VINCENT]
+            They call it Royale with Cheese.
+
+            [JULES]
+            Royale with Cheese. What do they call a Big Mac?
+
+            [VINCENT]
+            Big Mac's a Big Mac, but they call it Le Big Mac.
+
+            [JULES]
+            Le big Mac! Ahhaha, what do they call a Whopper?
+
+            [VINCENT]
+            I dunno, I didn't go into a Burger King.".to_string()
+        );
+        assert_eq!(
+            result,
+            Ok(15),
+            "deberia poder manejar nombres muy largos, truncandolos en 100 caracteres"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_indice_correcto_por_nombre() {
+        let sist = contrato_con_categorias_cargada();
+        assert_eq!(
+            sist.get_categoria_by_name("categoria 9"),
+            Ok(9),
+            "deberia devolver el indice correcto"
+        );
+        assert_eq!(
+            sist.get_categoria_by_name("categoria 3"),
+            Ok(3),
+            "deberia devolver el indice correcto"
+        );
+        assert_eq!(
+            sist.get_categoria_by_name("      categoria 4       "),
+            Ok(4),
+            "deberia devolver el indice correcto incluso con whitespace"
+        );
+        assert_eq!(
+            sist.get_categoria_by_name("cAtEGoRiA 5"),
+            Ok(5),
+            "deberia devolver el indice correcto incluso con mayusculas"
+        );
+
+        assert_eq!(
+            sist.get_categoria_by_name("Electrodomesticos"),
+            Err(ErroresContrato::CategoriaInexistente),
+            "deberia devolver que no encuentra la categoria"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_get_categoria_whitespaces() {
+        let sist = contrato_con_categorias_cargada();
+        assert_eq!(
+            sist.get_categoria_by_name("      categoria 4       "),
+            Ok(4),
+            "deberia devolver el indice correcto incluso con whitespace"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_get_categoria_case_sensitivity() {
+        let sist = contrato_con_categorias_cargada();
+        assert_eq!(
+            sist.get_categoria_by_name("cAtEGoRiA 5"),
+            Ok(5),
+            "deberia devolver el indice correcto incluso con mayusculas"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_get_categoria_inexistente() {
+        let sist = contrato_con_categorias_cargada();
+        assert_eq!(
+            sist.get_categoria_by_name("Electrodomesticos"),
+            Err(ErroresContrato::CategoriaInexistente),
+            "deberia devolver que no encuentra la categoria"
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_clean_name() {
+        let sist = setup_sistema();
+        assert_eq!(
+            sist.clean_cat_name("Electrodomésticos"),
+            Ok("electrodomésticos".to_string())
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_clean_name_whitespaces() {
+        let sist = setup_sistema();
+        assert_eq!(
+            sist.clean_cat_name("      cocina        "),
+            Ok("cocina".to_string())
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_clean_name_empty() {
+        let sist = setup_sistema();
+        assert_eq!(
+            sist.clean_cat_name(""),
+            Err(ErroresContrato::NombreCategoriaVacio)
+        );
+    }
+
+    #[ink::test]
+    fn test_categoria_clean_name_max_characters() {
+        let sist = setup_sistema();
+        assert_eq!(sist.clean_cat_name("
+            You know what they call a  Quarter Pounder with Cheese in Paris?
+
+            [JULES]
+            They don't call it a Quarter Pounder with Cheese?
+
+            [VINCENT]
+            No, they got the metric system there, they wouldn't know what the fuck a Quarter Pounder is.
+
+            [JULES]
+            Then what do they call it?
+
+            [VINCENT]
+            They call it Royale with Cheese.
+
+            [JULES]
+            Royale with Cheese. What do they call a Big Mac?
+
+            [VINCENT]
+            Big Mac's a Big Mac, but they call it Le Big Mac.
+
+            [JULES]
+            Le big Mac! Ahhaha, what do they call a Whopper?
+
+            [VINCENT]
+            I dunno, I didn't go into a Burger King."
+        ),
+            Ok("you know what they call a  quarter pounder with cheese in paris?
+
+            [jules]
+            th".to_string())
+        );
+    }
+
+    //_crear_producto
+    //si el producto es exitoso (no duplicado y es vendedor)
+    #[ink::test]
+    fn test_crear_producto_exitoso() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+
+        assert!(res.is_ok());
+
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(productos.len(), 1);
+
+        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+        assert_eq!(productos[0], esperado);
+    }
+
+    //test falla por que el producto esta duplicado
+    #[ink::test]
+    fn test_crear_producto_falla_si_ya_esxiste() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+        assert!(res.is_ok());
+
+        let res2 =
+            sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+        assert!(res2.is_err());
+        assert_eq!(res2.unwrap_err(), ErroresContrato::ProductoYaExistente);
+    }
+    //test falla por que el usuario no es vendedor
+    #[ink::test]
+    fn test_crear_producto_falla_si_es_comprador() {
+        let mut sistema = setup_sistema();
+        let id = id_comprador();
+
+        registrar_comprador(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err(), ErroresContrato::UsuarioNoEsVendedor);
+    }
+
+    //producto_existe (caso existente)
+    #[ink::test]
+    fn test_producto_existe() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        let _ = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+
+        // Armamos el producto igual al creado
+        let producto = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+
+        let existe = sistema.producto_existe(&producto);
+        assert!(existe);
+    }
+    //producto_existe (caso inexistente)
+    #[ink::test]
+    fn test_producto_no_existe() {
+        let sistema = setup_sistema();
+        let id = id_vendedor();
+        let producto = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+
+        let existe = sistema.producto_existe(&producto);
+        assert!(!existe);
+    }
+
+    #[ink::test]
+    fn test_mismo_producto_distintos_vendedores() {
+        let mut sistema = setup_sistema();
+        let vendedor_1 = id_vendedor();
+        let vendedor_2 = AccountId::from([0x40; 32]);
+
+        registrar_vendedor(&mut sistema, vendedor_1);
+        sistema
+            ._registrar_usuario(vendedor_2, "otro".into(), "otro@gmail.com".into(), Rol::Vendedor)
+            .unwrap();
+        agregar_categoria(&mut sistema, "Hogar");
+
+        let id_1 = sistema
+            ._crear_producto(vendedor_1, "Mate".into(), "desc".into(), "Hogar".into(), 10)
+            .unwrap();
+        let id_2 = sistema
+            ._crear_producto(vendedor_2, "Mate".into(), "desc".into(), "Hogar".into(), 5)
+            .unwrap();
+        assert_ne!(id_1, id_2);
+
+        // El mismo vendedor no puede repetirlo, aunque cambie mayúsculas o espacios
+        assert_eq!(
+            sistema._crear_producto(vendedor_1, " MATE ".into(), "desc".into(), "hogar".into(), 3),
+            Err(ErroresContrato::ProductoYaExistente)
+        );
+
+        let propios = sistema._listar_productos_vendedor(vendedor_2, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(propios.len(), 1);
+        assert_eq!(propios[0].get_id(), id_2);
+        assert_eq!(propios[0].get_id_vendedor(), vendedor_2);
+        assert!(sistema._listar_productos_vendedor(id_comprador(), 0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+    }
+
+    #[ink::test]
+    fn test_publicar_producto_ajeno_requiere_compartirlo() {
+        let mut sistema = setup_sistema();
+        let dueno = id_vendedor();
+        let otro = AccountId::from([0x40; 32]);
+
+        registrar_vendedor(&mut sistema, dueno);
+        sistema
+            ._registrar_usuario(otro, "otro".into(), "otro@gmail.com".into(), Rol::Vendedor)
+            .unwrap();
+        agregar_categoria(&mut sistema, "Hogar");
+        let id_prod = sistema
+            ._crear_producto(dueno, "Mate".into(), "desc".into(), "Hogar".into(), 10)
+            .unwrap();
+
+        assert_eq!(
+            sistema._crear_publicacion(id_prod, otro, 5, 100),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+        assert_eq!(
+            sistema._compartir_producto(id_prod, otro, true),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+
+        sistema._compartir_producto(id_prod, dueno, true).unwrap();
+        assert!(sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_prod as usize].es_compartido());
+        assert_eq!(eventos_emitidos::<ProductoCompartido>().len(), 1);
+
+        // El otro vendedor publica con su propio stock, sin tocar el del dueño
+        assert!(sistema._crear_publicacion(id_prod, otro, 50, 100).is_ok());
+        assert_eq!(sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_prod as usize].get_cantidad(), 10);
+
+        sistema._compartir_producto(id_prod, dueno, false).unwrap();
+        assert_eq!(
+            sistema._crear_publicacion(id_prod, otro, 5, 100),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+    }
+
+    // Test de descuento de stock de producto a través de crear_publicacion
+    #[ink::test]
+    fn test_descontar_stock_producto_exitoso() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        sistema
+            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10)
+            .unwrap();
+
+        // El descuento se hace automáticamente al crear la publicación
+        let res = sistema._crear_publicacion(0, id, 3, 1000);
+        assert!(res.is_ok());
+
+        // Verificar que el stock del producto se redujo
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 7);
+        assert_eq!(productos[0], esperado);
+    }
+    // Test de falla al intentar crear publicacion con producto inexistente
+    #[ink::test]
+    fn test_descontar_stock_falla_producto_inexistente() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        registrar_vendedor(&mut sistema, id);
+        // Intentar crear publicación con producto inexistente
+        let res = sistema._crear_publicacion(99, id, 1, 1000);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err(), ErroresContrato::ProductoInexistente);
+    }
+    // Test de falla por stock insuficiente al crear publicacion
+    #[ink::test]
+    fn test_descontar_stock_falla_stock_insuficiente() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        sistema
+            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 5)
+            .unwrap();
+
+        // Intentar crear publicación con más stock del disponible
+        let res = sistema._crear_publicacion(0, id, 10, 1000);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err(), ErroresContrato::StockInsuficiente);
+    }
+    #[ink::test]
+    fn test_listar_productos_con_productos() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        sistema
+            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10)
+            .unwrap();
+
+        let productos = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(productos.len(), 1);
+
+        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+        assert_eq!(productos[0], esperado); //modificado
+    }
+    /// Test para listar productos sin productos registrado
+    #[ink::test]
+    fn test_listar_productos_sin_productos() {
+        let sistema = setup_sistema();
+
+        let res = sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert!(res.is_empty());
+    }
+
+    #[ink::test]
+    fn registra_usuario_correctamente() {
+        let mut app = setup_sistema();
+        let (id_comprador, _) = build_testing_accounts();
+
+        assert!(
+            app._registrar_usuario(
+                id_comprador,
+                "user_name".to_string(),
+                "user_email".to_string(),
+                Rol::Ambos,
+            )
+            .is_ok(),
+            "Se esperaba que se registre un usuario"
+        );
+
+        let user_created = app.listar_usuarios(0, 1).unwrap().elementos[0].clone();
+
+        assert!(
+            !user_created.get_name().is_empty(),
+            "nombre de usuario no debe estar vacio"
+        );
+        assert!(
+            !user_created.get_mail().is_empty(),
+            "email de usuario no debe estar vacio"
+        );
+
+        assert_eq!(
+            user_created.get_name(),
+            "user_name".to_string(),
+            "el usuario deberia poseer el nombre que fue ingresado"
+        );
+        assert_eq!(
+            user_created.get_mail(),
+            "user_email".to_string(),
+            "el usuario deberia poseer el email que fue ingresado"
+        );
+    }
+
+    #[ink::test]
+    fn registra_usuario_nombre_de_usuario_vacio() {
+        let mut app = setup_sistema();
+        let (id_comprador, _) = build_testing_accounts();
+        assert_eq!(
+            app._registrar_usuario(
+                id_comprador,
+                String::new(),
+                "user_email_1".to_string(),
+                Rol::Comprador,
+            ),
+            Err(ErroresContrato::NombreUsuarioVacio)
+        )
+    }
+
+    #[ink::test]
+    fn registra_usuario_mail_vacio() {
+        let mut app = setup_sistema();
+        let (id_comprador, _) = build_testing_accounts();
+        assert_eq!(
+            app._registrar_usuario(
+                id_comprador,
+                "user_name".to_string(),
+                String::new(),
+                Rol::Comprador,
+            ),
+            Err(ErroresContrato::MailUsuarioVacio)
+        )
+    }
+
+    #[ink::test]
+    fn registra_usuario_nombre_ya_existente() {
+        let mut app = setup_sistema();
+        let id = id_vendedor();
+
+        app._registrar_usuario(
+            id,
+            "Vendedor".into(),
+            "vendedor@gmail.com".into(),
+            Rol::Vendedor,
+        )
+        .unwrap();
+        assert_eq!(
+            app._registrar_usuario(
+                id,
+                "Vendedor".into(),
+                "emailvendedor@gmail.com".into(),
+                Rol::Vendedor,
+            ),
+            Err(ErroresContrato::UsuarioYaExistente)
+        )
+    }
+
+    #[ink::test]
+    fn registra_usuario_mail_ya_existente() {
+        let mut app = setup_sistema();
+        let (id_comprador, _) = build_testing_accounts();
+        app._registrar_usuario(
+            id_comprador,
+            "user_name".to_string(),
+            "user_email".to_string(),
+            Rol::Comprador,
+        )
+        .expect("No se pudo registrar el usuario");
+
+        assert_eq!(
+            app._registrar_usuario(
+                id_comprador,
+                "user_name11".to_string(),
+                "user_email".to_string(),
+                Rol::Vendedor,
+            ),
+            Err(ErroresContrato::MailYaExistente)
+        )
+    }
+
+    #[ink::test]
+    fn test_registra_usuario_duplicados_distintas_cuentas() {
+        let (mut app, _, _) = build_testing_setup();
+        let otra_cuenta = AccountId::from([0x30; 32]);
+
+        // Mismo nombre desde otra cuenta
+        assert_eq!(
+            app._registrar_usuario(
+                otra_cuenta,
+                "user_name_1".to_string(),
+                "otro_email".to_string(),
+                Rol::Comprador,
+            ),
+            Err(ErroresContrato::UsuarioYaExistente)
+        );
+        // Mismo mail desde otra cuenta, con mayúsculas y espacios
+        assert_eq!(
+            app._registrar_usuario(
+                otra_cuenta,
+                "otro_nombre".to_string(),
+                "  USER_EMAIL_2 ".to_string(),
+                Rol::Comprador,
+            ),
+            Err(ErroresContrato::MailYaExistente)
+        );
+        // Nombre con distinta capitalización
+        assert_eq!(
+            app._registrar_usuario(
+                otra_cuenta,
+                "User_Name_2".to_string(),
+                "otro_email".to_string(),
+                Rol::Comprador,
+            ),
+            Err(ErroresContrato::UsuarioYaExistente)
+        );
+        assert_eq!(app._get_cantidad_usuarios(), 2);
+
+        // Las búsquedas ignoran mayúsculas y espacios
+        assert_eq!(
+            app.get_usuario_by_username(" USER_NAME_2").unwrap().get_mail(),
+            "user_email_2"
+        );
+        assert_eq!(
+            app.get_usuario_by_username("inexistente").err(),
+            Some(ErroresContrato::UsuarioNoExiste)
+        );
+    }
+
+    #[ink::test]
+    fn test_actualizar_perfil_reindexa_mail_y_nombre() {
+        let (mut app, user_1, user_2) = build_testing_setup();
+
+        assert!(app
+            ._actualizar_perfil(user_1, "nuevo_nombre".to_string(), "nuevo_email".to_string())
+            .is_ok());
+
+        let usuario = app.get_usuario_by_mail("nuevo_email").unwrap();
+        assert_eq!(usuario.get_id(), user_1);
+        assert_eq!(usuario.get_name(), "nuevo_nombre");
+        assert_eq!(app.get_usuario_by_username("nuevo_nombre").unwrap().get_id(), user_1);
+        assert_eq!(
+            app.get_usuario_by_mail("user_email_1").err(),
+            Some(ErroresContrato::MailInexistente)
+        );
+        assert_eq!(
+            app.get_usuario_by_username("user_name_1").err(),
+            Some(ErroresContrato::UsuarioNoExiste)
+        );
+
+        // Los valores liberados pueden ser tomados por otra cuenta
+        assert!(app
+            ._registrar_usuario(
+                AccountId::from([0x30; 32]),
+                "user_name_1".to_string(),
+                "user_email_1".to_string(),
+                Rol::Comprador,
+            )
+            .is_ok());
+
+        // No se puede tomar el mail o el nombre de otro usuario
+        assert_eq!(
+            app._actualizar_perfil(user_2, "user_name_2".to_string(), "NUEVO_EMAIL".to_string()),
+            Err(ErroresContrato::MailYaExistente)
+        );
+        assert_eq!(
+            app._actualizar_perfil(user_2, "nuevo_nombre".to_string(), "user_email_2".to_string()),
+            Err(ErroresContrato::UsuarioYaExistente)
+        );
+
+        // Conservar los propios valores es válido
+        assert!(app
+            ._actualizar_perfil(user_2, "User_Name_2".to_string(), "user_email_2".to_string())
+            .is_ok());
+        assert_eq!(app.get_user(&user_2).unwrap().get_name(), "User_Name_2");
+        assert_eq!(eventos_emitidos::<PerfilActualizado>().len(), 2);
+    }
+
+    #[ink::test]
+    fn test_actualizar_perfil_no_registrado() {
+        let (mut app, _, _) = build_testing_setup();
+        assert_eq!(
+            app._actualizar_perfil(
+                AccountId::from([0x30; 32]),
+                "nombre".to_string(),
+                "mail".to_string()
+            ),
+            Err(ErroresContrato::UsuarioNoExiste)
+        );
+    }
+
+    #[ink::test]
+    fn devuelve_user_con_id_correctamente() {
+        let (mut app, user_id, _) = build_testing_setup();
+        let expected = app.listar_usuarios(0, 1).unwrap().elementos[0].clone();
+
+        assert_eq!(
+            app.get_user(&user_id).unwrap().get_name(),
+            expected.get_name(),
+            "Se esperaba que el campo nombre coincida"
+        );
+
+        assert_eq!(
+            app.get_user(&user_id).unwrap().get_mail(),
+            expected.get_mail(),
+            "Se esperaba que el campo mail coincida"
+        );
+
+        assert_eq!(
+            app.get_user(&user_id).unwrap().get_id(),
+            expected.get_id(),
+            "Se esperaba que el campo ID coincida"
+        );
+    }
+
+    #[ink::test]
+    fn devuelve_user_con_email_correctamente() {
+        let (app, _, _) = build_testing_setup();
+        let expected = app.listar_usuarios(0, 1).unwrap().elementos[0].clone();
+
+        assert!(
+            app.get_usuario_by_mail("not_existent_email@email.com")
+                .is_err(),
+            "Se esperaba un error si no existe un usuario con el email"
+        );
+
+        assert_eq!(
+            app.get_usuario_by_mail(&expected.get_mail())
+                .unwrap()
+                .get_name(),
+            expected.get_name(),
+            "Se esperaba que el campo nombre coincida"
+        );
+
+        assert_eq!(
+            app.get_usuario_by_mail(&expected.get_mail())
+                .unwrap()
+                .get_mail(),
+            expected.get_mail(),
+            "Se esperaba que el campo mail coincida"
+        );
+
+        assert_eq!(
+            app.get_usuario_by_mail(&expected.get_mail())
+                .unwrap()
+                .get_id(),
+            expected.get_id(),
+            "Se esperaba que el campo ID coincida"
+        );
+    }
+
+    #[ink::test]
+    fn asigna_rol_correctamente() {
+        let (mut app, user_id_comprador, user_id_vendedor) = build_testing_setup();
+
+        assert!(
+            app._asignar_rol(user_id_comprador, Rol::Vendedor).is_ok(),
+            "Se esperaba que se asigne el rol correctamente"
+        );
+
+        assert!(
+            app._asignar_rol(user_id_vendedor, Rol::Comprador).is_ok(),
+            "Se esperaba que se asigne el rol correctamente"
+        );
+
+        assert_eq!(
+            app._asignar_rol(user_id_vendedor, Rol::Comprador),
+            Err(ErroresContrato::AlreadyHasRol),
+            "Se esperaba error AlreadyHasRol si el usuario ya tiene el rol asignado"
+        );
+    }
+
+    #[ink::test]
+    fn asigna_ambos_roles_correctamente() {
+        let (mut app, user_id_comprador, user_id_vendedor) = build_testing_setup();
+
+        assert!(
+            app._asignar_rol(user_id_comprador, Rol::Ambos).is_ok(),
+            "Se esperaba que se asigne el rol faltante correctamente"
+        );
+
+        assert!(
+            app._asignar_rol(user_id_vendedor, Rol::Ambos).is_ok(),
+            "Se esperaba que se asigne el rol faltante correctamente"
+        );
+
+        assert_eq!(
+            app._asignar_rol(user_id_vendedor, Rol::Ambos),
+            Err(ErroresContrato::AlreadyHasRol),
+            "Se esperaba error AlreadyHasRol si el usuario ya tiene los roles asignados"
+        );
+    }
+
+    #[ink::test]
+    fn listar_usuarios_paginado_correctamente() {
+        let (app, user1_id, user2_id) = build_testing_setup();
+        assert_eq!(app._get_cantidad_usuarios(), 2);
+
+        let pagina_1 = app.listar_usuarios(0, 1).unwrap();
+        assert_eq!(pagina_1.elementos.len(), 1);
+        assert_eq!(pagina_1.elementos[0].get_id(), user1_id);
+        assert_eq!(pagina_1.total, 2);
+        assert_eq!(pagina_1.siguiente, Some(1));
+
+        let pagina_2 = app.listar_usuarios(1, 1).unwrap();
+        assert_eq!(pagina_2.elementos.len(), 1);
+        assert_eq!(pagina_2.elementos[0].get_id(), user2_id);
+        assert_eq!(pagina_2.siguiente, None);
+
+        let todos = app.listar_usuarios(0, 10).unwrap();
+        assert_eq!(todos.elementos.len(), 2);
+        assert_eq!(todos.siguiente, None);
+    }
+
+    #[ink::test]
+    fn listar_usuarios_tamano_pagina_incorrectos() {
+        let (app, _user1_id, _user2_id) = build_testing_setup();
+
+        // Ya no existe la página 0 que devolvía todo
+        assert_eq!(
+            app.listar_usuarios(0, 0).err(),
+            Some(ErroresContrato::TamanoPaginaInvalido)
+        );
+        assert_eq!(
+            app.listar_usuarios(0, MAX_TAMANO_PAGINA + 1).err(),
+            Some(ErroresContrato::TamanoPaginaInvalido)
+        );
+
+        let pagina_2 = app.listar_usuarios(4294967295, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(pagina_2.elementos.len(), 0);
+        assert_eq!(pagina_2.total, 2);
+        assert_eq!(pagina_2.siguiente, None);
+    }
+
+    ///Tests gestion orden
+    #[ink::test]
+    fn test_crear_orden_con_exito() {
+        let mut contrato = setup_sistema();
+        let (id_comprador, id_vendedor) = build_testing_accounts();
+
+        registrar_comprador(&mut contrato, id_comprador);
+
+        registrar_vendedor(&mut contrato, id_vendedor);
+
+        contrato._registrar_categoria("Libros".to_string()).unwrap();
+        contrato
+            ._crear_producto(
+                id_vendedor,
+                "Rust Book".to_string(),
+                "Desc libro".to_string(),
+                "Libros".to_string(),
+                10,
+            )
+            .unwrap();
+        contrato
+            ._crear_publicacion(0, id_vendedor, 10, 100)
+            .unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(id_comprador); //setea el caller en Comprador
+
+        pagar(200);
+        let result = contrato.crear_orden(0, 2);
+        assert!(result.is_ok(), "Error al crear la orden");
+
+        let ordenes = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(ordenes.len(), 1);
+        assert_eq!(ordenes[0].get_cantidad(), 2);
+        assert_eq!(ordenes[0].get_status(), EstadoOrden::Pendiente);
+    }
+
+    #[ink::test]
+    fn test_crear_orden_con_cantidad_cero_fallido() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        registrar_vendedor(&mut contrato, vendedor);
+
+        registrar_comprador(&mut contrato, comprador);
+
+        contrato
+            ._registrar_categoria("Electronica".to_string())
+            .unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Auriculares".to_string(),
+                "BT".to_string(),
+                "Electronica".to_string(),
+                5,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 5, 250).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+
+        let res = contrato.crear_orden(0, 0);
+        assert!(matches!(
+            res,
+            Err(ErroresContrato::CantidadEnCarritoMenorAUno)
+        ));
+    }
+
+    #[ink::test]
+    fn test_crear_orden_sin_rol_comprador_fallido() {
+        let mut contrato = setup_sistema();
+        let (no_comprador, vendedor) = build_testing_accounts();
+
+        registrar_vendedor(&mut contrato, vendedor);
+        contrato
+            ._registrar_usuario(
+                no_comprador,
+                "Santi No comprador".to_string(),
+                "ST96@Gmail.com".to_string(),
+                Rol::Vendedor,
+            )
+            .unwrap();
+
+        contrato
+            ._registrar_categoria("Una cat".to_string())
+            .unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Nombre CAt".to_string(),
+                "Aux".to_string(),
+                "Una cat".to_string(),
+                3,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 3, 600).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(no_comprador);
+
+        let res = contrato.crear_orden(0, 1);
+        assert!(matches!(res, Err(ErroresContrato::RolNoApropiado)));
+    }
+
+    #[ink::test]
+    fn test_enviar_orden_pendiente_exito() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        // registrar usuarios y roles
+        registrar_vendedor(&mut contrato, vendedor);
+        registrar_comprador(&mut contrato, comprador);
+
+        // Creo categoría, producto y publicación
+        contrato._registrar_categoria("Juegos".to_string()).unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "PS5".to_string(),
+                "Sony".to_string(),
+                "Juegos".to_string(),
+                3,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 3, 600).unwrap();
+
+        // Creo orden como comprador
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(1200);
+        contrato.crear_orden(0, 2).unwrap();
+
+        // Envio orden como vendedor
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        let res = contrato.enviar_producto(0);
+
+        assert!(res.is_ok(), "Fallo al enviar la orden");
+        let ordenes = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(ordenes[0].get_status(), EstadoOrden::Enviada);
+    }
+
+    #[ink::test]
+    fn test_contrato_orden_pendiente_exitoso() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        // Setea la cuenta del contrato como callee en el entorno de test
+        let contrato_account = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+        ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contrato_account);
+
+        registrar_vendedor(&mut contrato, vendedor);
+
+        registrar_comprador(&mut contrato, comprador);
+
+        contrato._registrar_categoria("Libros".to_string()).unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Rust".to_string(),
+                "Desc".to_string(),
+                "Libros".to_string(),
+                10,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 10, 100).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+
+        pagar(100);
+        assert!(contrato.crear_orden(0, 1).is_ok());
     }
-}
 
-pub mod prelude {
-    pub use super::contract::{
-        SistemaRef, Categoria, Orden, Usuario, Rating, Producto, Publicacion, EstadoOrden, Rol
-    };
-}
+    #[ink::test]
+    fn test_orden_cancelada_con_consenso() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
 
+        registrar_vendedor(&mut contrato, vendedor);
+        registrar_comprador(&mut contrato, comprador);
 
-#[cfg(test)]
-mod tests {
-    use crate::contract::*;
+        contrato._registrar_categoria("Libros".to_string()).unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Rust".to_string(),
+                "Desc".to_string(),
+                "Libros".to_string(),
+                10,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 10, 100).unwrap();
 
-    use ink::{
-        env::DefaultEnvironment,
-        primitives::AccountId,
-    };
-    use ink_e2e::{account_id, AccountKeyring};
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(100);
+        let id_orden = contrato.crear_orden(0, 1).unwrap();
+        assert!(contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).is_ok());
+        let orden = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_status(), EstadoOrden::PreCancelada);
 
-    fn setup_sistema() -> Sistema {
-        Sistema::new()
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        assert!(contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).is_ok());
+        let orden = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_status(), EstadoOrden::Cancelada);
     }
 
-    fn id_comprador() -> <DefaultEnvironment as ink::env::Environment>::AccountId {
-        account_id(AccountKeyring::Alice)
+    fn setup_publicacion() -> (Sistema, AccountId, AccountId) {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        registrar_vendedor(&mut contrato, vendedor);
+        registrar_comprador(&mut contrato, comprador);
+
+        agregar_categoria(&mut contrato, "Libros");
+        contrato
+            ._crear_producto(vendedor, "Rust".into(), "Desc".into(), "Libros".into(), 10)
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 10, 100).unwrap();
+
+        (contrato, comprador, vendedor)
     }
 
-    fn id_vendedor() -> <DefaultEnvironment as ink::env::Environment>::AccountId {
-        account_id(AccountKeyring::Bob)
+    #[ink::test]
+    fn test_crear_orden_retiene_pago_en_contrato() {
+        let (mut contrato, comprador, _) = setup_publicacion();
+        let saldo_inicial = saldo_contrato();
+
+        set_caller(comprador);
+        pagar(300);
+        assert!(contrato.crear_orden(0, 3).is_ok());
+
+        assert_eq!(saldo_contrato(), saldo_inicial + 300);
+        assert_eq!(contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].get_precio_total(), 300);
     }
 
-    fn set_caller(caller: AccountId) {
-        ink::env::test::set_caller::<DefaultEnvironment>(caller);
+    #[ink::test]
+    fn test_crear_orden_pago_insuficiente() {
+        let (mut contrato, comprador, _) = setup_publicacion();
+
+        set_caller(comprador);
+        pagar(299);
+        let res = contrato.crear_orden(0, 3);
+
+        assert_eq!(res, Err(ErroresContrato::PagoInsuficiente));
+        assert!(contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+        assert_eq!(contrato.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
     }
 
-    fn build_testing_accounts() -> (AccountId, AccountId) {
-        let id_comprador = id_comprador();
-        let id_vendedor = id_vendedor();
-        (id_comprador, id_vendedor)
+    #[ink::test]
+    fn test_crear_orden_pago_excedente() {
+        let (mut contrato, comprador, _) = setup_publicacion();
+
+        set_caller(comprador);
+        pagar(301);
+        let res = contrato.crear_orden(0, 3);
+
+        assert_eq!(res, Err(ErroresContrato::PagoExcedente));
+        assert!(contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+        assert_eq!(contrato.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
     }
 
-    fn build_testing_setup() -> (Sistema, AccountId, AccountId) {
-        let mut app = setup_sistema();
-        let (user_1, user_2) = build_testing_accounts();
+    #[ink::test]
+    fn test_recibir_orden_libera_pago_al_vendedor() {
+        let (mut contrato, comprador, vendedor) = setup_publicacion();
 
-        app._registrar_usuario(
-            user_1,
-            "user_name_1".to_string(),
-            "user_email_1".to_string(),
-            Rol::Comprador,
-        )
-        .expect("No se pudo registrar el usuario");
-        app._registrar_usuario(
-            user_2,
-            "user_name_2".to_string(),
-            "user_email_2".to_string(),
-            Rol::Vendedor,
-        )
-        .expect("No se pudo registrar el usuario");
+        set_caller(comprador);
+        pagar(200);
+        let id_orden = contrato.crear_orden(0, 2).unwrap();
 
-        (app, user_1, user_2)
+        set_caller(vendedor);
+        contrato.enviar_producto(id_orden).unwrap();
+        let saldo_vendedor = saldo(vendedor);
+        let saldo_retenido = saldo_contrato();
+
+        set_caller(comprador);
+        contrato.recibir_producto(id_orden).unwrap();
+
+        assert_eq!(saldo(vendedor), saldo_vendedor + 200);
+        assert_eq!(saldo_contrato(), saldo_retenido - 200);
     }
 
-    //fn de test de agus olthoff
+    #[ink::test]
+    fn test_cancelar_orden_reembolsa_al_comprador() {
+        let (mut contrato, comprador, vendedor) = setup_publicacion();
 
-    fn registrar_comprador(
-        sistema: &mut Sistema,
-        id: <DefaultEnvironment as ink::env::Environment>::AccountId,
-    ) {
-        sistema
-            ._registrar_usuario(
-                id,
-                "Comprador".into(),
-                "comprador@gmail.com".into(),
-                Rol::Comprador,
+        set_caller(comprador);
+        pagar(200);
+        let id_orden = contrato.crear_orden(0, 2).unwrap();
+        let saldo_comprador = saldo(comprador);
+
+        // La pre-cancelación todavía no devuelve el dinero
+        contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
+        assert_eq!(saldo(comprador), saldo_comprador);
+
+        set_caller(vendedor);
+        let saldo_vendedor = saldo(vendedor);
+        contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
+
+        assert_eq!(saldo(comprador), saldo_comprador + 200);
+        assert_eq!(saldo(vendedor), saldo_vendedor);
+        assert_eq!(contrato.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
+    }
+
+    #[ink::test]
+    fn enviar_orden_inexistente_falla() {
+        let mut contrato = setup_sistema();
+        let vendedor = id_vendedor();
+
+        registrar_vendedor(&mut contrato, vendedor);
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+
+        // No hay orden 99
+        let res = contrato.enviar_producto(99);
+        assert_eq!(res, Err(ErroresContrato::OrdenInexistente));
+        //assert!(matches!(res, Err(ErroresContrato::OrdenInexistente)));
+    }
+
+    #[ink::test]
+    fn recibir_orden_enviada_exitoso() {
+        let mut contrato = setup_sistema();
+        let (comprador, vendedor) = build_testing_accounts();
+
+        // Registro usuarios y roles
+        registrar_vendedor(&mut contrato, vendedor);
+        registrar_comprador(&mut contrato, comprador);
+
+        //Creo publicación
+        contrato._registrar_categoria("Libros".to_string()).unwrap();
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Rust".to_string(),
+                "Desc".to_string(),
+                "Libros".to_string(),
+                5,
             )
             .unwrap();
+        contrato._crear_publicacion(0, vendedor, 5, 100).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(100);
+        contrato.crear_orden(0, 1).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        contrato.enviar_producto(0).unwrap();
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        let res = contrato.recibir_producto(0);
+
+        assert!(res.is_ok());
+        let orden = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_status(), EstadoOrden::Recibida);
     }
-    fn registrar_vendedor(
-        sistema: &mut Sistema,
-        id: <DefaultEnvironment as ink::env::Environment>::AccountId,
-    ) {
+    #[ink::test]
+    fn test_crear_producto_falla_datos_invalidos() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        // nombre vacio
+        let res_nombre = sistema._crear_producto(id, "".into(), "desc".into(), "Ropa".into(), 10);
+        assert_eq!(res_nombre, Err(ErroresContrato::DatosInvalidos));
+
+        // descripcion vacia
+        let res_desc = sistema._crear_producto(id, "Valid".into(), "   ".into(), "Ropa".into(), 10);
+        assert_eq!(res_desc, Err(ErroresContrato::DatosInvalidos));
+    }
+
+    #[ink::test]
+    fn test_crear_producto_falla_stock_cero() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        // stock 0
+        let res = sistema._crear_producto(id, "Valid".into(), "desc".into(), "Ropa".into(), 0);
+        assert_eq!(res, Err(ErroresContrato::StockInvalido));
+    }
+
+    #[ink::test]
+    fn test_crear_publicacion_falla_valores_cero() {
+        let mut sistema = setup_sistema();
+        let id = id_vendedor();
+        registrar_vendedor(&mut sistema, id);
+        agregar_categoria(&mut sistema, "Ropa");
+
+        // creo producto valido con 100 unidades
         sistema
+            ._crear_producto(id, "Prod".into(), "D".into(), "Ropa".into(), 100)
+            .unwrap();
+
+        // publico con precio = $0
+        let res_precio = sistema._crear_publicacion(0, id, 10, 0);
+        assert_eq!(res_precio, Err(ErroresContrato::PrecioInvalido));
+
+        // publico con stock = 0
+        let res_stock = sistema._crear_publicacion(0, id, 0, 100);
+        assert_eq!(res_stock, Err(ErroresContrato::StockInvalido));
+    }
+
+    #[ink::test]
+    fn test_enviar_orden_vendedor_correcto() {
+        let mut contrato = Sistema::new();
+        let comprador = account_id(AccountKeyring::Alice);
+        let vendedor = account_id(AccountKeyring::Bob);
+
+        // Configurar usuarios y productos
+        registrar_comprador(&mut contrato, comprador);
+        registrar_vendedor(&mut contrato, vendedor);
+
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        agregar_categoria(&mut contrato, "Electrónicos");
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Laptop".into(),
+                "Gaming laptop".into(),
+                "Electrónicos".into(),
+                5,
+            )
+            .unwrap();
+        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
+
+        // Crear orden como comprador
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(1000);
+        contrato.crear_orden(0, 1).unwrap();
+
+        // Enviar orden como vendedor (debe funcionar)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        let res = contrato.enviar_producto(0);
+
+        assert!(res.is_ok());
+        let orden = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_status(), EstadoOrden::Enviada);
+    }
+
+    #[ink::test]
+    fn test_enviar_orden_vendedor_incorrecto() {
+        let mut contrato = Sistema::new();
+        let comprador = account_id(AccountKeyring::Alice);
+        let vendedor_original = account_id(AccountKeyring::Bob);
+        let vendedor_intruso = account_id(AccountKeyring::Charlie);
+
+        // Configurar usuarios y productos
+        registrar_comprador(&mut contrato, comprador);
+        registrar_vendedor(&mut contrato, vendedor_original);
+        // Registrar segundo vendedor con email diferente
+        contrato
             ._registrar_usuario(
-                id,
-                "Vendedor".into(),
-                "vendedor@gmail.com".into(),
+                vendedor_intruso,
+                "Vendedor2".into(),
+                "vendedor2@gmail.com".into(),
                 Rol::Vendedor,
             )
             .unwrap();
-    }
-
-    fn agregar_categoria(sistema: &mut Sistema, nombre: &str) {
-        sistema._registrar_categoria(nombre.into()).unwrap();
-    }
-
-    fn contrato_con_categorias_cargada() -> Sistema {
-        let mut sist = Sistema::new();
-        for i in 0..10 {
-            let _ = sist._registrar_categoria(String::from(format!("categoria {}", i)));
-        }
-        return sist;
-    }
-
-    #[ink::test]
-    fn test_crear_publicacion_exito() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
 
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
-            ._crear_producto(id, "Zapatillas".into(), "desc".into(), "Ropa".into(), 10)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor_original);
+        agregar_categoria(&mut contrato, "Electrónicos");
+        contrato
+            ._crear_producto(
+                vendedor_original,
+                "Laptop".into(),
+                "Gaming laptop".into(),
+                "Electrónicos".into(),
+                5,
+            )
+            .unwrap();
+        contrato
+            ._crear_publicacion(0, vendedor_original, 2, 1000)
             .unwrap();
 
-        let esperado = Publicacion::new(0, 0, id, 5, 1000);
-        let res = sistema._crear_publicacion(0, id, 5, 1000);
+        // Crear orden como comprador
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(1000);
+        contrato.crear_orden(0, 1).unwrap();
 
-        assert!(res.is_ok());
+        // Intentar enviar orden como vendedor diferente (debe fallar)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor_intruso);
+        let res = contrato.enviar_producto(0);
 
-        let retorno = sistema._listar_publicaciones_propias(id)[0].clone();
-        assert_eq!(esperado, retorno);
+        assert!(matches!(res, Err(ErroresContrato::NoEsVendedorOriginal)));
     }
 
     #[ink::test]
-    fn test_crear_publicacion_falla_sin_rol_vendedor() {
-        let mut sistema = setup_sistema();
-        let id: AccountId = id_vendedor();
-        let id2: AccountId = id_comprador();
+    fn test_recibir_orden_comprador_correcto() {
+        let mut contrato = Sistema::new();
+        let comprador = account_id(AccountKeyring::Alice);
+        let vendedor = account_id(AccountKeyring::Bob);
 
-        registrar_vendedor(&mut sistema, id);
-        registrar_comprador(&mut sistema, id2);
+        // Configurar usuarios y productos
+        registrar_comprador(&mut contrato, comprador);
+        registrar_vendedor(&mut contrato, vendedor);
 
-        set_caller(id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
-            ._crear_producto(id, "Remera".into(), "desc".into(), "Ropa".into(), 10)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        agregar_categoria(&mut contrato, "Electrónicos");
+        contrato
+            ._crear_producto(
+                vendedor,
+                "Laptop".into(),
+                "Gaming laptop".into(),
+                "Electrónicos".into(),
+                5,
+            )
             .unwrap();
+        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
 
-        set_caller(id2);
-        let res = sistema._crear_publicacion(0, id2, 5, 200);
-        assert!(matches!(res, Err(ErroresContrato::RolNoApropiado)));
-    }
+        // Crear y enviar orden
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        pagar(1000);
+        contrato.crear_orden(0, 1).unwrap();
 
-    #[ink::test]
-    fn test_crear_publicacion_falla_producto_inexistente() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        contrato.enviar_producto(0).unwrap();
 
-        registrar_vendedor(&mut sistema, id);
+        // Recibir orden como comprador correcto (debe funcionar)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        let res = contrato.recibir_producto(0);
 
-        let res = sistema._crear_publicacion(99, id, 3, 500); // id_producto inválido
-        assert!(matches!(res, Err(ErroresContrato::ProductoInexistente)));
+        assert!(res.is_ok());
+        let orden = contrato.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_status(), EstadoOrden::Recibida);
     }
 
     #[ink::test]
-    fn test_crear_publicacion_falla_stock_insuficiente() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
+    fn test_recibir_orden_comprador_incorrecto() {
+        let mut contrato = Sistema::new();
+        let comprador_original = account_id(AccountKeyring::Alice);
+        let comprador_intruso = account_id(AccountKeyring::Charlie);
+        let vendedor = account_id(AccountKeyring::Bob);
 
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
-            ._crear_producto(id, "Pantalon".into(), "desc".into(), "Ropa".into(), 2)
+        // Configurar usuarios y productos
+        registrar_comprador(&mut contrato, comprador_original);
+        // Registrar segundo comprador con email diferente
+        contrato
+            ._registrar_usuario(
+                comprador_intruso,
+                "Comprador2".into(),
+                "comprador2@gmail.com".into(),
+                Rol::Comprador,
+            )
             .unwrap();
+        registrar_vendedor(&mut contrato, vendedor);
 
-        let res = sistema._crear_publicacion(0, id, 5, 300); // pide más stock del disponible
-        assert!(matches!(res, Err(ErroresContrato::StockInsuficiente)));
-    }
-
-    #[ink::test]
-    fn test_crear_publicacion_falla_usuario_inexistente() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor(); // Nunca lo registramos
-
-        let res = sistema._crear_publicacion(0, id, 5, 1000);
-        assert!(matches!(res, Err(ErroresContrato::UsuarioNoExiste)));
-    }
+        // ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_original);
+        // contrato.asignar_rol(Rol::Comprador).unwrap();
 
-    #[ink::test]
-    fn test_descontar_stock_publicacion_exito() {
-        let mut sistema = setup_sistema();
-        let id_vendedor = id_vendedor();
-        let id_comprador = id_comprador();
-        set_caller(id_vendedor);
+        // ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_intruso);
+        // contrato.asignar_rol(Rol::Comprador).unwrap();
 
-        registrar_vendedor(&mut sistema, id_vendedor);
-        registrar_comprador(&mut sistema, id_comprador);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        agregar_categoria(&mut contrato, "Electrónicos");
+        contrato
             ._crear_producto(
-                id_vendedor,
-                "Zapatillas".into(),
-                "desc".into(),
-                "Ropa".into(),
-                10,
+                vendedor,
+                "Laptop".into(),
+                "Gaming laptop".into(),
+                "Electrónicos".into(),
+                5,
             )
             .unwrap();
-        sistema._crear_publicacion(0, id_vendedor, 5, 1000).unwrap();
-
-        // El descuento se hace automáticamente al crear la orden
-        let res = sistema._crear_orden(0, id_comprador, 2);
-        assert!(res.is_ok());
+        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
 
-        // Verificar que el stock de la publicación se redujo
-        let publicaciones = sistema._listar_publicaciones();
-        assert_eq!(publicaciones[0].stock(), 3);
-    }
+        // Crear orden como comprador original
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_original);
+        pagar(1000);
+        contrato.crear_orden(0, 1).unwrap();
 
-    #[ink::test]
-    fn test_descontar_stock_publicacion_falla_publicacion_inexistente() {
-        let mut sistema = setup_sistema();
-        let id_vendedor = id_vendedor();
-        let id_comprador = id_comprador();
+        // Enviar orden como vendedor
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        contrato.enviar_producto(0).unwrap();
 
-        registrar_vendedor(&mut sistema, id_vendedor);
-        registrar_comprador(&mut sistema, id_comprador);
+        // Intentar recibir orden como comprador diferente (debe fallar)
+        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_intruso);
+        let res = contrato.recibir_producto(0);
 
-        // Intentar crear orden con publicación inexistente
-        let res = sistema._crear_orden(99, id_comprador, 1);
-        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
+        assert!(matches!(res, Err(ErroresContrato::NoEsCompradorOriginal)));
     }
 
-    #[ink::test]
-    fn test_descontar_stock_publicacion_falla_stock_insuficiente() {
+    fn setup_orden_recibida() -> (Sistema, u32, AccountId, AccountId) {
         let mut sistema = setup_sistema();
-        let id_vendedor = id_vendedor();
-        let id_comprador = id_comprador();
-        set_caller(id_vendedor);
+        let (comprador, vendedor) = build_testing_accounts();
 
-        registrar_vendedor(&mut sistema, id_vendedor);
-        registrar_comprador(&mut sistema, id_comprador);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
+        // 1. registro usuarios
+        registrar_vendedor(&mut sistema, vendedor);
+        registrar_comprador(&mut sistema, comprador);
+
+        // 2. creo una publicacion
+        agregar_categoria(&mut sistema, "TestCat");
         sistema
-            ._crear_producto(
-                id_vendedor,
-                "Zapatillas".into(),
-                "desc".into(),
-                "Ropa".into(),
-                10,
-            )
+            ._crear_producto(vendedor, "Prod".into(), "Desc".into(), "TestCat".into(), 10)
             .unwrap();
-        sistema._crear_publicacion(0, id_vendedor, 3, 1000).unwrap();
+        sistema._crear_publicacion(0, vendedor, 10, 100).unwrap();
 
-        // Intentar crear orden con más cantidad de la disponible en la publicación
-        let res = sistema._crear_orden(0, id_comprador, 5);
-        assert!(matches!(res, Err(ErroresContrato::StockInsuficiente)));
+        // 3. Creo orden comprador
+        set_caller(comprador);
+        pagar(200);
+        let id_orden = sistema.crear_orden(0, 2).unwrap();
+
+        // 4. Envio orden vendedor
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+
+        // 5. Recibo orden comprador
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+
+        (sistema, id_orden, comprador, vendedor)
+    }
+
+    /// Deja vencer el plazo de revelación y revela las calificaciones de la orden
+    fn revelar_por_vencimiento(sistema: &mut Sistema, id_orden: u32) {
+        avanzar_tiempo(PLAZO_REVELACION_DEFAULT);
+        sistema.revelar_calificaciones(id_orden).unwrap();
     }
 
     #[ink::test]
-    fn test_get_precio_unitario_ok() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        set_caller(id);
+    fn test_calificar_vendedor_exito() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
 
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
-            ._crear_producto(id, "Campera".into(), "desc".into(), "Ropa".into(), 10)
-            .unwrap();
-        sistema._crear_publicacion(0, id, 4, 1234).unwrap();
+        // el comprador califica con 5 estrellas
+        set_caller(comprador);
+        let res = sistema.calificar_compra(id_orden, 5);
+        assert!(res.is_ok(), "La calificación debería ser exitosa");
+        revelar_por_vencimiento(&mut sistema, id_orden);
+        
+        // Verificamos que la repu aumento
+        let usuario_vendedor = sistema.get_user(&vendedor).unwrap();
 
-        let res = sistema.get_precio_unitario(0);
-        assert!(matches!(res, Ok(1234)));
+        // accedemos a la tupla para ver los resultados
+        assert_eq!(
+            usuario_vendedor.get_calificacion_vendedor().0, 5,
+            "Debería tener 1 calificación"
+        );
+        assert_eq!(
+            usuario_vendedor.get_calificacion_vendedor().1, 1,
+            "La suma de puntos debería ser 5"
+        );
+        // assert_eq!(
+        //     usuario_vendedor.mostrar_calificacion_vendedor(), Ok(String::from("Calificacion como vendedor: 5,0")),
+        //     "Debería mostrar calificacion de 5,0"
+        // );
     }
 
     #[ink::test]
-    fn test_get_precio_unitario_falla_publicacion_inexistente() {
-        let sistema = setup_sistema(); // no hace falta crear nada
+    fn test_calificar_comprador_exito() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
 
-        let res = sistema.get_precio_unitario(42);
-        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
-    }
+        // calificamos al comprador con 4 estrellas
+        set_caller(vendedor);
+        let res = sistema.calificar_compra(id_orden, 4);
+        assert!(res.is_ok());
+        revelar_por_vencimiento(&mut sistema, id_orden);
+        let usuario_comprador = sistema.get_user(&comprador).unwrap();
 
-    #[ink::test]
-    fn test_get_id_vendedor_ok() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        set_caller(id);
+        assert_eq!(usuario_comprador.get_calificacion_comprador().0, 4);
+        assert_eq!(usuario_comprador.get_calificacion_comprador().1, 1);
 
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-        sistema
-            ._crear_producto(id, "Pantalón".into(), "desc".into(), "Ropa".into(), 8)
-            .unwrap();
-        sistema._crear_publicacion(0, id, 5, 999).unwrap();
+        //assert_eq!(usuario_comprador.mostrar_calificacion_comprador(), Ok(String::from("Calificacion como comprador: 4,0")));
 
-        let res = sistema.get_id_vendedor(0);
-        assert!(matches!(res, Ok(valor) if valor == id));
     }
 
     #[ink::test]
-    fn test_get_id_vendedor_falla_publicacion_inexistente() {
-        let sistema = setup_sistema();
+    fn test_calificar_puntaje_invalido() {
+        let (mut sistema, id_orden, comprador, _) = setup_orden_recibida();
+        set_caller(comprador);
 
-        let res = sistema.get_id_vendedor(42);
-        assert!(matches!(res, Err(ErroresContrato::PublicacionNoExiste)));
+        // puntaje 0 limites
+        let res_cero = sistema.calificar_compra(id_orden, 0);
+        assert_eq!(res_cero, Err(ErroresContrato::PuntajeInvalido));
+
+        // puntaje 6 limites
+        let res_seis = sistema.calificar_compra(id_orden, 6);
+        assert_eq!(res_seis, Err(ErroresContrato::PuntajeInvalido));
     }
 
     #[ink::test]
-    fn test_listar_productos_vacio() {
-        let sistema = setup_sistema();
-        let productos = sistema._listar_productos();
-        assert_eq!(productos.len(), 0);
+    fn test_calificar_doble_falla() {
+        let (mut sistema, id_orden, comprador, _) = setup_orden_recibida();
+        set_caller(comprador);
+
+        // califico por primera vez bien
+        sistema.calificar_compra(id_orden, 5).unwrap();
+
+        // califico por segunda vez falla
+        let res = sistema.calificar_compra(id_orden, 3);
+        assert_eq!(res, Err(ErroresContrato::YaCalificado));
     }
 
     #[ink::test]
-    fn test_listar_productos_uno() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        set_caller(id);
+    fn test_calificar_usuario_ajeno_a_la_orden() {
+        let (mut sistema, id_orden, _, _) = setup_orden_recibida();
+        let intruso = account_id(AccountKeyring::Charlie);
 
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
+        // creo un pj ajeno a la orden intruso
         sistema
-            ._crear_producto(id, "Buzo".into(), "desc".into(), "Ropa".into(), 5)
+            ._registrar_usuario(
+                intruso,
+                "Intruso".into(),
+                "intruso@gmail.com".into(),
+                Rol::Comprador,
+            )
             .unwrap();
 
-        let productos = sistema._listar_productos();
-        assert_eq!(productos.len(), 1);
+        set_caller(intruso);
+        let res = sistema.calificar_compra(id_orden, 5);
 
-        // Si `Producto` implementa PartialEq:
-        let esperado = Producto::new(0, id, "Buzo".into(), "desc".into(), 0, 5);
-        assert_eq!(productos[0], esperado);
+        // el intruso califica la orden
+        assert_eq!(res, Err(ErroresContrato::UsuarioNoCorresponde));
     }
 
     #[ink::test]
-    fn test_listar_productos_varios() {
+    fn test_calificar_orden_no_recibida() {
+        // cambio la orden de entregado a pendiente , para que falle
         let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        set_caller(id);
-
-        registrar_vendedor(&mut sistema, id);
-        sistema._registrar_categoria("Ropa".into()).unwrap();
-
-        sistema
-            ._crear_producto(id, "Buzo".into(), "desc".into(), "Ropa".into(), 5)
-            .unwrap();
+        let (comprador, vendedor) = build_testing_accounts();
+        registrar_vendedor(&mut sistema, vendedor);
+        registrar_comprador(&mut sistema, comprador);
+        agregar_categoria(&mut sistema, "Cat");
         sistema
-            ._crear_producto(id, "Campera".into(), "desc".into(), "Ropa".into(), 8)
+            ._crear_producto(vendedor, "P".into(), "D".into(), "Cat".into(), 10)
             .unwrap();
+        sistema._crear_publicacion(0, vendedor, 10, 100).unwrap();
 
-        let productos = sistema._listar_productos();
-        assert_eq!(productos.len(), 2);
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        // Intento calificar
+        let res = sistema.calificar_compra(id_orden, 5);
+        assert_eq!(res, Err(ErroresContrato::OrdenNoRecibida));
     }
 
     #[ink::test]
-    fn test_categoria_agregar_nueva() {
-        let mut sist = setup_sistema();
-
-        assert!(sist._listar_categorias().is_empty());
+    fn test_reputacion_exitosa_comprador() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
 
-        let result = sist._registrar_categoria("Limpieza".to_string());
+        // calificamos al comprador con 4 estrellas
+        set_caller(vendedor);
+        let res = sistema.calificar_compra(id_orden, 4);
+        assert!(res.is_ok());
+        revelar_por_vencimiento(&mut sistema, id_orden);
+        let usuario_comprador = sistema.get_user(&comprador).unwrap();
 
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente"))
-        );
-        assert_eq!(sist._listar_categorias().len(), 1);
+        let resultado_1 = usuario_comprador.get_reputacion_comprador(1).unwrap();
+        assert_eq!(resultado_1.promedio, Some(40));
+        assert_eq!(resultado_1.estrellas, [0, 0, 0, 1, 0]);
     }
 
     #[ink::test]
-    fn test_categoria_agregar_duplicada() {
-        let mut sist = contrato_con_categorias_cargada();
+    fn test_reputacion_exitosa_vendedor() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
 
-        assert!(!sist._listar_categorias().is_empty());
-        let result = sist._registrar_categoria("categoria 1".to_string());
-        assert_eq!(
-            result,
-            Err(ErroresContrato::CategoriaYaExistente),
-            "deberia tirar error que ya existe la categoria"
-        );
+        // calificamos al vendedor con 5 estrellas
+        set_caller(comprador);
+        let res = sistema.calificar_compra(id_orden, 5);
+        assert!(res.is_ok(), "La calificación debería ser exitosa");
+        revelar_por_vencimiento(&mut sistema, id_orden);
+        
+        // Verificamos que la reputacion aumenta
+        let usuario_vendedor = sistema.get_user(&vendedor).unwrap();
+
+        let resultado_2 = usuario_vendedor.get_reputacion_vendedor(1).unwrap();
+        assert_eq!(resultado_2.promedio, Some(50));
+        assert_eq!(resultado_2.estrellas, [0, 0, 0, 0, 1]);
     }
 
     #[ink::test]
-    fn test_categoria_formateo_nombre() {
-        let mut sist = contrato_con_categorias_cargada();
-
-        //nombre similar
-        let result = sist._registrar_categoria("CaTEgORia 1".to_string());
-        assert_eq!(
-            result,
-            Err(ErroresContrato::CategoriaYaExistente),
-            "deberia devolver que ya existe la categoria"
-        );
-
-        //nombre vacio
-        let result = sist._registrar_categoria(String::new());
-        assert_eq!(
-            result,
-            Err(ErroresContrato::NombreCategoriaVacio),
-            "deberia devolver que el nombre de la categoria esta vacia"
-        );
-
-        //nombres con unicode
-        let result = sist._registrar_categoria("не ваше дела идите на хуй".to_string());
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia poder manejar alfabeto cirilico"
-        );
-        let result = sist._registrar_categoria("የክፋት እቅድ".to_string());
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia poder manejar alfabeto amharico"
-        );
-        let result = sist._registrar_categoria("プログラミングが好きです".to_string());
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia poder manejar kanji, katakana e hiragana"
-        );
-        let result = sist._registrar_categoria("사랑해요".to_string());
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia poder manejar hangul"
-        );
-
-        //nombre con leading y trailing whitespace
-        let result = sist._registrar_categoria(
-            "          alguna categoria                                                "
-                .to_string(),
-        );
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia eliminar espacios en blanco al principio y final del string"
-        );
-
-        //nombre truncado
+    fn test_reputacion_sin_calificaciones() {
+        let (mut sistema, id, _,)= build_testing_setup();
+        let usuario = sistema.get_user(&id).unwrap();
 
-        let result = sist._registrar_categoria(
-            "You know what they call a  Quarter Pounder with Cheese in Paris?
 
-            [JULES]
-            They don't call it a Quarter Pounder with Cheese?
+        let resultado_1 = usuario.get_reputacion_vendedor(1).unwrap();
+        assert_eq!(resultado_1.cantidad, 0);
+        assert_eq!(resultado_1.promedio, None);
 
-            [VINCENT]
-            No, they got the metric system there, they wouldn't know what the fuck a Quarter Pounder is.
+        let resultado_2 = usuario.get_reputacion_comprador(1).unwrap();
+        assert_eq!(resultado_2.cantidad, 0);
+        assert_eq!(resultado_2.promedio, None);
+    }
+    #[ink::test]
+    fn test_eventos_registro_usuario_y_rol() {
+        let (_, comprador, vendedor) = build_testing_setup();
+
+        let registrados = eventos_emitidos::<UsuarioRegistrado>();
+        assert_eq!(registrados.len(), 2);
+        assert_eq!(registrados[0].id, comprador);
+        assert_eq!(registrados[0].nombre, "user_name_1");
+        assert_eq!(registrados[1].id, vendedor);
+
+        let roles = eventos_emitidos::<RolAsignado>();
+        assert_eq!(roles.len(), 2);
+        assert!(roles[0].id == comprador && roles[0].rol == Rol::Comprador);
+        assert!(roles[1].id == vendedor && roles[1].rol == Rol::Vendedor);
+    }
 
-            [JULES]
-            Then what do they call it?
+    #[ink::test]
+    fn test_eventos_categoria_producto_y_publicacion() {
+        let (_, _, vendedor) = setup_publicacion();
 
-            [VINCENT]
-            They call it Royale with Cheese.
+        let categorias = eventos_emitidos::<CategoriaRegistrada>();
+        assert_eq!(categorias.len(), 1);
+        assert_eq!(categorias[0].id, 0);
+        assert_eq!(categorias[0].nombre, "libros");
 
-            [JULES]
-            Royale with Cheese. What do they call a Big Mac?
+        let productos = eventos_emitidos::<ProductoCreado>();
+        assert_eq!(productos.len(), 1);
+        assert_eq!(productos[0].id_vendedor, vendedor);
+        assert_eq!(productos[0].categoria, 0);
+
+        let publicaciones = eventos_emitidos::<PublicacionCreada>();
+        assert_eq!(publicaciones.len(), 1);
+        assert_eq!(publicaciones[0].id_producto, 0);
+        assert_eq!(publicaciones[0].stock, 10);
+        assert_eq!(publicaciones[0].precio_unitario, 100);
+    }
 
-            [VINCENT]
-            Big Mac's a Big Mac, but they call it Le Big Mac.
+    #[ink::test]
+    fn test_eventos_flujo_completo_de_orden() {
+        let (_, id_orden, comprador, vendedor) = setup_orden_recibida();
+
+        let creadas = eventos_emitidos::<OrdenCreada>();
+        assert_eq!(creadas.len(), 1);
+        assert_eq!(creadas[0].id_orden, id_orden);
+        assert_eq!(creadas[0].id_comprador, comprador);
+        assert_eq!(creadas[0].id_vendedor, vendedor);
+        assert_eq!(creadas[0].precio_total, 200);
+        assert_eq!(creadas[0].lineas, vec![LineaOrden::new(0, 2, 100)]);
+
+        let transiciones = eventos_emitidos::<EstadoOrdenActualizado>();
+        assert_eq!(transiciones.len(), 2);
+        assert_eq!(transiciones[0].anterior, EstadoOrden::Pendiente);
+        assert_eq!(transiciones[0].nuevo, EstadoOrden::Enviada);
+        assert_eq!(transiciones[0].actor, vendedor);
+        assert_eq!(transiciones[1].anterior, EstadoOrden::Enviada);
+        assert_eq!(transiciones[1].nuevo, EstadoOrden::Recibida);
+        assert_eq!(transiciones[1].actor, comprador);
+    }
 
-            [JULES]
-            Le big Mac! Ahhaha, what do they call a Whopper?
+    #[ink::test]
+    fn test_eventos_cancelacion_de_orden() {
+        let (mut contrato, comprador, vendedor) = setup_publicacion();
 
-            [VINCENT]
-            I dunno, I didn't go into a Burger King.".to_string()
-        );
-        assert_eq!(
-            result,
-            Ok(String::from("la categoria fue registrada correctamente")),
-            "deberia poder manejar nombres muy largos, truncandolos en 100 caracteres"
-        );
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = contrato.crear_orden(0, 1).unwrap();
+        contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
+        set_caller(vendedor);
+        contrato.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
+
+        let transiciones = eventos_emitidos::<EstadoOrdenActualizado>();
+        assert_eq!(transiciones.len(), 2);
+        assert_eq!(transiciones[0].nuevo, EstadoOrden::PreCancelada);
+        assert_eq!(transiciones[0].actor, comprador);
+        assert_eq!(transiciones[1].anterior, EstadoOrden::PreCancelada);
+        assert_eq!(transiciones[1].nuevo, EstadoOrden::Cancelada);
+        assert_eq!(transiciones[1].actor, vendedor);
     }
 
     #[ink::test]
-    fn test_categoria_indice_correcto_por_nombre() {
-        let sist = contrato_con_categorias_cargada();
-        assert_eq!(
-            sist.get_categoria_by_name(&"categoria 9".to_string()),
-            Ok(9),
-            "deberia devolver el indice correcto"
-        );
-        assert_eq!(
-            sist.get_categoria_by_name(&"categoria 3".to_string()),
-            Ok(3),
-            "deberia devolver el indice correcto"
-        );
-        assert_eq!(
-            sist.get_categoria_by_name(&"      categoria 4       ".to_string()),
-            Ok(4),
-            "deberia devolver el indice correcto incluso con whitespace"
-        );
-        assert_eq!(
-            sist.get_categoria_by_name(&"cAtEGoRiA 5".to_string()),
-            Ok(5),
-            "deberia devolver el indice correcto incluso con mayusculas"
-        );
+    fn test_eventos_calificaciones() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
+
+        set_caller(comprador);
+        sistema.calificar_compra(id_orden, 5).unwrap();
+        set_caller(vendedor);
+        sistema.calificar_compra(id_orden, 3).unwrap();
+
+        let calificaciones = eventos_emitidos::<CalificacionRegistrada>();
+        assert_eq!(calificaciones.len(), 2);
+        assert_eq!(calificaciones[0].calificador, comprador);
+        assert_eq!(calificaciones[0].calificado, vendedor);
+        assert_eq!(calificaciones[0].puntaje, 5);
+        assert_eq!(calificaciones[1].calificador, vendedor);
+        assert_eq!(calificaciones[1].calificado, comprador);
+        assert_eq!(calificaciones[1].puntaje, 3);
+    }
 
-        assert_eq!(
-            sist.get_categoria_by_name(&"Electrodomesticos".to_string()),
-            Err(ErroresContrato::CategoriaInexistente),
-            "deberia devolver que no encuentra la categoria"
-        );
+    #[ink::test]
+    fn test_eventos_de_orden_indexables_por_topic() {
+        let (_, id_orden, comprador, _) = setup_orden_recibida();
+
+        let evento = ink::env::test::recorded_events()
+            .find(|evento| {
+                evento.topics.first().map(|t| t.as_slice())
+                    == <OrdenCreada as ink::env::Event>::SIGNATURE_TOPIC
+                        .as_ref()
+                        .map(|t| t.as_slice())
+            })
+            .expect("no se emitió OrdenCreada");
+
+        assert!(evento.topics.contains(&topic(&id_orden)));
+        assert!(evento.topics.contains(&topic(&comprador)));
+    }
+
+    /// Sistema desplegado por `owner`, con `otro` registrado como comprador
+    fn setup_con_owner() -> (Sistema, AccountId, AccountId) {
+        let owner = AccountId::from([0x10; 32]);
+        let otro = id_comprador();
+        set_caller(owner);
+        let mut sistema = Sistema::new();
+        registrar_comprador(&mut sistema, otro);
+        (sistema, owner, otro)
     }
 
     #[ink::test]
-    fn test_categoria_get_categoria_whitespaces() {
-        let sist = contrato_con_categorias_cargada();
-        assert_eq!(
-            sist.get_categoria_by_name(&"      categoria 4       ".to_string()),
-            Ok(4),
-            "deberia devolver el indice correcto incluso con whitespace"
-        );
+    fn test_owner_es_quien_despliega() {
+        let (sistema, owner, otro) = setup_con_owner();
+
+        assert_eq!(sistema.get_owner(), owner);
+        assert!(sistema.es_admin(owner));
+        assert!(!sistema.es_admin(otro));
+        assert_eq!(sistema.get_owner_propuesto(), None);
     }
 
     #[ink::test]
-    fn test_categoria_get_categoria_case_sensitivity() {
-        let sist = contrato_con_categorias_cargada();
+    fn test_registrar_categoria_solo_admin() {
+        let (mut sistema, owner, otro) = setup_con_owner();
+
+        set_caller(otro);
         assert_eq!(
-            sist.get_categoria_by_name(&"cAtEGoRiA 5".to_string()),
-            Ok(5),
-            "deberia devolver el indice correcto incluso con mayusculas"
+            sistema.registrar_categoria("Basura".into()),
+            Err(ErroresContrato::NoEsAdmin)
         );
+        assert!(sistema.listar_categorias(0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+
+        set_caller(owner);
+        assert!(sistema.registrar_categoria("Libros".into()).is_ok());
+
+        set_caller(owner);
+        sistema.agregar_admin(otro).unwrap();
+        set_caller(otro);
+        assert!(sistema.registrar_categoria("Ropa".into()).is_ok());
+        assert_eq!(sistema.listar_categorias(0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 2);
     }
 
     #[ink::test]
-    fn test_categoria_get_categoria_inexistente() {
-        let sist = contrato_con_categorias_cargada();
+    fn test_agregar_y_quitar_admin() {
+        let (mut sistema, owner, otro) = setup_con_owner();
+
+        set_caller(owner);
+        sistema.agregar_admin(otro).unwrap();
+        assert!(sistema.es_admin(otro));
+        assert_eq!(sistema.agregar_admin(otro), Err(ErroresContrato::YaEsAdmin));
+        assert_eq!(sistema.agregar_admin(owner), Err(ErroresContrato::YaEsAdmin));
+
+        sistema.quitar_admin(otro).unwrap();
+        assert!(!sistema.es_admin(otro));
+        assert_eq!(sistema.quitar_admin(otro), Err(ErroresContrato::NoEsAdmin));
+
+        set_caller(otro);
         assert_eq!(
-            sist.get_categoria_by_name(&"Electrodomesticos".to_string()),
-            Err(ErroresContrato::CategoriaInexistente),
-            "deberia devolver que no encuentra la categoria"
+            sistema.registrar_categoria("Ropa".into()),
+            Err(ErroresContrato::NoEsAdmin)
         );
+
+        let agregados = eventos_emitidos::<AdminAgregado>();
+        let quitados = eventos_emitidos::<AdminQuitado>();
+        assert_eq!(agregados.len(), 1);
+        assert_eq!(quitados.len(), 1);
+        assert_eq!(quitados[0].id, otro);
     }
 
     #[ink::test]
-    fn test_categoria_clean_name() {
-        let sist = setup_sistema();
-        assert_eq!(
-            sist.clean_cat_name(&"Electrodomésticos".to_string()),
-            Ok("electrodomésticos".to_string())
-        );
+    fn test_solo_owner_gestiona_admins() {
+        let (mut sistema, owner, otro) = setup_con_owner();
+        let tercero = id_vendedor();
+
+        set_caller(owner);
+        sistema.agregar_admin(otro).unwrap();
+
+        // un admin no puede otorgar ni revocar permisos
+        set_caller(otro);
+        assert_eq!(sistema.agregar_admin(tercero), Err(ErroresContrato::NoEsOwner));
+        assert_eq!(sistema.quitar_admin(otro), Err(ErroresContrato::NoEsOwner));
+        assert_eq!(sistema.proponer_owner(otro), Err(ErroresContrato::NoEsOwner));
     }
 
     #[ink::test]
-    fn test_categoria_clean_name_whitespaces() {
-        let sist = setup_sistema();
-        assert_eq!(
-            sist.clean_cat_name(&"      cocina        ".to_string()),
-            Ok("cocina".to_string())
-        );
+    fn test_transferencia_owner_en_dos_pasos() {
+        let (mut sistema, owner, otro) = setup_con_owner();
+
+        set_caller(owner);
+        sistema.proponer_owner(otro).unwrap();
+        // hasta que se acepte el owner no cambia
+        assert_eq!(sistema.get_owner(), owner);
+        assert_eq!(sistema.get_owner_propuesto(), Some(otro));
+
+        set_caller(otro);
+        sistema.aceptar_owner().unwrap();
+        assert_eq!(sistema.get_owner(), otro);
+        assert_eq!(sistema.get_owner_propuesto(), None);
+        assert!(!sistema.es_admin(owner));
+
+        // el owner anterior ya no puede gestionar el contrato
+        set_caller(owner);
+        assert_eq!(sistema.agregar_admin(owner), Err(ErroresContrato::NoEsOwner));
+
+        let transferencias = eventos_emitidos::<OwnerTransferido>();
+        assert_eq!(transferencias.len(), 1);
+        assert_eq!(transferencias[0].anterior, owner);
+        assert_eq!(transferencias[0].nuevo, otro);
     }
 
     #[ink::test]
-    fn test_categoria_clean_name_empty() {
-        let sist = setup_sistema();
+    fn test_aceptar_owner_errores() {
+        let (mut sistema, owner, otro) = setup_con_owner();
+        let tercero = id_vendedor();
+
+        set_caller(otro);
         assert_eq!(
-            sist.clean_cat_name(&"".to_string()),
-            Err(ErroresContrato::NombreCategoriaVacio)
+            sistema.aceptar_owner(),
+            Err(ErroresContrato::SinTransferenciaPendiente)
         );
-    }
 
-    #[ink::test]
-    fn test_categoria_clean_name_max_characters() {
-        let sist = setup_sistema();
-        assert_eq!(sist.clean_cat_name(&"
-            You know what they call a  Quarter Pounder with Cheese in Paris?
+        set_caller(owner);
+        sistema.proponer_owner(tercero).unwrap();
+        // una propuesta equivocada se corrige proponiendo de nuevo
+        sistema.proponer_owner(otro).unwrap();
 
-            [JULES]
-            They don't call it a Quarter Pounder with Cheese?
+        set_caller(tercero);
+        assert_eq!(sistema.aceptar_owner(), Err(ErroresContrato::NoEsOwnerPropuesto));
+        assert_eq!(sistema.get_owner(), owner);
 
-            [VINCENT]
-            No, they got the metric system there, they wouldn't know what the fuck a Quarter Pounder is.
+        set_caller(otro);
+        assert!(sistema.aceptar_owner().is_ok());
+    }
 
-            [JULES]
-            Then what do they call it?
+    /// Orden de 2 unidades (precio total 200) en estado `Enviada` y un árbitro registrado
+    fn setup_orden_enviada() -> (Sistema, u32, AccountId, AccountId, AccountId) {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let arbitro = AccountId::from([0x20; 32]);
+        set_caller(sistema.get_owner());
+        sistema.registrar_arbitro(arbitro).unwrap();
 
-            [VINCENT]
-            They call it Royale with Cheese.
+        set_caller(comprador);
+        pagar(200);
+        let id_orden = sistema.crear_orden(0, 2).unwrap();
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
 
-            [JULES]
-            Royale with Cheese. What do they call a Big Mac?
+        (sistema, id_orden, comprador, vendedor, arbitro)
+    }
 
-            [VINCENT]
-            Big Mac's a Big Mac, but they call it Le Big Mac.
+    #[ink::test]
+    fn test_registrar_arbitro_solo_admin() {
+        let (mut sistema, comprador, _) = setup_publicacion();
+        let arbitro = AccountId::from([0x20; 32]);
 
-            [JULES]
-            Le big Mac! Ahhaha, what do they call a Whopper?
+        set_caller(comprador);
+        assert_eq!(sistema.registrar_arbitro(arbitro), Err(ErroresContrato::NoEsAdmin));
 
-            [VINCENT]
-            I dunno, I didn't go into a Burger King.".to_string()
-        ),
-            Ok("you know what they call a  quarter pounder with cheese in paris?
+        set_caller(sistema.get_owner());
+        sistema.registrar_arbitro(arbitro).unwrap();
+        assert!(sistema.es_arbitro(arbitro));
+        assert_eq!(sistema.registrar_arbitro(arbitro), Err(ErroresContrato::YaEsArbitro));
 
-            [jules]
-            th".to_string())
-        );
+        set_caller(comprador);
+        assert_eq!(sistema.quitar_arbitro(arbitro), Err(ErroresContrato::NoEsAdmin));
+        set_caller(sistema.get_owner());
+        sistema.quitar_arbitro(arbitro).unwrap();
+        assert!(!sistema.es_arbitro(arbitro));
+        assert_eq!(sistema.quitar_arbitro(arbitro), Err(ErroresContrato::NoEsArbitro));
     }
 
-    //_crear_producto
-    //si el producto es exitoso (no duplicado y es vendedor)
     #[ink::test]
-    fn test_crear_producto_exitoso() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+    fn test_abrir_disputa_desde_enviada() {
+        let (mut sistema, id_orden, comprador, _, _) = setup_orden_enviada();
 
-        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::EnDisputa);
+        let disputa = orden.get_disputa().unwrap();
+        assert_eq!(disputa.get_abierta_por(), comprador);
+        assert_eq!(disputa.get_estado_previo(), EstadoOrden::Enviada);
+        assert_eq!(disputa.get_fallo(), None);
+        assert_eq!(disputa.get_arbitro(), None);
+        assert_eq!(eventos_emitidos::<DisputaAbierta>().len(), 1);
+    }
 
-        assert!(res.is_ok());
+    #[ink::test]
+    fn test_abrir_disputa_desde_precancelada_por_vendedor() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
 
-        let productos = sistema._listar_productos();
-        assert_eq!(productos.len(), 1);
+        set_caller(vendedor);
+        sistema.abrir_disputa(id_orden).unwrap();
 
-        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
-        assert_eq!(productos[0], esperado);
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::EnDisputa);
+        assert_eq!(
+            orden.get_disputa().unwrap().get_estado_previo(),
+            EstadoOrden::PreCancelada
+        );
+        // mientras está en disputa el vendedor ya no puede confirmar la cancelación
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador),
+            Err(ErroresContrato::OrdenNoPendiente)
+        );
     }
 
-    //test falla por que el producto esta duplicado
     #[ink::test]
-    fn test_crear_producto_falla_si_ya_esxiste() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
+    fn test_abrir_disputa_errores() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
 
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+        // una orden pendiente todavía se puede cancelar, no admite disputa
+        assert_eq!(sistema.abrir_disputa(id_orden), Err(ErroresContrato::OrdenNoDisputable));
+        assert_eq!(sistema.abrir_disputa(42), Err(ErroresContrato::OrdenInexistente));
 
-        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
-        assert!(res.is_ok());
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(AccountId::from([0x30; 32]));
+        assert_eq!(sistema.abrir_disputa(id_orden), Err(ErroresContrato::UsuarioNoCorresponde));
 
-        let res2 =
-            sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
-        assert!(res2.is_err());
-        assert_eq!(res2.unwrap_err(), ErroresContrato::ProductoYaExistente);
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+        assert_eq!(sistema.abrir_disputa(id_orden), Err(ErroresContrato::OrdenNoDisputable));
     }
-    //test falla por que el usuario no es vendedor
+
     #[ink::test]
-    fn test_crear_producto_falla_si_es_comprador() {
-        let mut sistema = setup_sistema();
-        let id = id_comprador();
+    fn test_aportar_evidencia_ambas_partes() {
+        let (mut sistema, id_orden, comprador, vendedor, _) = setup_orden_enviada();
+        let hash_comprador = Hash::from([0xaa; 32]);
+        let hash_vendedor = Hash::from([0xbb; 32]);
 
-        registrar_comprador(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+        set_caller(comprador);
+        assert_eq!(
+            sistema.aportar_evidencia(id_orden, hash_comprador),
+            Err(ErroresContrato::OrdenNoEnDisputa)
+        );
+        sistema.abrir_disputa(id_orden).unwrap();
+        sistema.aportar_evidencia(id_orden, hash_comprador).unwrap();
+        set_caller(vendedor);
+        sistema.aportar_evidencia(id_orden, hash_vendedor).unwrap();
+        set_caller(AccountId::from([0x30; 32]));
+        assert_eq!(
+            sistema.aportar_evidencia(id_orden, hash_vendedor),
+            Err(ErroresContrato::UsuarioNoCorresponde)
+        );
 
-        let res = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
-        assert!(res.is_err());
-        assert_eq!(res.unwrap_err(), ErroresContrato::UsuarioNoEsVendedor);
+        let disputa = sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize].get_disputa().unwrap();
+        assert_eq!(disputa.get_evidencia_comprador(), vec![hash_comprador]);
+        assert_eq!(disputa.get_evidencia_vendedor(), vec![hash_vendedor]);
+        assert_eq!(eventos_emitidos::<EvidenciaAportada>().len(), 2);
     }
 
-    //producto_existe (caso existente)
     #[ink::test]
-    fn test_producto_existe() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
+    fn test_aportar_evidencia_maximo() {
+        let (mut sistema, id_orden, comprador, _, _) = setup_orden_enviada();
 
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+        for i in 0..MAX_EVIDENCIAS {
+            sistema.aportar_evidencia(id_orden, Hash::from([i as u8; 32])).unwrap();
+        }
+        assert_eq!(
+            sistema.aportar_evidencia(id_orden, Hash::from([0xff; 32])),
+            Err(ErroresContrato::MaxEvidenciasAlcanzado)
+        );
+    }
 
-        let _ = sistema._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10);
+    #[ink::test]
+    fn test_resolver_disputa_a_favor_del_comprador() {
+        let (mut sistema, id_orden, comprador, _, arbitro) = setup_orden_enviada();
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+        let saldo_comprador = saldo(comprador);
+        let saldo_inicial = saldo_contrato();
 
-        // Armamos el producto igual al creado
-        let producto = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+        set_caller(arbitro);
+        sistema.resolver_disputa(id_orden, FalloDisputa::Comprador).unwrap();
 
-        let existe = sistema.producto_existe(&producto);
-        assert!(existe);
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::Cancelada);
+        let disputa = orden.get_disputa().unwrap();
+        assert_eq!(disputa.get_fallo(), Some(FalloDisputa::Comprador));
+        assert_eq!(disputa.get_arbitro(), Some(arbitro));
+        // stock devuelto a la publicación y pago reembolsado
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
+        assert_eq!(saldo(comprador), saldo_comprador + 200);
+        assert_eq!(saldo_contrato(), saldo_inicial - 200);
+
+        let resueltas = eventos_emitidos::<DisputaResuelta>();
+        assert_eq!(resueltas.len(), 1);
+        assert_eq!(resueltas[0].arbitro, arbitro);
     }
-    //producto_existe (caso inexistente)
+
     #[ink::test]
-    fn test_producto_no_existe() {
-        let sistema = setup_sistema();
-        let id = id_vendedor();
-        let producto = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
+    fn test_resolver_disputa_a_favor_del_vendedor() {
+        let (mut sistema, id_orden, comprador, vendedor, arbitro) = setup_orden_enviada();
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+        let saldo_vendedor = saldo(vendedor);
 
-        let existe = sistema.producto_existe(&producto);
-        assert!(!existe);
+        set_caller(arbitro);
+        sistema.resolver_disputa(id_orden, FalloDisputa::Vendedor).unwrap();
+
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::Recibida);
+        assert_eq!(orden.get_disputa().unwrap().get_fallo(), Some(FalloDisputa::Vendedor));
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 8);
+        assert_eq!(saldo(vendedor), saldo_vendedor + 200);
+
+        // una vez resuelta, la orden se puede calificar normalmente
+        set_caller(comprador);
+        assert!(sistema.calificar_compra(id_orden, 2).is_ok());
     }
 
-    // Test de descuento de stock de producto a través de crear_publicacion
     #[ink::test]
-    fn test_descontar_stock_producto_exitoso() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
+    fn test_resolver_disputa_errores() {
+        let (mut sistema, id_orden, comprador, vendedor, arbitro) = setup_orden_enviada();
 
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+        set_caller(arbitro);
+        assert_eq!(
+            sistema.resolver_disputa(id_orden, FalloDisputa::Comprador),
+            Err(ErroresContrato::OrdenNoEnDisputa)
+        );
 
-        sistema
-            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10)
-            .unwrap();
+        set_caller(comprador);
+        sistema.abrir_disputa(id_orden).unwrap();
+        assert_eq!(
+            sistema.resolver_disputa(id_orden, FalloDisputa::Comprador),
+            Err(ErroresContrato::NoEsArbitro)
+        );
 
-        // El descuento se hace automáticamente al crear la publicación
-        let res = sistema._crear_publicacion(0, id, 3, 1000);
-        assert!(res.is_ok());
+        // un árbitro no puede fallar en una orden de la que es parte
+        set_caller(sistema.get_owner());
+        sistema.registrar_arbitro(vendedor).unwrap();
+        set_caller(vendedor);
+        assert_eq!(
+            sistema.resolver_disputa(id_orden, FalloDisputa::Vendedor),
+            Err(ErroresContrato::ArbitroEsParte)
+        );
 
-        // Verificar que el stock del producto se redujo
-        let productos = sistema._listar_productos();
-        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 7);
-        assert_eq!(productos[0], esperado);
-    }
-    // Test de falla al intentar crear publicacion con producto inexistente
-    #[ink::test]
-    fn test_descontar_stock_falla_producto_inexistente() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        registrar_vendedor(&mut sistema, id);
-        // Intentar crear publicación con producto inexistente
-        let res = sistema._crear_publicacion(99, id, 1, 1000);
-        assert!(res.is_err());
-        assert_eq!(res.unwrap_err(), ErroresContrato::ProductoInexistente);
+        set_caller(arbitro);
+        sistema.resolver_disputa(id_orden, FalloDisputa::Vendedor).unwrap();
+        assert_eq!(
+            sistema.resolver_disputa(id_orden, FalloDisputa::Comprador),
+            Err(ErroresContrato::OrdenNoEnDisputa)
+        );
     }
-    // Test de falla por stock insuficiente al crear publicacion
-    #[ink::test]
-    fn test_descontar_stock_falla_stock_insuficiente() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
 
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+    /// Publicación 0 (stock 10, precio 100) y publicación 1 (stock 5, precio 50) del mismo
+    /// vendedor, y publicación 2 de otro vendedor
+    fn setup_carrito() -> (Sistema, AccountId, AccountId) {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        sistema
+            ._crear_producto(vendedor, "Go".into(), "Desc".into(), "Libros".into(), 5)
+            .unwrap();
+        sistema._crear_publicacion(1, vendedor, 5, 50).unwrap();
 
+        let otro_vendedor = AccountId::from([0x40; 32]);
         sistema
-            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 5)
+            ._registrar_usuario(otro_vendedor, "Otro".into(), "otro@gmail.com".into(), Rol::Vendedor)
+            .unwrap();
+        sistema
+            ._crear_producto(otro_vendedor, "C".into(), "Desc".into(), "Libros".into(), 5)
             .unwrap();
+        sistema._crear_publicacion(2, otro_vendedor, 5, 10).unwrap();
 
-        // Intentar crear publicación con más stock del disponible
-        let res = sistema._crear_publicacion(0, id, 10, 1000);
-        assert!(res.is_err());
-        assert_eq!(res.unwrap_err(), ErroresContrato::StockInsuficiente);
+        set_caller(comprador);
+        (sistema, comprador, vendedor)
     }
-    #[ink::test]
-    fn test_listar_productos_con_productos() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
 
-        sistema
-            ._crear_producto(id, "Zapatilla".into(), "desc".into(), "Ropa".into(), 10)
-            .unwrap();
+    #[ink::test]
+    fn test_agregar_al_carrito() {
+        let (mut sistema, _, _) = setup_carrito();
 
-        let productos = sistema._listar_productos();
-        assert_eq!(productos.len(), 1);
+        sistema.agregar_al_carrito(0, 2).unwrap();
+        sistema.agregar_al_carrito(1, 1).unwrap();
+        // agregar una publicación que ya estaba suma la cantidad
+        sistema.agregar_al_carrito(0, 3).unwrap();
 
-        let esperado = Producto::new(0, id, "Zapatilla".into(), "desc".into(), 0, 10);
-        assert_eq!(productos[0], esperado); //modificado
+        assert_eq!(
+            sistema.ver_carrito(),
+            vec![ItemCarrito::new(0, 5), ItemCarrito::new(1, 1)]
+        );
     }
-    /// Test para listar productos sin productos registrado
+
     #[ink::test]
-    fn test_listar_productos_sin_productos() {
-        let sistema = setup_sistema();
+    fn test_agregar_al_carrito_errores() {
+        let (mut sistema, _, vendedor) = setup_carrito();
 
-        let res = sistema._listar_productos();
-        assert!(res.is_empty());
+        assert_eq!(
+            sistema.agregar_al_carrito(0, 0),
+            Err(ErroresContrato::CantidadEnCarritoMenorAUno)
+        );
+        assert_eq!(
+            sistema.agregar_al_carrito(9, 1),
+            Err(ErroresContrato::PublicacionNoExiste)
+        );
+        assert_eq!(
+            sistema.agregar_al_carrito(1, 6),
+            Err(ErroresContrato::StockInsuficiente)
+        );
+
+        sistema.agregar_al_carrito(0, 1).unwrap();
+        assert_eq!(
+            sistema.agregar_al_carrito(2, 1),
+            Err(ErroresContrato::VendedorDistintoEnCarrito)
+        );
+
+        set_caller(vendedor);
+        assert_eq!(sistema.agregar_al_carrito(0, 1), Err(ErroresContrato::RolNoApropiado));
+        set_caller(AccountId::from([0x30; 32]));
+        assert_eq!(
+            sistema.agregar_al_carrito(0, 1),
+            Err(ErroresContrato::CuentaNoRegistrada)
+        );
     }
 
     #[ink::test]
-    fn registra_usuario_correctamente() {
-        let mut app = setup_sistema();
-        let (id_comprador, _) = build_testing_accounts();
+    fn test_actualizar_y_quitar_del_carrito() {
+        let (mut sistema, _, _) = setup_carrito();
+        sistema.agregar_al_carrito(0, 2).unwrap();
+        sistema.agregar_al_carrito(1, 1).unwrap();
 
-        assert!(
-            app._registrar_usuario(
-                id_comprador,
-                "user_name".to_string(),
-                "user_email".to_string(),
-                Rol::Ambos,
-            )
-            .is_ok(),
-            "Se esperaba que se registre un usuario"
+        sistema.actualizar_carrito(1, 4).unwrap();
+        assert_eq!(sistema.ver_carrito()[1], ItemCarrito::new(1, 4));
+        assert_eq!(
+            sistema.actualizar_carrito(1, 0),
+            Err(ErroresContrato::CantidadEnCarritoMenorAUno)
         );
-
-        let user_created = app.listar_usuarios(0, 1)[0].clone();
-
-        assert!(
-            !user_created.get_name().is_empty(),
-            "nombre de usuario no debe estar vacio"
+        assert_eq!(
+            sistema.actualizar_carrito(1, 9),
+            Err(ErroresContrato::StockInsuficiente)
         );
-        assert!(
-            !user_created.get_mail().is_empty(),
-            "email de usuario no debe estar vacio"
+        assert_eq!(
+            sistema.actualizar_carrito(2, 1),
+            Err(ErroresContrato::PublicacionNoEnCarrito)
         );
 
+        sistema.quitar_del_carrito(0).unwrap();
+        assert_eq!(sistema.ver_carrito(), vec![ItemCarrito::new(1, 4)]);
         assert_eq!(
-            user_created.get_name(),
-            "user_name".to_string(),
-            "el usuario deberia poseer el nombre que fue ingresado"
+            sistema.quitar_del_carrito(0),
+            Err(ErroresContrato::PublicacionNoEnCarrito)
         );
+
+        sistema.vaciar_carrito();
+        assert!(sistema.ver_carrito().is_empty());
+    }
+
+    #[ink::test]
+    fn test_confirmar_carrito_crea_una_orden_con_lineas() {
+        let (mut sistema, comprador, vendedor) = setup_carrito();
+        sistema.agregar_al_carrito(0, 2).unwrap();
+        sistema.agregar_al_carrito(1, 3).unwrap();
+
+        pagar(350);
+        let id_orden = sistema.confirmar_carrito().unwrap();
+
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
         assert_eq!(
-            user_created.get_mail(),
-            "user_email".to_string(),
-            "el usuario deberia poseer el email que fue ingresado"
+            orden.get_lineas(),
+            vec![LineaOrden::new(0, 2, 100), LineaOrden::new(1, 3, 50)]
         );
+        assert_eq!(orden.get_precio_total(), 350);
+        assert_eq!(orden.get_cantidad(), 5);
+        assert_eq!(orden.get_id_comprador(), comprador);
+        assert_eq!(orden.get_id_vendedor(), vendedor);
+
+        let publicaciones = sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(publicaciones[0].stock(), 8);
+        assert_eq!(publicaciones[1].stock(), 2);
+        assert!(sistema.ver_carrito().is_empty());
     }
 
     #[ink::test]
-    fn registra_usuario_nombre_de_usuario_vacio() {
-        let mut app = setup_sistema();
-        let (id_comprador, _) = build_testing_accounts();
-        assert_eq!(
-            app._registrar_usuario(
-                id_comprador,
-                String::new(),
-                "user_email_1".to_string(),
-                Rol::Comprador,
-            ),
-            Err(ErroresContrato::NombreUsuarioVacio)
-        )
+    fn test_confirmar_carrito_sin_stock_no_descuenta_ninguna_linea() {
+        let (mut sistema, comprador, _) = setup_carrito();
+        sistema.agregar_al_carrito(0, 2).unwrap();
+        sistema.agregar_al_carrito(1, 5).unwrap();
+
+        // otro pedido se lleva el stock de la publicación 1 antes de confirmar
+        pagar(50);
+        sistema.crear_orden(1, 1).unwrap();
+
+        set_caller(comprador);
+        pagar(450);
+        assert_eq!(sistema.confirmar_carrito(), Err(ErroresContrato::StockInsuficiente));
+
+        let publicaciones = sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(publicaciones[0].stock(), 10);
+        assert_eq!(publicaciones[1].stock(), 4);
+        assert_eq!(sistema.ver_carrito().len(), 2);
+        assert_eq!(sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 1);
     }
 
     #[ink::test]
-    fn registra_usuario_mail_vacio() {
-        let mut app = setup_sistema();
-        let (id_comprador, _) = build_testing_accounts();
-        assert_eq!(
-            app._registrar_usuario(
-                id_comprador,
-                "user_name".to_string(),
-                String::new(),
-                Rol::Comprador,
-            ),
-            Err(ErroresContrato::MailUsuarioVacio)
-        )
+    fn test_confirmar_carrito_errores() {
+        let (mut sistema, _, _) = setup_carrito();
+
+        assert_eq!(sistema.confirmar_carrito(), Err(ErroresContrato::ListaSinProductos));
+
+        sistema.agregar_al_carrito(0, 1).unwrap();
+        sistema.agregar_al_carrito(1, 1).unwrap();
+        pagar(100);
+        assert_eq!(sistema.confirmar_carrito(), Err(ErroresContrato::PagoInsuficiente));
+        pagar(200);
+        assert_eq!(sistema.confirmar_carrito(), Err(ErroresContrato::PagoExcedente));
+        assert_eq!(sistema.ver_carrito().len(), 2);
     }
 
     #[ink::test]
-    fn registra_usuario_nombre_ya_existente() {
-        let mut app = setup_sistema();
-        let id = id_vendedor();
+    fn test_cancelar_orden_de_carrito_devuelve_todas_las_lineas() {
+        let (mut sistema, comprador, vendedor) = setup_carrito();
+        sistema.agregar_al_carrito(0, 2).unwrap();
+        sistema.agregar_al_carrito(1, 3).unwrap();
+        pagar(350);
+        let id_orden = sistema.confirmar_carrito().unwrap();
+        let saldo_comprador = saldo(comprador);
+
+        sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
+        set_caller(vendedor);
+        sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador).unwrap();
 
-        app._registrar_usuario(
-            id,
-            "Vendedor".into(),
-            "vendedor@gmail.com".into(),
-            Rol::Vendedor,
-        )
-        .unwrap();
-        assert_eq!(
-            app._registrar_usuario(
-                id,
-                "Vendedor".into(),
-                "emailvendedor@gmail.com".into(),
-                Rol::Vendedor,
-            ),
-            Err(ErroresContrato::UsuarioYaExistente)
-        )
+        let publicaciones = sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(publicaciones[0].stock(), 10);
+        assert_eq!(publicaciones[1].stock(), 5);
+        assert_eq!(saldo(comprador), saldo_comprador + 350);
+    }
+
+    /// Pasa a un nuevo bloque cuyo timestamp es exactamente `ms` milisegundos posterior al actual
+    fn avanzar_tiempo(ms: u64) {
+        let ahora = ink::env::block_timestamp::<DefaultEnvironment>();
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(ahora + ms);
     }
 
     #[ink::test]
-    fn registra_usuario_mail_ya_existente() {
-        let mut app = setup_sistema();
-        let (id_comprador, _) = build_testing_accounts();
-        app._registrar_usuario(
-            id_comprador,
-            "user_name".to_string(),
-            "user_email".to_string(),
-            Rol::Comprador,
-        )
-        .expect("No se pudo registrar el usuario");
+    fn test_orden_registra_timestamps() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000);
 
-        assert_eq!(
-            app._registrar_usuario(
-                id_comprador,
-                "user_name11".to_string(),
-                "user_email".to_string(),
-                Rol::Vendedor,
-            ),
-            Err(ErroresContrato::MailYaExistente)
-        )
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        avanzar_tiempo(500);
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_creada_en(), 1_000);
+        assert_eq!(orden.get_enviada_en(), Some(1_500));
     }
 
     #[ink::test]
-    fn devuelve_user_con_id_correctamente() {
-        let (mut app, user_id, _) = build_testing_setup();
-        let expected = app.listar_usuarios(0, 1)[0].clone();
+    fn test_vencimiento_cancela_pendiente_sin_enviar() {
+        let (mut sistema, comprador, _) = setup_publicacion();
+        set_caller(comprador);
+        pagar(300);
+        let id_orden = sistema.crear_orden(0, 3).unwrap();
+        let saldo_comprador = saldo(comprador);
 
+        // cualquier cuenta puede procesar el vencimiento
+        set_caller(AccountId::from([0x30; 32]));
+        avanzar_tiempo(PLAZO_ENVIO_DEFAULT - 1);
         assert_eq!(
-            app.get_user(&user_id).unwrap().get_name(),
-            expected.get_name(),
-            "Se esperaba que el campo nombre coincida"
+            sistema.procesar_vencimiento(id_orden),
+            Err(ErroresContrato::PlazoNoVencido)
         );
+        avanzar_tiempo(1);
+        assert_eq!(sistema.procesar_vencimiento(id_orden), Ok(EstadoOrden::Cancelada));
+
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
+        assert_eq!(saldo(comprador), saldo_comprador + 300);
+    }
+
+    #[ink::test]
+    fn test_vencimiento_confirma_enviada_sin_confirmar() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        pagar(200);
+        let id_orden = sistema.crear_orden(0, 2).unwrap();
+        avanzar_tiempo(PLAZO_ENVIO_DEFAULT);
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        let saldo_vendedor = saldo(vendedor);
 
+        // el plazo de confirmación se cuenta desde el envío
+        avanzar_tiempo(PLAZO_CONFIRMACION_DEFAULT - 1);
         assert_eq!(
-            app.get_user(&user_id).unwrap().get_mail(),
-            expected.get_mail(),
-            "Se esperaba que el campo mail coincida"
+            sistema.procesar_vencimiento(id_orden),
+            Err(ErroresContrato::PlazoNoVencido)
         );
+        avanzar_tiempo(1);
+        assert_eq!(sistema.procesar_vencimiento(id_orden), Ok(EstadoOrden::Recibida));
+
+        assert_eq!(saldo(vendedor), saldo_vendedor + 200);
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 8);
+        // la orden confirmada automáticamente se puede calificar
+        set_caller(comprador);
+        assert!(sistema.calificar_compra(id_orden, 3).is_ok());
+    }
+
+    #[ink::test]
+    fn test_vencimiento_orden_sin_plazo() {
+        let (mut sistema, id_orden, _, _) = setup_orden_recibida();
+        avanzar_tiempo(PLAZO_CONFIRMACION_DEFAULT * 10);
 
         assert_eq!(
-            app.get_user(&user_id).unwrap().get_id(),
-            expected.get_id(),
-            "Se esperaba que el campo ID coincida"
+            sistema.procesar_vencimiento(id_orden),
+            Err(ErroresContrato::OrdenSinPlazo)
+        );
+        assert_eq!(
+            sistema.procesar_vencimiento(42),
+            Err(ErroresContrato::OrdenInexistente)
         );
     }
 
     #[ink::test]
-    fn devuelve_user_con_email_correctamente() {
-        let (app, _, _) = build_testing_setup();
-        let expected = app.listar_usuarios(0, 1)[0].clone();
+    fn test_configurar_plazos() {
+        let (mut sistema, comprador, _) = setup_publicacion();
 
-        assert!(
-            app.get_usuario_by_mail("not_existent_email@email.com")
-                .is_err(),
-            "Se esperaba un error si no existe un usuario con el email"
-        );
+        set_caller(comprador);
+        assert_eq!(sistema.configurar_plazos(10, 20), Err(ErroresContrato::NoEsAdmin));
+
+        set_caller(sistema.get_owner());
+        assert_eq!(sistema.configurar_plazos(0, 20), Err(ErroresContrato::PlazoInvalido));
+        sistema.configurar_plazos(10, 20).unwrap();
+        assert_eq!(sistema.get_plazos(), (10, 20));
+
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        avanzar_tiempo(10);
+        assert_eq!(sistema.procesar_vencimiento(id_orden), Ok(EstadoOrden::Cancelada));
+    }
+
+    /// Orden pendiente de 3 unidades (precio total 300) sobre la publicación 0
+    fn setup_orden_pendiente() -> (Sistema, u32, AccountId, AccountId) {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        pagar(300);
+        let id_orden = sistema.crear_orden(0, 3).unwrap();
+        (sistema, id_orden, comprador, vendedor)
+    }
+
+    #[ink::test]
+    fn test_vendedor_propone_cancelacion_y_comprador_acepta() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
+        let saldo_comprador = saldo(comprador);
+
+        set_caller(vendedor);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::SinStock)
+            .unwrap();
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::PreCancelada);
+        let solicitud = orden.get_cancelacion().unwrap();
+        assert_eq!(solicitud.get_propuesta_por(), vendedor);
+        assert_eq!(solicitud.get_motivo(), MotivoCancelacion::SinStock);
+        assert_eq!(solicitud.get_estado_previo(), EstadoOrden::Pendiente);
 
+        set_caller(comprador);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::Otro)
+            .unwrap();
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::Cancelada);
+        // el motivo registrado es el de la propuesta
         assert_eq!(
-            app.get_usuario_by_mail(&expected.get_mail())
-                .unwrap()
-                .get_name(),
-            expected.get_name(),
-            "Se esperaba que el campo nombre coincida"
+            orden.get_cancelacion().unwrap().get_motivo(),
+            MotivoCancelacion::SinStock
         );
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 10);
+        assert_eq!(saldo(comprador), saldo_comprador + 300);
+
+        let propuestas = eventos_emitidos::<CancelacionPropuesta>();
+        assert_eq!(propuestas.len(), 1);
+        assert_eq!(propuestas[0].propuesta_por, vendedor);
+        assert_eq!(propuestas[0].motivo, MotivoCancelacion::SinStock);
+    }
 
+    #[ink::test]
+    fn test_quien_propone_no_puede_confirmar_la_cancelacion() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
+
+        set_caller(vendedor);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::SinStock)
+            .unwrap();
         assert_eq!(
-            app.get_usuario_by_mail(&expected.get_mail())
-                .unwrap()
-                .get_mail(),
-            expected.get_mail(),
-            "Se esperaba que el campo mail coincida"
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::SinStock),
+            Err(ErroresContrato::CancelacionDeOrdenSinConsenso)
         );
 
+        set_caller(comprador);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::SinStock)
+            .unwrap();
         assert_eq!(
-            app.get_usuario_by_mail(&expected.get_mail())
-                .unwrap()
-                .get_id(),
-            expected.get_id(),
-            "Se esperaba que el campo ID coincida"
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::SinStock),
+            Err(ErroresContrato::OrdenYaCancelada)
         );
     }
 
     #[ink::test]
-    fn asigna_rol_correctamente() {
-        let (mut app, user_id_comprador, user_id_vendedor) = build_testing_setup();
+    fn test_rechazar_cancelacion_vuelve_al_estado_previo() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
+        let saldo_inicial = saldo_contrato();
 
-        assert!(
-            app._asignar_rol(user_id_comprador, Rol::Vendedor).is_ok(),
-            "Se esperaba que se asigne el rol correctamente"
-        );
+        set_caller(comprador);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador)
+            .unwrap();
+        set_caller(vendedor);
+        sistema.rechazar_cancelacion(id_orden).unwrap();
 
-        assert!(
-            app._asignar_rol(user_id_vendedor, Rol::Comprador).is_ok(),
-            "Se esperaba que se asigne el rol correctamente"
-        );
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_status(), EstadoOrden::Pendiente);
+        assert_eq!(orden.get_cancelacion(), None);
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[0].stock(), 7);
+        assert_eq!(saldo_contrato(), saldo_inicial);
 
-        assert_eq!(
-            app._asignar_rol(user_id_vendedor, Rol::Comprador),
-            Err(ErroresContrato::AlreadyHasRol),
-            "Se esperaba error AlreadyHasRol si el usuario ya tiene el rol asignado"
-        );
+        // la orden sigue su curso normal
+        assert!(sistema.enviar_producto(id_orden).is_ok());
+
+        let rechazos = eventos_emitidos::<CancelacionRechazada>();
+        assert_eq!(rechazos.len(), 1);
+        assert_eq!(rechazos[0].rechazada_por, vendedor);
     }
 
     #[ink::test]
-    fn asigna_ambos_roles_correctamente() {
-        let (mut app, user_id_comprador, user_id_vendedor) = build_testing_setup();
+    fn test_rechazar_cancelacion_errores() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
 
-        assert!(
-            app._asignar_rol(user_id_comprador, Rol::Ambos).is_ok(),
-            "Se esperaba que se asigne el rol faltante correctamente"
+        set_caller(vendedor);
+        assert_eq!(
+            sistema.rechazar_cancelacion(id_orden),
+            Err(ErroresContrato::SinCancelacionPendiente)
         );
 
-        assert!(
-            app._asignar_rol(user_id_vendedor, Rol::Ambos).is_ok(),
-            "Se esperaba que se asigne el rol faltante correctamente"
+        set_caller(comprador);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::ProblemaDePago)
+            .unwrap();
+        assert_eq!(
+            sistema.rechazar_cancelacion(id_orden),
+            Err(ErroresContrato::CancelacionPropia)
         );
-
+        set_caller(AccountId::from([0x30; 32]));
         assert_eq!(
-            app._asignar_rol(user_id_vendedor, Rol::Ambos),
-            Err(ErroresContrato::AlreadyHasRol),
-            "Se esperaba error AlreadyHasRol si el usuario ya tiene los roles asignados"
+            sistema.rechazar_cancelacion(id_orden),
+            Err(ErroresContrato::UsuarioNoCorresponde)
+        );
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::Otro),
+            Err(ErroresContrato::CuentaNoRegistrada)
         );
     }
 
     #[ink::test]
-    fn listar_usuarios_paginado_correctamente() {
-        let (app, user1_id, user2_id) = build_testing_setup();
-        assert_eq!(app._get_cantidad_usuarios(), 2);
-        
-        let pagina_1 = app.listar_usuarios(1, 1);
-        assert_eq!(pagina_1.len(), 1);
-        assert_eq!(pagina_1[0].get_id(), user1_id);
+    fn test_cancelar_orden_enviada_falla() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
 
-        let pagina_2 = app.listar_usuarios(2, 1);
-        assert_eq!(pagina_2.len(), 1);
-        assert_eq!(pagina_2[0].get_id(), user2_id);
-        
-        let todos = app.listar_usuarios(0, 10);
-        assert_eq!(todos.len(), 2);
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::SinStock),
+            Err(ErroresContrato::OrdenNoPendiente)
+        );
+        set_caller(comprador);
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::DemoraEnEnvio),
+            Err(ErroresContrato::OrdenNoPendiente)
+        );
     }
 
     #[ink::test]
-    fn listar_usuarios_tamano_pagina_incorrectos() {
-        let (app, _user1_id, _user2_id) = build_testing_setup();
-        
-        let pagina_1 = app.listar_usuarios(0, 0);
-        assert_eq!(pagina_1.len(), 2);
+    fn test_mensajes_devuelven_resultados_tipados() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_pendiente();
+
+        set_caller(sistema.get_owner());
+        assert_eq!(sistema.registrar_categoria("Ropa".into()), Ok(1));
+
+        set_caller(comprador);
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador),
+            Ok(ResultadoCancelacion::CancelacionIniciada)
+        );
+        set_caller(vendedor);
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador),
+            Ok(ResultadoCancelacion::CancelacionConfirmada)
+        );
 
-        let pagina_2 = app.listar_usuarios(2, 4294967295);
-        assert_eq!(pagina_2.len(), 0);
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        set_caller(vendedor);
+        assert_eq!(sistema.enviar_producto(id_orden), Ok(EstadoOrden::Enviada));
+        set_caller(comprador);
+        assert_eq!(sistema.recibir_producto(id_orden), Ok(EstadoOrden::Recibida));
+        assert_eq!(sistema.calificar_compra(id_orden, 5), Ok(()));
+        assert_eq!(sistema.asignar_rol(Rol::Vendedor), Ok(()));
+    }
 
+    /// Compra una unidad de la publicación 0 y completa la orden calificando a ambas partes
+    fn comprar_y_calificar(
+        sistema: &mut Sistema,
+        (comprador, vendedor): (AccountId, AccountId),
+        puntaje_vendedor: u8,
+        puntaje_comprador: u8,
+    ) {
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        sistema.calificar_compra(id_orden, puntaje_vendedor).unwrap();
+        set_caller(vendedor);
+        sistema.calificar_compra(id_orden, puntaje_comprador).unwrap();
     }
 
-    ///Tests gestion orden
     #[ink::test]
-    fn test_crear_orden_con_exito() {
-        let mut contrato = setup_sistema();
-        let (id_comprador, id_vendedor) = build_testing_accounts();
-
-        registrar_comprador(&mut contrato, id_comprador);
-
-        registrar_vendedor(&mut contrato, id_vendedor);
-
-        contrato._registrar_categoria("Libros".to_string()).unwrap();
-        contrato
-            ._crear_producto(
-                id_vendedor,
-                "Rust Book".to_string(),
-                "Desc libro".to_string(),
-                "Libros".to_string(),
-                10,
-            )
-            .unwrap();
-        contrato
-            ._crear_publicacion(0, id_vendedor, 10, 100)
-            .unwrap();
+    fn test_get_reputacion_de_cualquier_usuario() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 5, 2);
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 4, 3);
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 4, 3);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(id_comprador); //setea el caller en Comprador
+        // la consulta la puede hacer cualquier cuenta, sin estar registrada
+        set_caller(AccountId::from([0x30; 32]));
+        let reputacion = sistema.get_reputacion(vendedor, 2).unwrap();
 
-        let result = contrato.crear_orden(0, 2);
-        assert!(result.is_ok(), "Error al crear la orden");
+        assert_eq!(reputacion.id, vendedor);
+        assert_eq!(
+            reputacion.como_vendedor,
+            Reputacion {
+                suma: 13,
+                cantidad: 3,
+                promedio: Some(433),
+                suma_ponderada: 1300,
+                peso_total: 300,
+                promedio_ponderado: Some(433),
+                promedio_reciente: Some(433),
+                decimales: 2,
+                estrellas: [0, 0, 0, 2, 1],
+            }
+        );
+        assert_eq!(reputacion.como_comprador.cantidad, 0);
+        assert_eq!(reputacion.como_comprador.promedio, None);
 
-        let ordenes = contrato.listar_ordenes();
-        assert_eq!(ordenes.len(), 1);
-        assert_eq!(ordenes[0].get_cantidad(), 2);
-        assert_eq!(ordenes[0].get_status(), EstadoOrden::Pendiente);
+        let reputacion = sistema.get_reputacion(comprador, 0).unwrap();
+        assert_eq!(reputacion.como_comprador.suma, 8);
+        assert_eq!(reputacion.como_comprador.promedio, Some(2));
+        assert_eq!(reputacion.como_comprador.estrellas, [0, 1, 2, 0, 0]);
     }
 
     #[ink::test]
-    fn test_crear_orden_con_cantidad_cero_fallido() {
-        let mut contrato = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
+    fn test_get_reputacion_precision() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 5, 1);
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 4, 1);
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 4, 1);
+
+        let promedio = |decimales| {
+            sistema
+                .get_reputacion(vendedor, decimales)
+                .unwrap()
+                .como_vendedor
+                .promedio
+        };
+        assert_eq!(promedio(0), Some(4));
+        assert_eq!(promedio(1), Some(43));
+        assert_eq!(promedio(MAX_DECIMALES_PROMEDIO), Some(4_333_333));
+        assert_eq!(
+            sistema.get_reputacion(vendedor, MAX_DECIMALES_PROMEDIO + 1),
+            Err(ErroresContrato::PrecisionInvalida)
+        );
+    }
 
-        registrar_vendedor(&mut contrato, vendedor);
+    #[ink::test]
+    fn test_get_reputacion_usuario_inexistente() {
+        let sistema = setup_sistema();
+        assert_eq!(
+            sistema.get_reputacion(AccountId::from([0x30; 32]), 2),
+            Err(ErroresContrato::UsuarioNoExiste)
+        );
+    }
 
-        registrar_comprador(&mut contrato, comprador);
+    /// Producto 0 en el catálogo compartido, ofrecido por tres vendedores:
+    /// pub 0 (vendedor, 10 u. a 100), pub 1 (otro, 5 u. a 80), pub 2 (tercero, 10 u. a 100)
+    fn setup_catalogo() -> (Sistema, AccountId, AccountId, AccountId, AccountId) {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let otro = AccountId::from([0x40; 32]);
+        let tercero = AccountId::from([0x50; 32]);
 
-        contrato
-            ._registrar_categoria("Electronica".to_string())
+        sistema._asignar_sku(0, vendedor, "7790001000010".into()).unwrap();
+        sistema
+            ._registrar_usuario(otro, "otro".into(), "otro@gmail.com".into(), Rol::Vendedor)
             .unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Auriculares".to_string(),
-                "BT".to_string(),
-                "Electronica".to_string(),
-                5,
-            )
+        sistema
+            ._registrar_usuario(tercero, "tercero".into(), "tercero@gmail.com".into(), Rol::Vendedor)
             .unwrap();
-        contrato._crear_publicacion(0, vendedor, 5, 250).unwrap();
-
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+        sistema._crear_publicacion(0, otro, 5, 80).unwrap();
+        sistema._crear_publicacion(0, tercero, 10, 100).unwrap();
 
-        let res = contrato.crear_orden(0, 0);
-        assert!(matches!(
-            res,
-            Err(ErroresContrato::CantidadEnCarritoMenorAUno)
-        ));
+        (sistema, comprador, vendedor, otro, tercero)
     }
 
     #[ink::test]
-    fn test_crear_orden_sin_rol_comprador_fallido() {
-        let mut contrato = setup_sistema();
-        let (no_comprador, vendedor) = build_testing_accounts();
-
-        registrar_vendedor(&mut contrato, vendedor);
-        contrato
-            ._registrar_usuario(
-                no_comprador,
-                "Santi No comprador".to_string(),
-                "ST96@Gmail.com".to_string(),
-                Rol::Vendedor,
-            )
+    fn test_asignar_sku() {
+        let (mut sistema, _, vendedor) = setup_publicacion();
+        sistema
+            ._crear_producto(vendedor, "Go".into(), "Desc".into(), "Libros".into(), 10)
             .unwrap();
 
-        contrato
-            ._registrar_categoria("Una cat".to_string())
-            .unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Nombre CAt".to_string(),
-                "Aux".to_string(),
-                "Una cat".to_string(),
-                3,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 3, 600).unwrap();
+        assert_eq!(
+            sistema._asignar_sku(0, vendedor, "  ".into()),
+            Err(ErroresContrato::SkuInvalido)
+        );
+        assert_eq!(
+            sistema._asignar_sku(0, id_comprador(), "ABC-1".into()),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+        assert!(sistema._asignar_sku(0, vendedor, "ABC-1".into()).is_ok());
+        assert_eq!(
+            sistema._asignar_sku(1, vendedor, "abc-1".into()),
+            Err(ErroresContrato::SkuYaExistente)
+        );
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(no_comprador);
+        let producto = sistema.buscar_producto_por_sku(" abc-1".into()).unwrap();
+        assert_eq!(producto.get_id(), 0);
+        assert_eq!(producto.get_sku(), Some("ABC-1".into()));
+        assert!(producto.es_compartido());
+        assert_eq!(eventos_emitidos::<ProductoCatalogado>().len(), 1);
 
-        let res = contrato.crear_orden(0, 1);
-        assert!(matches!(res, Err(ErroresContrato::RolNoApropiado)));
+        // Al cambiar el SKU el anterior queda libre
+        sistema._asignar_sku(0, vendedor, "ABC-2".into()).unwrap();
+        assert_eq!(
+            sistema.buscar_producto_por_sku("ABC-1".into()),
+            Err(ErroresContrato::SkuInexistente)
+        );
+        assert!(sistema._asignar_sku(1, vendedor, "ABC-1".into()).is_ok());
     }
 
     #[ink::test]
-    fn test_enviar_orden_pendiente_exito() {
-        let mut contrato = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
-
-        // registrar usuarios y roles
-        registrar_vendedor(&mut contrato, vendedor);
-        registrar_comprador(&mut contrato, comprador);
-
-        // Creo categoría, producto y publicación
-        contrato._registrar_categoria("Juegos".to_string()).unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "PS5".to_string(),
-                "Sony".to_string(),
-                "Juegos".to_string(),
-                3,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 3, 600).unwrap();
+    fn test_ofertas_ordenadas_por_precio_y_reputacion() {
+        let (mut sistema, comprador, vendedor, otro, tercero) = setup_catalogo();
 
-        // Creo orden como comprador
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        contrato.crear_orden(0, 2).unwrap();
+        // Sin calificaciones, a igual precio se respeta el orden de publicación
+        let ids: Vec<u32> = sistema.listar_ofertas(0, 0, MAX_TAMANO_PAGINA).unwrap().elementos.iter().map(|p| p.get_id()).collect();
+        assert_eq!(ids, vec![1, 0, 2]);
 
-        // Envio orden como vendedor
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        let res = contrato.enviar_producto(0);
+        // El tercero recibe mejor calificación que el vendedor original
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(2, 1).unwrap();
+        set_caller(tercero);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        sistema.calificar_compra(id_orden, 5).unwrap();
+        set_caller(tercero);
+        sistema.calificar_compra(id_orden, 5).unwrap();
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 2, 3);
 
-        assert!(res.is_ok(), "Fallo al enviar la orden");
-        let ordenes = contrato.listar_ordenes();
-        assert_eq!(ordenes[0].get_status(), EstadoOrden::Enviada);
+        let ofertas = sistema.listar_ofertas(0, 0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        let ids: Vec<u32> = ofertas.iter().map(|p| p.get_id()).collect();
+        assert_eq!(ids, vec![1, 2, 0]);
+        assert_eq!(ofertas[0].get_id_vendedor(), otro);
+        assert_eq!(ofertas[0].get_precio_unitario(), 80);
     }
 
     #[ink::test]
-    fn test_contrato_orden_pendiente_exitoso() {
-        let mut contrato = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
-
-        // Setea la cuenta del contrato como callee en el entorno de test
-        let contrato_account = ink::env::test::callee::<ink::env::DefaultEnvironment>();
-        ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contrato_account);
+    fn test_mejor_oferta_considera_stock() {
+        let (sistema, _, _, otro, tercero) = setup_catalogo();
+
+        assert_eq!(sistema.mejor_oferta(0, 1).unwrap().get_id_vendedor(), otro);
+        // La más barata no alcanza para 6 unidades
+        assert_eq!(sistema.mejor_oferta(0, 6).unwrap().get_id(), 0);
+        assert_ne!(sistema.mejor_oferta(0, 6).unwrap().get_id_vendedor(), tercero);
+        assert_eq!(sistema.mejor_oferta(0, 11), Err(ErroresContrato::SinOfertas));
+        assert_eq!(sistema.mejor_oferta(7, 1), Err(ErroresContrato::SinOfertas));
+    }
 
-        registrar_vendedor(&mut contrato, vendedor);
+    #[ink::test]
+    fn test_crear_orden_por_producto_usa_la_mejor_oferta() {
+        let (mut sistema, comprador, _, otro, _) = setup_catalogo();
 
-        registrar_comprador(&mut contrato, comprador);
+        set_caller(comprador);
+        pagar(400);
+        let id_orden = sistema.crear_orden_por_producto(0, 5).unwrap();
+        let orden = &sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize];
+        assert_eq!(orden.get_id_vendedor(), otro);
+        assert_eq!(orden.get_precio_total(), 400);
+
+        // La oferta de `otro` se agotó: la siguiente compra va a la siguiente mejor
+        let ids: Vec<u32> = sistema.listar_ofertas(0, 0, MAX_TAMANO_PAGINA).unwrap().elementos.iter().map(|p| p.get_id()).collect();
+        assert_eq!(ids, vec![0, 2]);
+        pagar(100);
+        let id_orden = sistema.crear_orden_por_producto(0, 1).unwrap();
+        assert_eq!(
+            sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize].get_id_vendedor(),
+            id_vendedor()
+        );
 
-        contrato._registrar_categoria("Libros".to_string()).unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Rust".to_string(),
-                "Desc".to_string(),
-                "Libros".to_string(),
-                10,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 10, 100).unwrap();
+        assert_eq!(
+            sistema.crear_orden_por_producto(0, 50),
+            Err(ErroresContrato::SinOfertas)
+        );
+    }
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+    /// Stock que le queda al producto fuera de sus publicaciones
+    fn stock_producto(sistema: &Sistema, id_producto: u32) -> u32 {
+        sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_producto as usize].get_cantidad()
+    }
 
-        assert!(contrato.crear_orden(0, 1).is_ok());
+    fn publicacion(sistema: &Sistema, id_pub: u32) -> Publicacion {
+        sistema._listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos[id_pub as usize].clone()
     }
 
     #[ink::test]
-    fn test_orden_cancelada_con_consenso() {
-        let mut contrato = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
+    fn test_actualizar_precio_publicacion() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
 
-        registrar_vendedor(&mut contrato, vendedor);
-        registrar_comprador(&mut contrato, comprador);
+        assert_eq!(
+            sistema._actualizar_precio(0, vendedor, 0),
+            Err(ErroresContrato::PrecioInvalido)
+        );
+        assert_eq!(
+            sistema._actualizar_precio(0, comprador, 50),
+            Err(ErroresContrato::PublicacionAjena)
+        );
+        assert_eq!(
+            sistema._actualizar_precio(9, vendedor, 50),
+            Err(ErroresContrato::PublicacionNoExiste)
+        );
+        assert!(sistema._actualizar_precio(0, vendedor, 150).is_ok());
+        assert_eq!(publicacion(&sistema, 0).get_precio_unitario(), 150);
+        assert_eq!(eventos_emitidos::<PublicacionActualizada>().len(), 1);
 
-        contrato._registrar_categoria("Libros".to_string()).unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Rust".to_string(),
-                "Desc".to_string(),
-                "Libros".to_string(),
-                10,
-            )
+        set_caller(comprador);
+        pagar(100);
+        assert_eq!(sistema.crear_orden(0, 1), Err(ErroresContrato::PagoInsuficiente));
+        pagar(150);
+        assert!(sistema.crear_orden(0, 1).is_ok());
+    }
+
+    #[ink::test]
+    fn test_reponer_stock_desde_el_producto() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        sistema
+            ._crear_producto(vendedor, "Go".into(), "Desc".into(), "Libros".into(), 20)
             .unwrap();
-        contrato._crear_publicacion(0, vendedor, 10, 100).unwrap();
+        let id_pub = sistema._crear_publicacion(1, vendedor, 5, 10).unwrap();
+        assert_eq!(stock_producto(&sistema, 1), 15);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        let id_orden = contrato.crear_orden(0, 1).unwrap();
-        assert!(contrato.cancelar_orden(id_orden).is_ok());
-        let orden = contrato.listar_ordenes()[0].clone();
-        assert_eq!(orden.get_status(), EstadoOrden::PreCancelada);
+        assert_eq!(
+            sistema._reponer_stock(id_pub, vendedor, 0),
+            Err(ErroresContrato::StockInvalido)
+        );
+        assert_eq!(
+            sistema._reponer_stock(id_pub, comprador, 5),
+            Err(ErroresContrato::PublicacionAjena)
+        );
+        assert_eq!(
+            sistema._reponer_stock(id_pub, vendedor, 16),
+            Err(ErroresContrato::StockInsuficiente)
+        );
+        assert!(sistema._reponer_stock(id_pub, vendedor, 5).is_ok());
+        assert_eq!(publicacion(&sistema, id_pub).stock(), 10);
+        assert_eq!(stock_producto(&sistema, 1), 10);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        assert!(contrato.cancelar_orden(id_orden).is_ok());
-        let orden = contrato.listar_ordenes()[0].clone();
-        assert_eq!(orden.get_status(), EstadoOrden::Cancelada);
+        // Una publicación agotada vuelve a estar activa al reponer
+        set_caller(comprador);
+        pagar(100);
+        sistema.crear_orden(id_pub, 10).unwrap();
+        assert!(!publicacion(&sistema, id_pub).esta_activa());
+        sistema._reponer_stock(id_pub, vendedor, 2).unwrap();
+        assert!(publicacion(&sistema, id_pub).esta_activa());
     }
 
     #[ink::test]
-    fn enviar_orden_inexistente_falla() {
-        let mut contrato = setup_sistema();
-        let vendedor = id_vendedor();
+    fn test_pausar_y_reanudar_publicacion() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
 
-        registrar_vendedor(&mut contrato, vendedor);
+        assert_eq!(
+            sistema._pausar_publicacion(0, comprador),
+            Err(ErroresContrato::PublicacionAjena)
+        );
+        assert_eq!(
+            sistema._reanudar_publicacion(0, vendedor),
+            Err(ErroresContrato::PublicacionNoPausada)
+        );
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+        sistema._pausar_publicacion(0, vendedor).unwrap();
+        let publi = publicacion(&sistema, 0);
+        assert!(publi.esta_pausada());
+        assert!(!publi.esta_activa());
+        assert!(sistema._listar_ofertas(0).is_empty());
+        assert_eq!(
+            sistema._pausar_publicacion(0, vendedor),
+            Err(ErroresContrato::PublicacionYaPausada)
+        );
 
-        // No hay orden 99
-        let res = contrato.enviar_producto(99);
-        assert_eq!(res, Err(ErroresContrato::OrdenInexistente));
-        //assert!(matches!(res, Err(ErroresContrato::OrdenInexistente)));
+        sistema._reanudar_publicacion(0, vendedor).unwrap();
+        let publi = publicacion(&sistema, 0);
+        assert!(!publi.esta_pausada());
+        assert!(publi.esta_activa());
+        assert_eq!(publi.stock(), 10);
     }
 
     #[ink::test]
-    fn recibir_orden_enviada_exitoso() {
-        let mut contrato = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
-
-        // Registro usuarios y roles
-        registrar_vendedor(&mut contrato, vendedor);
-        registrar_comprador(&mut contrato, comprador);
-
-        //Creo publicación
-        contrato._registrar_categoria("Libros".to_string()).unwrap();
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Rust".to_string(),
-                "Desc".to_string(),
-                "Libros".to_string(),
-                5,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 5, 100).unwrap();
-
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        contrato.crear_orden(0, 1).unwrap();
+    fn test_cerrar_publicacion_devuelve_stock_al_producto() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        pagar(300);
+        sistema.crear_orden(0, 3).unwrap();
+        assert_eq!(stock_producto(&sistema, 0), 0);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        contrato.enviar_producto(0).unwrap();
+        assert_eq!(
+            sistema._cerrar_publicacion(0, comprador),
+            Err(ErroresContrato::PublicacionAjena)
+        );
+        assert!(sistema._cerrar_publicacion(0, vendedor).is_ok());
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        let res = contrato.recibir_producto(0);
+        let publi = publicacion(&sistema, 0);
+        assert!(publi.esta_cerrada());
+        assert!(!publi.esta_activa());
+        assert_eq!(publi.stock(), 0);
+        assert_eq!(stock_producto(&sistema, 0), 7);
 
-        assert!(res.is_ok());
-        let orden = contrato.listar_ordenes()[0].clone();
-        assert_eq!(orden.get_status(), EstadoOrden::Recibida);
+        // Una publicación cerrada no admite más cambios
+        assert_eq!(
+            sistema._cerrar_publicacion(0, vendedor),
+            Err(ErroresContrato::PublicacionCerrada)
+        );
+        assert_eq!(
+            sistema._reponer_stock(0, vendedor, 1),
+            Err(ErroresContrato::PublicacionCerrada)
+        );
+        assert_eq!(
+            sistema._actualizar_precio(0, vendedor, 10),
+            Err(ErroresContrato::PublicacionCerrada)
+        );
+        assert_eq!(
+            sistema._reanudar_publicacion(0, vendedor),
+            Err(ErroresContrato::PublicacionCerrada)
+        );
     }
+
     #[ink::test]
-    fn test_crear_producto_falla_datos_invalidos() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+    fn test_cerrar_publicacion_de_producto_compartido() {
+        let (mut sistema, _, vendedor, otro, _) = setup_catalogo();
+
+        // El stock de `otro` no pertenece al producto, así que no vuelve a él
+        sistema._cerrar_publicacion(1, otro).unwrap();
+        assert_eq!(stock_producto(&sistema, 0), 0);
+        assert!(sistema._reponer_stock(0, vendedor, 1).is_err());
+        assert_eq!(sistema._listar_ofertas(0).len(), 2);
+    }
 
-        // nombre vacio
-        let res_nombre = sistema._crear_producto(id, "".into(), "desc".into(), "Ropa".into(), 10);
-        assert_eq!(res_nombre, Err(ErroresContrato::DatosInvalidos));
+    /// Compra `cantidad` unidades de la publicación 0 y devuelve el id de la orden
+    fn comprar(sistema: &mut Sistema, comprador: AccountId, cantidad: u32) -> u32 {
+        set_caller(comprador);
+        pagar(100 * cantidad as u128);
+        sistema.crear_orden(0, cantidad).unwrap()
+    }
 
-        // descripcion vacia
-        let res_desc = sistema._crear_producto(id, "Valid".into(), "   ".into(), "Ropa".into(), 10);
-        assert_eq!(res_desc, Err(ErroresContrato::DatosInvalidos));
+    fn cancelar_con_consenso(sistema: &mut Sistema, id_orden: u32, (comprador, vendedor): (AccountId, AccountId)) {
+        set_caller(comprador);
+        sistema
+            .cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador)
+            .unwrap();
+        set_caller(vendedor);
+        assert_eq!(
+            sistema.cancelar_orden(id_orden, MotivoCancelacion::PedidoDelComprador),
+            Ok(ResultadoCancelacion::CancelacionConfirmada)
+        );
     }
 
     #[ink::test]
-    fn test_crear_producto_falla_stock_cero() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+    fn test_publicacion_agotada_se_reactiva_al_cancelar() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let id_orden = comprar(&mut sistema, comprador, 10);
+
+        assert!(!publicacion(&sistema, 0).esta_activa());
+        assert!(sistema.listar_publicaciones(true, 0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+        assert_eq!(sistema.listar_publicaciones(false, 0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 1);
+        pagar(100);
+        assert_eq!(sistema.crear_orden(0, 1), Err(ErroresContrato::StockInsuficiente));
+
+        cancelar_con_consenso(&mut sistema, id_orden, (comprador, vendedor));
+        let publi = publicacion(&sistema, 0);
+        assert!(publi.esta_activa());
+        assert_eq!(publi.stock(), 10);
+        assert_eq!(sistema.listar_publicaciones(true, 0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 1);
+        comprar(&mut sistema, comprador, 1);
+    }
 
-        // stock 0
-        let res = sistema._crear_producto(id, "Valid".into(), "desc".into(), "Ropa".into(), 0);
-        assert_eq!(res, Err(ErroresContrato::StockInvalido));
+    #[ink::test]
+    fn test_no_se_compra_una_publicacion_pausada() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        sistema._pausar_publicacion(0, vendedor).unwrap();
+
+        set_caller(comprador);
+        pagar(100);
+        assert_eq!(sistema.crear_orden(0, 1), Err(ErroresContrato::PublicacionInactiva));
+        assert_eq!(
+            sistema.agregar_al_carrito(0, 1),
+            Err(ErroresContrato::PublicacionInactiva)
+        );
+        assert!(sistema.listar_publicaciones(true, 0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
+        // El stock sigue disponible, no se descontó nada
+        assert_eq!(publicacion(&sistema, 0).stock(), 10);
+
+        sistema._reanudar_publicacion(0, vendedor).unwrap();
+        assert_eq!(sistema.listar_publicaciones(true, 0, MAX_TAMANO_PAGINA).unwrap().elementos.len(), 1);
+        comprar(&mut sistema, comprador, 1);
     }
 
     #[ink::test]
-    fn test_crear_publicacion_falla_valores_cero() {
-        let mut sistema = setup_sistema();
-        let id = id_vendedor();
-        registrar_vendedor(&mut sistema, id);
-        agregar_categoria(&mut sistema, "Ropa");
+    fn test_cancelar_en_publicacion_pausada_no_la_reactiva() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let id_orden = comprar(&mut sistema, comprador, 10);
+        sistema._pausar_publicacion(0, vendedor).unwrap();
+
+        cancelar_con_consenso(&mut sistema, id_orden, (comprador, vendedor));
+        let publi = publicacion(&sistema, 0);
+        assert_eq!(publi.stock(), 10);
+        assert!(!publi.esta_activa());
+
+        sistema._reanudar_publicacion(0, vendedor).unwrap();
+        assert!(publicacion(&sistema, 0).esta_activa());
+    }
 
-        // creo producto valido con 100 unidades
+    #[ink::test]
+    fn test_cancelar_en_publicacion_cerrada_devuelve_al_producto() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let id_orden = comprar(&mut sistema, comprador, 3);
+        sistema._cerrar_publicacion(0, vendedor).unwrap();
+        assert_eq!(stock_producto(&sistema, 0), 7);
+
+        cancelar_con_consenso(&mut sistema, id_orden, (comprador, vendedor));
+        let publi = publicacion(&sistema, 0);
+        assert_eq!(publi.stock(), 0);
+        assert!(publi.esta_cerrada());
+        assert!(!publi.esta_activa());
+        assert_eq!(stock_producto(&sistema, 0), 10);
+    }
+
+    #[ink::test]
+    fn test_editar_producto() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        agregar_categoria(&mut sistema, "Hogar");
         sistema
-            ._crear_producto(id, "Prod".into(), "D".into(), "Ropa".into(), 100)
+            ._crear_producto(vendedor, "Mate".into(), "Desc".into(), "Hogar".into(), 5)
             .unwrap();
 
-        // publico con precio = $0
-        let res_precio = sistema._crear_publicacion(0, id, 10, 0);
-        assert_eq!(res_precio, Err(ErroresContrato::PrecioInvalido));
+        assert_eq!(
+            sistema._editar_producto(0, vendedor, " ".into(), "Desc".into(), "Libros".into()),
+            Err(ErroresContrato::DatosInvalidos)
+        );
+        assert_eq!(
+            sistema._editar_producto(0, comprador, "Rust".into(), "Desc".into(), "Libros".into()),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+        assert_eq!(
+            sistema._editar_producto(0, vendedor, "Rust".into(), "Desc".into(), "Autos".into()),
+            Err(ErroresContrato::CategoriaInexistente)
+        );
+        assert_eq!(
+            sistema._editar_producto(0, vendedor, "mate".into(), "Desc".into(), "hogar".into()),
+            Err(ErroresContrato::ProductoYaExistente)
+        );
 
-        // publico con stock = 0
-        let res_stock = sistema._crear_publicacion(0, id, 0, 100);
-        assert_eq!(res_stock, Err(ErroresContrato::StockInvalido));
+        assert!(sistema
+            ._editar_producto(0, vendedor, "Rust 2024".into(), "Nueva".into(), "Hogar".into())
+            .is_ok());
+        let producto = &sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos[0];
+        assert_eq!(producto.get_nombre(), "Rust 2024");
+        assert_eq!(producto.get_descripcion(), "Nueva");
+        assert_eq!(producto.get_id_categoria(), 1);
+        assert_eq!(eventos_emitidos::<ProductoActualizado>().len(), 1);
+
+        // El nombre anterior queda libre y el nuevo ocupado
+        assert!(sistema
+            ._crear_producto(vendedor, "Rust".into(), "Desc".into(), "Libros".into(), 1)
+            .is_ok());
+        assert_eq!(
+            sistema._crear_producto(vendedor, "rust 2024".into(), "Desc".into(), "Hogar".into(), 1),
+            Err(ErroresContrato::ProductoYaExistente)
+        );
     }
 
     #[ink::test]
-    fn test_enviar_orden_vendedor_correcto() {
-        let mut contrato = Sistema::new();
-        let comprador = account_id(AccountKeyring::Alice);
-        let vendedor = account_id(AccountKeyring::Bob);
-
-        // Configurar usuarios y productos
-        registrar_comprador(&mut contrato, comprador);
-        registrar_vendedor(&mut contrato, vendedor);
+    fn test_ajustar_stock_producto() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        agregar_categoria(&mut contrato, "Electrónicos");
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Laptop".into(),
-                "Gaming laptop".into(),
-                "Electrónicos".into(),
-                5,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
+        assert_eq!(
+            sistema._ajustar_stock_producto(0, comprador, 5),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+        assert_eq!(
+            sistema._ajustar_stock_producto(3, vendedor, 5),
+            Err(ErroresContrato::ProductoInexistente)
+        );
+        assert!(sistema._ajustar_stock_producto(0, vendedor, 5).is_ok());
+        assert_eq!(stock_producto(&sistema, 0), 5);
+        sistema._reponer_stock(0, vendedor, 5).unwrap();
+        assert_eq!(stock_producto(&sistema, 0), 0);
+        assert_eq!(publicacion(&sistema, 0).stock(), 15);
+    }
 
-        // Crear orden como comprador
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        contrato.crear_orden(0, 1).unwrap();
+    #[ink::test]
+    fn test_retirar_producto_con_publicacion_u_orden_abierta() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let id_orden = comprar(&mut sistema, comprador, 3);
 
-        // Enviar orden como vendedor (debe funcionar)
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        let res = contrato.enviar_producto(0);
+        assert_eq!(
+            sistema._retirar_producto(0, vendedor),
+            Err(ErroresContrato::ProductoConPublicacionesActivas)
+        );
+        sistema._cerrar_publicacion(0, vendedor).unwrap();
+        assert_eq!(
+            sistema._retirar_producto(0, vendedor),
+            Err(ErroresContrato::ProductoConOrdenesAbiertas)
+        );
 
-        assert!(res.is_ok());
-        let orden = contrato.listar_ordenes()[0].clone();
-        assert_eq!(orden.get_status(), EstadoOrden::Enviada);
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        assert_eq!(
+            sistema._retirar_producto(0, comprador),
+            Err(ErroresContrato::ProductoAjeno)
+        );
+        assert!(sistema._retirar_producto(0, vendedor).is_ok());
+        assert_eq!(eventos_emitidos::<ProductoDadoDeBaja>().len(), 1);
     }
 
     #[ink::test]
-    fn test_enviar_orden_vendedor_incorrecto() {
-        let mut contrato = Sistema::new();
-        let comprador = account_id(AccountKeyring::Alice);
-        let vendedor_original = account_id(AccountKeyring::Bob);
-        let vendedor_intruso = account_id(AccountKeyring::Charlie);
+    fn test_producto_retirado() {
+        let (mut sistema, _, vendedor) = setup_publicacion();
+        sistema._asignar_sku(0, vendedor, "ABC-1".into()).unwrap();
+        sistema._cerrar_publicacion(0, vendedor).unwrap();
+        sistema._retirar_producto(0, vendedor).unwrap();
 
-        // Configurar usuarios y productos
-        registrar_comprador(&mut contrato, comprador);
-        registrar_vendedor(&mut contrato, vendedor_original);
-        // Registrar segundo vendedor con email diferente
-        contrato
-            ._registrar_usuario(
-                vendedor_intruso,
-                "Vendedor2".into(),
-                "vendedor2@gmail.com".into(),
-                Rol::Vendedor,
-            )
-            .unwrap();
+        let producto = &sistema._listar_productos(0, MAX_TAMANO_PAGINA).unwrap().elementos[0];
+        assert!(producto.esta_retirado());
+        assert!(!producto.es_compartido());
+        assert!(sistema._listar_productos_vendedor(vendedor, 0, MAX_TAMANO_PAGINA).unwrap().elementos.is_empty());
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor_original);
-        agregar_categoria(&mut contrato, "Electrónicos");
-        contrato
-            ._crear_producto(
-                vendedor_original,
-                "Laptop".into(),
-                "Gaming laptop".into(),
-                "Electrónicos".into(),
-                5,
-            )
+        assert_eq!(
+            sistema._retirar_producto(0, vendedor),
+            Err(ErroresContrato::ProductoRetirado)
+        );
+        assert_eq!(
+            sistema._crear_publicacion(0, vendedor, 1, 100),
+            Err(ErroresContrato::ProductoRetirado)
+        );
+        assert_eq!(
+            sistema._ajustar_stock_producto(0, vendedor, 1),
+            Err(ErroresContrato::ProductoRetirado)
+        );
+
+        // El nombre y el SKU pueden volver a usarse
+        let id = sistema
+            ._crear_producto(vendedor, "Rust".into(), "Desc".into(), "Libros".into(), 10)
             .unwrap();
-        contrato
-            ._crear_publicacion(0, vendedor_original, 2, 1000)
+        assert!(sistema._asignar_sku(id, vendedor, "abc-1".into()).is_ok());
+    }
+
+    /// Al catálogo de `setup_catalogo` le agrega la pub 3 (vendedor, 4 u. a 30) de un
+    /// producto de otra categoría, y califica al tercero con 5 estrellas y al vendedor con 3
+    fn setup_busqueda() -> (Sistema, AccountId, AccountId, AccountId) {
+        let (mut sistema, comprador, vendedor, otro, tercero) = setup_catalogo();
+        agregar_categoria(&mut sistema, "Hogar");
+        sistema
+            ._crear_producto(vendedor, "Mate".into(), "Desc".into(), "Hogar".into(), 4)
             .unwrap();
+        sistema._crear_publicacion(1, vendedor, 4, 30).unwrap();
 
-        // Crear orden como comprador
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        contrato.crear_orden(0, 1).unwrap();
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(2, 1).unwrap();
+        set_caller(tercero);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        sistema.calificar_compra(id_orden, 5).unwrap();
+        set_caller(tercero);
+        sistema.calificar_compra(id_orden, 5).unwrap();
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 3, 3);
 
-        // Intentar enviar orden como vendedor diferente (debe fallar)
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor_intruso);
-        let res = contrato.enviar_producto(0);
+        (sistema, vendedor, otro, tercero)
+    }
 
-        assert!(matches!(res, Err(ErroresContrato::NoEsVendedorOriginal)));
+    fn ids_busqueda(sistema: &Sistema, filtro: FiltroPublicaciones, orden: OrdenBusqueda) -> Vec<u32> {
+        sistema
+            .buscar_publicaciones(filtro, orden, 0, MAX_TAMANO_PAGINA)
+            .unwrap()
+            .elementos
+            .iter()
+            .map(|p| p.get_id())
+            .collect()
     }
 
     #[ink::test]
-    fn test_recibir_orden_comprador_correcto() {
-        let mut contrato = Sistema::new();
-        let comprador = account_id(AccountKeyring::Alice);
-        let vendedor = account_id(AccountKeyring::Bob);
+    fn test_buscar_publicaciones_filtros() {
+        let (mut sistema, vendedor, otro, _) = setup_busqueda();
+        let todas = FiltroPublicaciones::default();
 
-        // Configurar usuarios y productos
-        registrar_comprador(&mut contrato, comprador);
-        registrar_vendedor(&mut contrato, vendedor);
+        let filtro = FiltroPublicaciones { categoria: Some("hogar".into()), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![3]);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        agregar_categoria(&mut contrato, "Electrónicos");
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Laptop".into(),
-                "Gaming laptop".into(),
-                "Electrónicos".into(),
-                5,
-            )
-            .unwrap();
-        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
+        let filtro = FiltroPublicaciones { id_vendedor: Some(vendedor), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![3, 0]);
 
-        // Crear y enviar orden
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        contrato.crear_orden(0, 1).unwrap();
+        let filtro = FiltroPublicaciones { precio_min: Some(50), precio_max: Some(90), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![1]);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        contrato.enviar_producto(0).unwrap();
+        let filtro = FiltroPublicaciones { stock_min: Some(9), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![0, 2]);
 
-        // Recibir orden como comprador correcto (debe funcionar)
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
-        let res = contrato.recibir_producto(0);
+        // Promedio del tercero 5,00 y del vendedor 3,00; `otro` no tiene calificaciones
+        let filtro = FiltroPublicaciones { reputacion_min: Some(300), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![3, 0, 2]);
+        let filtro = FiltroPublicaciones { reputacion_min: Some(301), ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![2]);
 
-        assert!(res.is_ok());
-        let orden = contrato.listar_ordenes()[0].clone();
-        assert_eq!(orden.get_status(), EstadoOrden::Recibida);
+        sistema._pausar_publicacion(1, otro).unwrap();
+        let filtro = FiltroPublicaciones { solo_activas: true, ..todas.clone() };
+        assert_eq!(ids_busqueda(&sistema, filtro, OrdenBusqueda::Precio), vec![3, 0, 2]);
+        assert_eq!(ids_busqueda(&sistema, todas, OrdenBusqueda::Precio).len(), 4);
+
+        let filtro = FiltroPublicaciones { categoria: Some("Autos".into()), ..Default::default() };
+        assert_eq!(
+            sistema.buscar_publicaciones(filtro, OrdenBusqueda::Precio, 0, 10),
+            Err(ErroresContrato::CategoriaInexistente)
+        );
     }
 
     #[ink::test]
-    fn test_recibir_orden_comprador_incorrecto() {
-        let mut contrato = Sistema::new();
-        let comprador_original = account_id(AccountKeyring::Alice);
-        let comprador_intruso = account_id(AccountKeyring::Charlie);
-        let vendedor = account_id(AccountKeyring::Bob);
+    fn test_buscar_publicaciones_orden() {
+        let (sistema, _, _, _) = setup_busqueda();
+        let todas = FiltroPublicaciones::default();
 
-        // Configurar usuarios y productos
-        registrar_comprador(&mut contrato, comprador_original);
-        // Registrar segundo comprador con email diferente
-        contrato
-            ._registrar_usuario(
-                comprador_intruso,
-                "Comprador2".into(),
-                "comprador2@gmail.com".into(),
-                Rol::Comprador,
-            )
+        assert_eq!(ids_busqueda(&sistema, todas.clone(), OrdenBusqueda::Precio), vec![3, 1, 0, 2]);
+        assert_eq!(ids_busqueda(&sistema, todas.clone(), OrdenBusqueda::MasRecientes), vec![3, 2, 1, 0]);
+        assert_eq!(ids_busqueda(&sistema, todas, OrdenBusqueda::ReputacionVendedor), vec![2, 0, 3, 1]);
+    }
+
+    #[ink::test]
+    fn test_buscar_publicaciones_paginado() {
+        let (sistema, _, _, _) = setup_busqueda();
+        let todas = FiltroPublicaciones::default();
+
+        let pagina = sistema
+            .buscar_publicaciones(todas.clone(), OrdenBusqueda::Precio, 0, 3)
             .unwrap();
-        registrar_vendedor(&mut contrato, vendedor);
+        assert_eq!(pagina.total, 4);
+        assert_eq!(pagina.elementos.len(), 3);
+        assert_eq!(pagina.siguiente, Some(3));
 
-        // ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_original);
-        // contrato.asignar_rol(Rol::Comprador).unwrap();
+        let pagina = sistema
+            .buscar_publicaciones(todas.clone(), OrdenBusqueda::Precio, 3, 3)
+            .unwrap();
+        assert_eq!(pagina.elementos.len(), 1);
+        assert_eq!(pagina.elementos[0].get_id(), 3);
+        assert_eq!(pagina.siguiente, None);
+
+        // Las publicaciones que no cumplen el filtro no ocupan lugar en la página
+        let filtro = FiltroPublicaciones { stock_min: Some(9), ..todas.clone() };
+        let pagina = sistema
+            .buscar_publicaciones(filtro, OrdenBusqueda::MasRecientes, 0, 2)
+            .unwrap();
+        assert_eq!(pagina.elementos.iter().map(|p| p.get_id()).collect::<Vec<_>>(), vec![2, 0]);
+        assert_eq!(pagina.siguiente, None);
+        let pagina = sistema
+            .buscar_publicaciones(todas.clone(), OrdenBusqueda::Precio, 1, 2)
+            .unwrap();
+        assert_eq!(pagina.elementos.iter().map(|p| p.get_id()).collect::<Vec<_>>(), vec![1, 2]);
 
-        // ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_intruso);
-        // contrato.asignar_rol(Rol::Comprador).unwrap();
+        let pagina = sistema
+            .buscar_publicaciones(todas.clone(), OrdenBusqueda::Precio, 10, 3)
+            .unwrap();
+        assert!(pagina.elementos.is_empty());
+        assert_eq!(pagina.siguiente, None);
 
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        agregar_categoria(&mut contrato, "Electrónicos");
-        contrato
-            ._crear_producto(
-                vendedor,
-                "Laptop".into(),
-                "Gaming laptop".into(),
-                "Electrónicos".into(),
-                5,
-            )
+        assert_eq!(
+            sistema.buscar_publicaciones(todas.clone(), OrdenBusqueda::Precio, 0, 0),
+            Err(ErroresContrato::TamanoPaginaInvalido)
+        );
+        assert_eq!(
+            sistema.buscar_publicaciones(todas, OrdenBusqueda::Precio, 0, MAX_TAMANO_PAGINA + 1),
+            Err(ErroresContrato::TamanoPaginaInvalido)
+        );
+    }
+
+    #[ink::test]
+    fn test_buscar_publicaciones_por_precio_en_dos_paginas() {
+        let (mut sistema, vendedor, otro, _) = setup_busqueda();
+        sistema
+            ._crear_producto(otro, "Termo".into(), "Desc".into(), "Hogar".into(), 6)
             .unwrap();
-        contrato._crear_publicacion(0, vendedor, 2, 1000).unwrap();
+        sistema._crear_publicacion(2, otro, 6, 20).unwrap();
+        sistema._pausar_publicacion(0, vendedor).unwrap();
+        let activas = FiltroPublicaciones { solo_activas: true, ..Default::default() };
 
-        // Crear orden como comprador original
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_original);
-        contrato.crear_orden(0, 1).unwrap();
+        // La pub 0 está pausada: se revisa pero no ocupa lugar en la primera página
+        let pagina = sistema
+            .buscar_publicaciones(activas.clone(), OrdenBusqueda::Precio, 0, 2)
+            .unwrap();
+        assert_eq!(pagina.elementos.iter().map(|p| p.get_id()).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(pagina.siguiente, Some(3));
 
-        // Enviar orden como vendedor
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
-        contrato.enviar_producto(0).unwrap();
+        // La segunda página sigue donde se dejó de revisar y ordena sus propios resultados
+        let pagina = sistema
+            .buscar_publicaciones(activas, OrdenBusqueda::Precio, 3, 2)
+            .unwrap();
+        assert_eq!(pagina.elementos.iter().map(|p| p.get_id()).collect::<Vec<_>>(), vec![4, 3]);
+        assert_eq!(pagina.siguiente, None);
+        assert_eq!(pagina.total, 5);
+    }
 
-        // Intentar recibir orden como comprador diferente (debe fallar)
-        ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador_intruso);
-        let res = contrato.recibir_producto(0);
+    #[ink::test]
+    fn test_listados_paginados() {
+        let (mut sistema, comprador, vendedor, otro, _) = setup_catalogo();
+        comprar(&mut sistema, comprador, 1);
+        comprar(&mut sistema, comprador, 2);
 
-        assert!(matches!(res, Err(ErroresContrato::NoEsCompradorOriginal)));
+        let ordenes = sistema.listar_ordenes(1, 1).unwrap();
+        assert_eq!(ordenes.total, 2);
+        assert_eq!(ordenes.elementos[0].get_cantidad(), 2);
+        assert_eq!(ordenes.siguiente, None);
+
+        let productos = sistema.listar_productos(0, 1).unwrap();
+        assert_eq!((productos.total, productos.siguiente), (1, None));
+
+        let categorias = sistema.listar_categorias(0, 1).unwrap();
+        assert_eq!(categorias.elementos[0].get_nombre(), "libros");
+
+        let ofertas = sistema.listar_ofertas(0, 0, 2).unwrap();
+        assert_eq!(ofertas.total, 3);
+        assert_eq!(ofertas.siguiente, Some(2));
+        assert_eq!(ofertas.elementos[0].get_id_vendedor(), otro);
+
+        set_caller(vendedor);
+        let propias = sistema.listar_publicaciones_propias(0, 5).unwrap();
+        assert_eq!(propias.total, 1);
+        assert_eq!(propias.elementos[0].get_id(), 0);
+
+        assert_eq!(
+            sistema.listar_ordenes(0, 0).err(),
+            Some(ErroresContrato::TamanoPaginaInvalido)
+        );
+        assert_eq!(
+            sistema.listar_categorias(0, MAX_TAMANO_PAGINA + 1).err(),
+            Some(ErroresContrato::TamanoPaginaInvalido)
+        );
     }
 
-    fn setup_orden_recibida() -> (Sistema, u32, AccountId, AccountId) {
-        let mut sistema = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
+    #[ink::test]
+    fn test_listar_publicaciones_activas_por_ventana() {
+        let (mut sistema, _, _, otro, _) = setup_catalogo();
+        sistema._pausar_publicacion(1, otro).unwrap();
+
+        // La primera ventana recorre las publicaciones 0 y 1, y omite la pausada
+        let pagina = sistema.listar_publicaciones(true, 0, 2).unwrap();
+        assert_eq!(pagina.elementos.len(), 1);
+        assert_eq!(pagina.total, 3);
+        assert_eq!(pagina.siguiente, Some(2));
+
+        let pagina = sistema.listar_publicaciones(true, 2, 2).unwrap();
+        assert_eq!(pagina.elementos[0].get_id(), 2);
+        assert_eq!(pagina.siguiente, None);
+    }
 
-        // 1. registro usuarios
-        registrar_vendedor(&mut sistema, vendedor);
-        registrar_comprador(&mut sistema, comprador);
+    #[ink::test]
+    fn test_listar_mis_compras_y_ventas() {
+        let (mut sistema, comprador, vendedor, otro, _) = setup_catalogo();
+        let primera = comprar(&mut sistema, comprador, 1);
+        comprar(&mut sistema, comprador, 2);
+        pagar(80);
+        sistema.crear_orden(1, 1).unwrap();
+        sistema._enviar_orden(primera, vendedor).unwrap();
 
-        // 2. creo una publicacion
-        agregar_categoria(&mut sistema, "TestCat");
-        sistema
-            ._crear_producto(vendedor, "Prod".into(), "Desc".into(), "TestCat".into(), 10)
+        set_caller(comprador);
+        let compras = sistema.listar_mis_compras(None, 0, 2).unwrap();
+        assert_eq!(compras.total, 3);
+        assert_eq!(compras.siguiente, Some(2));
+        assert_eq!(compras.elementos[0].get_id(), primera);
+        let enviadas = sistema
+            .listar_mis_compras(Some(EstadoOrden::Enviada), 0, MAX_TAMANO_PAGINA)
             .unwrap();
-        sistema._crear_publicacion(0, vendedor, 10, 100).unwrap();
+        assert_eq!(enviadas.total, 3);
+        assert_eq!(enviadas.elementos.len(), 1);
+        assert_eq!(enviadas.elementos[0].get_id(), primera);
+        // el filtro se aplica dentro de la ventana pedida
+        let ventana = sistema
+            .listar_mis_compras(Some(EstadoOrden::Enviada), 1, 2)
+            .unwrap();
+        assert!(ventana.elementos.is_empty());
+        assert_eq!(ventana.siguiente, None);
+        assert_eq!(sistema.listar_mis_ventas(None, 0, MAX_TAMANO_PAGINA).unwrap().total, 0);
+
+        set_caller(vendedor);
+        let pendientes = sistema
+            .listar_mis_ventas(Some(EstadoOrden::Pendiente), 0, MAX_TAMANO_PAGINA)
+            .unwrap();
+        assert_eq!(pendientes.total, 2);
+        assert_eq!(pendientes.elementos.len(), 1);
+        assert!(pendientes.elementos.iter().all(|o| o.get_id_vendedor() == vendedor));
+
+        set_caller(AccountId::from([0x99; 32]));
+        assert_eq!(
+            sistema.listar_mis_compras(None, 0, MAX_TAMANO_PAGINA).err(),
+            Some(ErroresContrato::CuentaNoRegistrada)
+        );
+        assert_eq!(
+            sistema.get_cantidad_ordenes(comprador),
+            Ok(CantidadOrdenes { como_comprador: 3, como_vendedor: 0 })
+        );
+        assert_eq!(
+            sistema.get_cantidad_ordenes(otro),
+            Ok(CantidadOrdenes { como_comprador: 0, como_vendedor: 1 })
+        );
+        assert_eq!(
+            sistema.get_cantidad_ordenes(AccountId::from([0x99; 32])),
+            Err(ErroresContrato::UsuarioNoExiste)
+        );
+    }
+
+    #[ink::test]
+    fn test_historial_de_orden() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(1_000);
 
-        // 3. Creo orden comprador
         set_caller(comprador);
-        let id_orden = sistema.crear_orden(0, 2).unwrap();
+        pagar(100);
+        let id_orden = sistema.crear_orden(0, 1).unwrap();
+        assert_eq!(sistema.get_historial_orden(id_orden), Ok(Vec::new()));
 
-        // 4. Envio orden vendedor
+        avanzar_tiempo(500);
+        sistema.cancelar_orden(id_orden, MotivoCancelacion::Otro).unwrap();
+        avanzar_tiempo(500);
         set_caller(vendedor);
+        sistema.rechazar_cancelacion(id_orden).unwrap();
         sistema.enviar_producto(id_orden).unwrap();
 
-        // 5. Recibo orden comprador
-        set_caller(comprador);
-        sistema.recibir_producto(id_orden).unwrap();
+        let historial = sistema.get_historial_orden(id_orden).unwrap();
+        let estados: Vec<(EstadoOrden, EstadoOrden, AccountId)> =
+            historial.iter().map(|t| (t.anterior, t.nuevo, t.actor)).collect();
+        assert_eq!(
+            estados,
+            vec![
+                (EstadoOrden::Pendiente, EstadoOrden::PreCancelada, comprador),
+                (EstadoOrden::PreCancelada, EstadoOrden::Pendiente, vendedor),
+                (EstadoOrden::Pendiente, EstadoOrden::Enviada, vendedor),
+            ]
+        );
+        assert_eq!(historial[0].timestamp, 1_500);
+        assert_eq!(historial[1].timestamp, 2_000);
+        assert_eq!(historial[1].bloque, historial[0].bloque + 1);
+        assert_eq!(historial[2].bloque, historial[1].bloque);
 
-        (sistema, id_orden, comprador, vendedor)
+        assert_eq!(
+            sistema.get_historial_orden(id_orden + 1),
+            Err(ErroresContrato::OrdenInexistente)
+        );
     }
 
     #[ink::test]
-    fn test_calificar_vendedor_exito() {
-        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
+    fn test_compra_propia_rechazada() {
+        let (mut sistema, _, vendedor) = setup_publicacion();
+        sistema._asignar_rol(vendedor, Rol::Comprador).unwrap();
 
-        // el comprador califica con 5 estrellas
+        set_caller(vendedor);
+        pagar(100);
+        assert_eq!(sistema.crear_orden(0, 1), Err(ErroresContrato::CompraPropia));
+        assert_eq!(publicacion(&sistema, 0).stock(), 10);
+    }
+
+    #[ink::test]
+    fn test_limite_calificaciones_por_par() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
         set_caller(comprador);
-        let res = sistema.calificar_compra(id_orden, 5);
-        assert!(res.is_ok(), "La calificación debería ser exitosa");
-        
-        // Verificamos que la repu aumento
-        let mut usuario_vendedor = sistema.get_user(&vendedor).unwrap();
+        assert_eq!(
+            sistema.configurar_limite_calificaciones(2, 1_000),
+            Err(ErroresContrato::NoEsAdmin)
+        );
+        set_caller(sistema.get_owner());
+        assert_eq!(
+            sistema.configurar_limite_calificaciones(0, 1_000),
+            Err(ErroresContrato::LimiteCalificacionesInvalido)
+        );
+        sistema.configurar_limite_calificaciones(2, 1_000).unwrap();
+        assert_eq!(sistema.get_limite_calificaciones(), (2, 1_000));
+        sistema.configurar_plazo_revelacion(500).unwrap();
+
+        let mut ordenes = Vec::new();
+        for _ in 0..4 {
+            let id_orden = comprar(&mut sistema, comprador, 1);
+            sistema._enviar_orden(id_orden, vendedor).unwrap();
+            sistema._recibir_orden(id_orden, comprador).unwrap();
+            ordenes.push(id_orden);
+        }
+        sistema._calificar_orden(ordenes[0], comprador, 5).unwrap();
+        sistema._calificar_orden(ordenes[1], comprador, 5).unwrap();
+        assert_eq!(
+            sistema._calificar_orden(ordenes[2], comprador, 5),
+            Err(ErroresContrato::LimiteCalificacionesAlcanzado)
+        );
+        // El límite es por dirección: el vendedor todavía puede calificar al comprador
+        sistema._calificar_orden(ordenes[2], vendedor, 4).unwrap();
+
+        // Pasada la ventana se libera el cupo
+        avanzar_tiempo(1_000);
+        sistema._calificar_orden(ordenes[2], comprador, 5).unwrap();
+        sistema.revelar_calificaciones(ordenes[0]).unwrap();
+        sistema.revelar_calificaciones(ordenes[1]).unwrap();
+        let vendedor_rep = sistema.get_reputacion(vendedor, 0).unwrap().como_vendedor;
+        assert_eq!(vendedor_rep.cantidad, 3);
+    }
 
-        // accedemos a la tupla para ver los resultados
+    #[ink::test]
+    fn test_reputacion_ponderada_por_precio() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        let chica = comprar(&mut sistema, comprador, 1);
+        let grande = comprar(&mut sistema, comprador, 3);
+        for id_orden in [chica, grande] {
+            sistema._enviar_orden(id_orden, vendedor).unwrap();
+            sistema._recibir_orden(id_orden, comprador).unwrap();
+        }
+        sistema._calificar_orden(chica, comprador, 1).unwrap();
+        sistema._calificar_orden(grande, comprador, 5).unwrap();
+        sistema._calificar_orden(chica, vendedor, 4).unwrap();
+        sistema._calificar_orden(grande, vendedor, 4).unwrap();
+
+        let reputacion = sistema.get_reputacion(vendedor, 2).unwrap().como_vendedor;
+        assert_eq!(reputacion.promedio, Some(300));
+        // (1 * 100 + 5 * 300) / 400
+        assert_eq!(reputacion.suma_ponderada, 1600);
+        assert_eq!(reputacion.peso_total, 400);
+        assert_eq!(reputacion.promedio_ponderado, Some(400));
+    }
+
+    #[ink::test]
+    fn test_valor_minimo_para_calificar() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
         assert_eq!(
-            usuario_vendedor.get_calificacion_vendedor().0, 5,
-            "Debería tener 1 calificación"
+            sistema.configurar_valor_minimo_calificacion(200),
+            Err(ErroresContrato::NoEsAdmin)
         );
+        set_caller(sistema.get_owner());
+        sistema.configurar_valor_minimo_calificacion(200).unwrap();
+        assert_eq!(sistema.get_valor_minimo_calificacion(), 200);
+
+        let chica = comprar(&mut sistema, comprador, 1);
+        let grande = comprar(&mut sistema, comprador, 2);
+        for id_orden in [chica, grande] {
+            sistema._enviar_orden(id_orden, vendedor).unwrap();
+            sistema._recibir_orden(id_orden, comprador).unwrap();
+            sistema._calificar_orden(id_orden, comprador, 5).unwrap();
+            sistema._calificar_orden(id_orden, vendedor, 5).unwrap();
+        }
+
+        // La orden chica queda calificada pero no suma a la reputación
+        let ordenes = sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos;
+        assert_eq!(ordenes[chica as usize].get_calificacion_vendedor(), Some(5));
         assert_eq!(
-            usuario_vendedor.get_calificacion_vendedor().1, 1,
-            "La suma de puntos debería ser 5"
+            sistema._calificar_orden(chica, comprador, 5),
+            Err(ErroresContrato::YaCalificado)
         );
-        // assert_eq!(
-        //     usuario_vendedor.mostrar_calificacion_vendedor(), Ok(String::from("Calificacion como vendedor: 5,0")),
-        //     "Debería mostrar calificacion de 5,0"
-        // );
+        let reputacion = sistema.get_reputacion(vendedor, 0).unwrap().como_vendedor;
+        assert_eq!(reputacion.cantidad, 1);
+        assert_eq!(reputacion.peso_total, 200);
     }
 
     #[ink::test]
-    fn test_calificar_comprador_exito() {
+    fn test_reputacion_reciente_con_vida_media() {
+        let (mut sistema, comprador, vendedor) = setup_publicacion();
+        set_caller(comprador);
+        assert_eq!(
+            sistema.configurar_vida_media_reputacion(10),
+            Err(ErroresContrato::NoEsAdmin)
+        );
+        set_caller(sistema.get_owner());
+        assert_eq!(
+            sistema.configurar_vida_media_reputacion(0),
+            Err(ErroresContrato::VidaMediaInvalida)
+        );
+        sistema.configurar_vida_media_reputacion(10).unwrap();
+        assert_eq!(sistema.get_vida_media_reputacion(), 10);
+
+        let vieja = comprar(&mut sistema, comprador, 1);
+        let nueva = comprar(&mut sistema, comprador, 1);
+        for id_orden in [vieja, nueva] {
+            sistema._enviar_orden(id_orden, vendedor).unwrap();
+            sistema._recibir_orden(id_orden, comprador).unwrap();
+        }
+        sistema._calificar_orden(vieja, comprador, 5).unwrap();
+        sistema._calificar_orden(vieja, vendedor, 5).unwrap();
+        // Dos vidas medias después la primera calificación pesa un cuarto
+        for _ in 0..20 {
+            ink::env::test::advance_block::<DefaultEnvironment>();
+        }
+        sistema._calificar_orden(nueva, comprador, 1).unwrap();
+        sistema._calificar_orden(nueva, vendedor, 5).unwrap();
+
+        let reputacion = sistema.get_reputacion(vendedor, 2).unwrap().como_vendedor;
+        assert_eq!(reputacion.promedio, Some(300));
+        // (5 * 1/4 + 1) / (1/4 + 1)
+        assert_eq!(reputacion.promedio_reciente, Some(180));
+    }
+
+    #[ink::test]
+    fn test_resenas_y_respuestas() {
         let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
 
-        // calificamos al comprador con 4 estrellas
-        set_caller(vendedor);
-        let res = sistema.calificar_compra(id_orden, 4);
-        assert!(res.is_ok());
-        let mut usuario_comprador = sistema.get_user(&comprador).unwrap();
+        set_caller(comprador);
+        assert_eq!(
+            sistema.calificar_con_resena(id_orden, 5, "   ".into()),
+            Err(ErroresContrato::ResenaInvalida)
+        );
+        assert_eq!(
+            sistema.calificar_con_resena(id_orden, 5, "a".repeat(MAX_LARGO_RESENA + 1)),
+            Err(ErroresContrato::ResenaInvalida)
+        );
+        let id_resena = sistema
+            .calificar_con_resena(id_orden, 5, " Llegó rápido ".into())
+            .unwrap();
+        // Sigue valiendo una calificación por parte y por orden
+        assert_eq!(
+            sistema.calificar_con_resena(id_orden, 4, "Otra".into()),
+            Err(ErroresContrato::YaCalificado)
+        );
+        assert_eq!(
+            sistema.responder_resena(id_resena, "Gracias".into()),
+            Err(ErroresContrato::ResenaInexistente)
+        );
 
-        assert_eq!(usuario_comprador.get_calificacion_comprador().0, 4);
-        assert_eq!(usuario_comprador.get_calificacion_comprador().1, 1);
+        // La reseña se ve recién cuando se revelan las calificaciones de la orden
+        set_caller(vendedor);
+        let ocultas = sistema.listar_resenas(vendedor, 0, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(ocultas.total, 1);
+        assert!(ocultas.elementos.is_empty());
+        sistema.calificar_compra(id_orden, 4).unwrap();
+        assert_eq!(sistema.get_reputacion(vendedor, 0).unwrap().como_vendedor.cantidad, 1);
 
-        //assert_eq!(usuario_comprador.mostrar_calificacion_comprador(), Ok(String::from("Calificacion como comprador: 4,0")));
+        set_caller(comprador);
+        assert_eq!(
+            sistema.responder_resena(id_resena, "Gracias".into()),
+            Err(ErroresContrato::NoEsDestinatarioResena)
+        );
+        set_caller(vendedor);
+        sistema.responder_resena(id_resena, "Gracias".into()).unwrap();
+        assert_eq!(
+            sistema.responder_resena(id_resena, "Gracias de nuevo".into()),
+            Err(ErroresContrato::ResenaYaRespondida)
+        );
+        assert_eq!(
+            sistema.responder_resena(id_resena + 1, "Hola".into()),
+            Err(ErroresContrato::ResenaInexistente)
+        );
 
+        let resenas = sistema.listar_resenas(vendedor, 0, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(resenas.total, 1);
+        let resena = &resenas.elementos[0];
+        assert_eq!(resena.get_autor(), comprador);
+        assert_eq!(resena.get_puntaje(), 5);
+        assert_eq!(resena.get_texto(), "Llegó rápido");
+        assert_eq!(resena.get_respuesta(), Some("Gracias".into()));
+        assert_eq!(sistema.listar_resenas(comprador, 0, MAX_TAMANO_PAGINA).unwrap().total, 0);
+        assert_eq!(eventos_emitidos::<ResenaPublicada>().len(), 1);
+        assert_eq!(eventos_emitidos::<ResenaRespondida>().len(), 1);
     }
 
     #[ink::test]
-    fn test_calificar_puntaje_invalido() {
-        let (mut sistema, id_orden, comprador, _) = setup_orden_recibida();
+    fn test_calificaciones_ocultas_hasta_revelar() {
+        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
         set_caller(comprador);
+        assert_eq!(
+            sistema.revelar_calificaciones(id_orden),
+            Err(ErroresContrato::SinCalificacionesPendientes)
+        );
+        sistema.calificar_compra(id_orden, 2).unwrap();
 
-        // puntaje 0 limites
-        let res_cero = sistema.calificar_compra(id_orden, 0);
-        assert_eq!(res_cero, Err(ErroresContrato::PuntajeInvalido));
+        // Ni la orden ni la reputación muestran el puntaje antes de revelarlo
+        let orden = sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_calificacion_vendedor(), None);
+        assert_eq!(sistema.get_reputacion(vendedor, 0).unwrap().como_vendedor.cantidad, 0);
+        assert_eq!(eventos_emitidos::<CalificacionPendiente>().len(), 1);
+        assert!(eventos_emitidos::<CalificacionRegistrada>().is_empty());
 
-        // puntaje 6 limites
-        let res_seis = sistema.calificar_compra(id_orden, 6);
-        assert_eq!(res_seis, Err(ErroresContrato::PuntajeInvalido));
+        avanzar_tiempo(PLAZO_REVELACION_DEFAULT - 1);
+        assert_eq!(
+            sistema.revelar_calificaciones(id_orden),
+            Err(ErroresContrato::PlazoNoVencido)
+        );
+        avanzar_tiempo(1);
+        set_caller(AccountId::from([0x30; 32]));
+        sistema.revelar_calificaciones(id_orden).unwrap();
+
+        let orden = sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[0].clone();
+        assert_eq!(orden.get_calificacion_vendedor(), Some(2));
+        assert_eq!(sistema.get_reputacion(vendedor, 0).unwrap().como_vendedor.suma, 2);
+        let registradas = eventos_emitidos::<CalificacionRegistrada>();
+        assert_eq!(registradas.len(), 1);
+        assert_eq!(registradas[0].calificado, vendedor);
+
+        // Revelada por vencimiento, la otra parte ya no puede calificar
+        set_caller(vendedor);
+        assert_eq!(
+            sistema.calificar_compra(id_orden, 1),
+            Err(ErroresContrato::CalificacionCerrada)
+        );
+        set_caller(comprador);
+        assert_eq!(sistema.calificar_compra(id_orden, 5), Err(ErroresContrato::YaCalificado));
+        assert_eq!(
+            sistema.revelar_calificaciones(id_orden),
+            Err(ErroresContrato::SinCalificacionesPendientes)
+        );
     }
 
     #[ink::test]
-    fn test_calificar_doble_falla() {
-        let (mut sistema, id_orden, comprador, _) = setup_orden_recibida();
+    fn test_configurar_plazo_revelacion() {
+        let (mut sistema, comprador, _) = setup_publicacion();
+        assert_eq!(sistema.get_plazo_revelacion(), PLAZO_REVELACION_DEFAULT);
+
         set_caller(comprador);
+        assert_eq!(sistema.configurar_plazo_revelacion(10), Err(ErroresContrato::NoEsAdmin));
+        set_caller(sistema.get_owner());
+        assert_eq!(sistema.configurar_plazo_revelacion(0), Err(ErroresContrato::PlazoInvalido));
+        sistema.configurar_plazo_revelacion(10).unwrap();
+        assert_eq!(sistema.get_plazo_revelacion(), 10);
+    }
 
-        // califico por primera vez bien
-        sistema.calificar_compra(id_orden, 5).unwrap();
+    #[ink::test]
+    fn test_ofertas_vendedor_sin_calificar_va_ultimo_en_el_empate() {
+        let (mut sistema, comprador, vendedor, otro, tercero) = setup_catalogo();
+        sistema._actualizar_precio(1, otro, 100).unwrap();
 
-        // califico por segunda vez falla
-        let res = sistema.calificar_compra(id_orden, 3);
-        assert_eq!(res, Err(ErroresContrato::YaCalificado));
+        // A igual precio: vendedor con 2 estrellas, otro sin calificar y tercero con 5
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 2, 3);
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(2, 1).unwrap();
+        sistema._enviar_orden(id_orden, tercero).unwrap();
+        sistema._recibir_orden(id_orden, comprador).unwrap();
+        sistema._calificar_orden(id_orden, comprador, 5).unwrap();
+        sistema._calificar_orden(id_orden, tercero, 5).unwrap();
+
+        let ids: Vec<u32> = sistema._listar_ofertas(0).iter().map(|p| p.get_id()).collect();
+        assert_eq!(ids, vec![2, 0, 1]);
+        assert_eq!(sistema.mejor_oferta(0, 1).unwrap().get_id_vendedor(), tercero);
     }
 
     #[ink::test]
-    fn test_calificar_usuario_ajeno_a_la_orden() {
-        let (mut sistema, id_orden, _, _) = setup_orden_recibida();
-        let intruso = account_id(AccountKeyring::Charlie);
-
-        // creo un pj ajeno a la orden intruso
-        sistema
-            ._registrar_usuario(
-                intruso,
-                "Intruso".into(),
-                "intruso@gmail.com".into(),
-                Rol::Comprador,
-            )
-            .unwrap();
+    fn test_mejor_oferta_omite_publicaciones_propias() {
+        let (mut sistema, _, vendedor, otro, _) = setup_catalogo();
+        // otro también compra y su publicación es la más barata
+        sistema._asignar_rol(otro, Rol::Comprador).unwrap();
+
+        set_caller(otro);
+        assert_eq!(sistema.mejor_oferta(0, 1).unwrap().get_id(), 0);
+        pagar(100);
+        let id_orden = sistema.crear_orden_por_producto(0, 1).unwrap();
+        let orden = sistema.listar_ordenes(0, MAX_TAMANO_PAGINA).unwrap().elementos[id_orden as usize].clone();
+        assert_eq!(orden.get_id_vendedor(), vendedor);
+        assert_eq!(orden.get_id_comprador(), otro);
+    }
 
-        set_caller(intruso);
-        let res = sistema.calificar_compra(id_orden, 5);
+    #[ink::test]
+    fn test_retirar_producto_con_orden_cancelada_de_otro_vendedor() {
+        let (mut sistema, comprador, vendedor, otro, tercero) = setup_catalogo();
+        set_caller(comprador);
+        pagar(80);
+        let id_orden = sistema.crear_orden(1, 1).unwrap();
+        for (id_pub, id_usuario) in [(0, vendedor), (1, otro), (2, tercero)] {
+            sistema._cerrar_publicacion(id_pub, id_usuario).unwrap();
+        }
 
-        // el intruso califica la orden
-        assert_eq!(res, Err(ErroresContrato::UsuarioNoCorresponde));
+        // La orden sobre la publicación de otro también bloquea el retiro del producto
+        assert_eq!(
+            sistema._retirar_producto(0, vendedor),
+            Err(ErroresContrato::ProductoConOrdenesAbiertas)
+        );
+        cancelar_con_consenso(&mut sistema, id_orden, (comprador, otro));
+        assert!(sistema._retirar_producto(0, vendedor).is_ok());
     }
 
     #[ink::test]
-    fn test_calificar_orden_no_recibida() {
-        // cambio la orden de entregado a pendiente , para que falle
-        let mut sistema = setup_sistema();
-        let (comprador, vendedor) = build_testing_accounts();
-        registrar_vendedor(&mut sistema, vendedor);
-        registrar_comprador(&mut sistema, comprador);
-        agregar_categoria(&mut sistema, "Cat");
+    fn test_una_publicacion_abierta_por_vendedor_y_producto() {
+        let (mut sistema, _, vendedor, otro, tercero) = setup_catalogo();
+        assert_eq!(
+            sistema._crear_publicacion(0, otro, 1, 10),
+            Err(ErroresContrato::PublicacionYaAbierta)
+        );
+
+        // Las ofertas de otro vendedor no impiden que el resto, ni el dueño, publique
+        let cuarto = AccountId::from([0x60; 32]);
         sistema
-            ._crear_producto(vendedor, "P".into(), "D".into(), "Cat".into(), 10)
+            ._registrar_usuario(cuarto, "cuarto".into(), "cuarto@gmail.com".into(), Rol::Vendedor)
             .unwrap();
-        sistema._crear_publicacion(0, vendedor, 10, 100).unwrap();
+        assert!(sistema._crear_publicacion(0, cuarto, 1, 90).is_ok());
+        sistema._cerrar_publicacion(0, vendedor).unwrap();
+        sistema._ajustar_stock_producto(0, vendedor, 5).unwrap();
+        assert!(sistema._crear_publicacion(0, vendedor, 5, 95).is_ok());
+
+        // Cerrar la publicación le permite al vendedor volver a publicar el producto
+        sistema._cerrar_publicacion(2, tercero).unwrap();
+        assert!(sistema._listar_ofertas(0).iter().all(|p| p.get_id() != 2));
+        assert!(sistema._crear_publicacion(0, tercero, 1, 90).is_ok());
+        assert_eq!(sistema.listar_ofertas(0, 0, MAX_TAMANO_PAGINA).unwrap().total, 4);
+    }
 
+    #[ink::test]
+    fn test_cancelar_en_publicacion_cerrada_de_producto_compartido() {
+        let (mut sistema, comprador, _, otro, _) = setup_catalogo();
         set_caller(comprador);
-        let id_orden = sistema.crear_orden(0, 1).unwrap();
-        // Intento calificar
-        let res = sistema.calificar_compra(id_orden, 5);
-        assert_eq!(res, Err(ErroresContrato::OrdenNoRecibida));
+        pagar(160);
+        let id_orden = sistema.crear_orden(1, 2).unwrap();
+        sistema._cerrar_publicacion(1, otro).unwrap();
+        assert_eq!(publicacion(&sistema, 1).stock(), 0);
+
+        // Las unidades no van al producto del vendedor original, vuelven a la publicación cerrada
+        cancelar_con_consenso(&mut sistema, id_orden, (comprador, otro));
+        let publi = publicacion(&sistema, 1);
+        assert_eq!(publi.stock(), 2);
+        assert!(publi.esta_cerrada());
+        assert!(!publi.esta_activa());
+        assert_eq!(stock_producto(&sistema, 0), 0);
     }
 
     #[ink::test]
-    fn test_mostrar_calificacion_exitosa_comprador() {
-        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
-
-        // calificamos al comprador con 4 estrellas
-        set_caller(vendedor);
-        let res = sistema.calificar_compra(id_orden, 4);
-        assert!(res.is_ok());
-        let usuario_comprador = sistema.get_user(&comprador).unwrap();
-
-        let resultado_1 = usuario_comprador.mostrar_calificacion_comprador();
-        assert_eq!(resultado_1, Ok(String::from("Calificacion como comprador: 4,0")));
+    fn test_listar_productos_vendedor_omite_retirados() {
+        let (mut sistema, _, vendedor) = setup_publicacion();
+        sistema
+            ._crear_producto(vendedor, "Go".into(), "Desc".into(), "Libros".into(), 5)
+            .unwrap();
+        sistema._cerrar_publicacion(0, vendedor).unwrap();
+        sistema._retirar_producto(0, vendedor).unwrap();
+
+        let pagina = sistema._listar_productos_vendedor(vendedor, 0, 1).unwrap();
+        assert!(pagina.elementos.is_empty());
+        assert_eq!(pagina.total, 2);
+        assert_eq!(pagina.siguiente, Some(1));
+        let pagina = sistema._listar_productos_vendedor(vendedor, 1, 1).unwrap();
+        assert_eq!(pagina.elementos[0].get_nombre(), "Go");
+        assert_eq!(pagina.siguiente, None);
     }
 
     #[ink::test]
-    fn test_mostrar_calificacion_exitosa_vendedor() {
-        let (mut sistema, id_orden, comprador, vendedor) = setup_orden_recibida();
+    fn test_listar_publicaciones_propias_por_posicion() {
+        let (mut sistema, _, vendedor, otro, _) = setup_catalogo();
+        sistema._cerrar_publicacion(1, otro).unwrap();
+        sistema._crear_publicacion(0, otro, 2, 90).unwrap();
+
+        let pagina = sistema._listar_publicaciones_propias(otro, 1, 1).unwrap();
+        assert_eq!(pagina.total, 2);
+        assert_eq!(pagina.elementos[0].get_id(), 3);
+        assert_eq!(pagina.siguiente, None);
+        let ids: Vec<u32> = sistema
+            ._listar_publicaciones_propias(vendedor, 0, MAX_TAMANO_PAGINA)
+            .unwrap()
+            .elementos
+            .iter()
+            .map(|p| p.get_id())
+            .collect();
+        assert_eq!(ids, vec![0]);
+    }
 
-        // calificamos al vendedor con 5 estrellas
-        set_caller(comprador);
-        let res = sistema.calificar_compra(id_orden, 5);
-        assert!(res.is_ok(), "La calificación debería ser exitosa");
-        
-        // Verificamos que la reputacion aumenta
-        let usuario_vendedor = sistema.get_user(&vendedor).unwrap();
+    #[ink::test]
+    fn test_get_por_ids() {
+        let (sistema, _, _, _, _) = setup_catalogo();
+
+        let ids: Vec<u32> = sistema
+            .get_publicaciones(vec![2, 0, 2])
+            .unwrap()
+            .iter()
+            .map(|p| p.get_id())
+            .collect();
+        assert_eq!(ids, vec![2, 0, 2]);
+        assert_eq!(sistema.get_productos(vec![0]).unwrap()[0].get_nombre(), "Rust");
+        assert_eq!(sistema.get_categorias(vec![0]).unwrap()[0].get_nombre(), "libros");
+        assert!(sistema.get_publicaciones(Vec::new()).unwrap().is_empty());
 
-        let resultado_2 = usuario_vendedor.mostrar_calificacion_vendedor();
-        assert_eq!(resultado_2, Ok(String::from("Calificacion como vendedor: 5,0")));
+        assert_eq!(
+            sistema.get_publicaciones(vec![0, 3]),
+            Err(ErroresContrato::PublicacionNoExiste)
+        );
+        assert_eq!(sistema.get_productos(vec![1]), Err(ErroresContrato::ProductoInexistente));
+        assert_eq!(
+            sistema.get_categorias(vec![1]).err(),
+            Some(ErroresContrato::CategoriaInexistente)
+        );
+        assert_eq!(
+            sistema.get_publicaciones(vec![0; MAX_TAMANO_PAGINA as usize + 1]),
+            Err(ErroresContrato::TamanoPaginaInvalido)
+        );
     }
 
     #[ink::test]
-    fn test_mostrar_calificacion_inexistente() {
-        let (mut sistema, id, _,)= build_testing_setup();
-        let usuario = sistema.get_user(&id).unwrap();
-
+    fn test_ventas_acumuladas_y_rankings() {
+        let (mut sistema, vendedor, _, tercero) = setup_busqueda();
+        let comprador = id_comprador();
+        let ids_ranking = |sistema: &Sistema| -> Vec<u32> {
+            let pagina = sistema.listar_productos_mas_vendidos(0, MAX_TAMANO_PAGINA).unwrap();
+            pagina.elementos.iter().map(|(id, _)| *id).collect()
+        };
+
+        // Las dos compras recibidas del producto 0 (Libros), calificadas con 5 y 3
+        let (_, ventas) = sistema.listar_productos_mas_vendidos(0, 1).unwrap().elementos[0];
+        assert_eq!(
+            ventas,
+            VentasAcumuladas { unidades: 2, ordenes: 2, monto: 200, calificaciones: 2, suma_calificaciones: 8 }
+        );
+        let categorias = sistema.listar_ventas_por_categoria(0, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(categorias.elementos, vec![(0, ventas)]);
+        assert_eq!(categorias.total, 2);
 
-        let resultado_1 = usuario.mostrar_calificacion_vendedor();
-        assert!(matches!(resultado_1, Err(ErroresContrato::NoTieneCalificaciones)));
+        // Una orden pendiente todavía no es una venta
+        comprar(&mut sistema, comprador, 1);
+        set_caller(comprador);
+        pagar(90);
+        let id_orden = sistema.crear_orden(3, 3).unwrap();
+        assert_eq!(ids_ranking(&sistema), vec![0]);
 
-        let resultado_2 = usuario.mostrar_calificacion_comprador();
-        assert!(matches!(resultado_2, Err(ErroresContrato::NoTieneCalificaciones)));
+        // Al recibirse, el producto 1 (Hogar) pasa al primer lugar con 3 unidades
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        assert_eq!(ids_ranking(&sistema), vec![1, 0]);
+        let categorias = sistema.listar_ventas_por_categoria(0, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(categorias.elementos[1].1.monto, 90);
+
+        // El tercero (5,00) está antes que el vendedor (3,00) hasta que lo califican con 1
+        let ranking = |sistema: &Sistema| -> Vec<AccountId> {
+            let pagina = sistema.listar_mejores_vendedores(0, MAX_TAMANO_PAGINA).unwrap();
+            pagina.elementos.iter().map(|u| u.get_id()).collect()
+        };
+        assert_eq!(ranking(&sistema), vec![tercero, vendedor]);
+        comprar_y_calificar(&mut sistema, (comprador, vendedor), 5, 5);
+        set_caller(comprador);
+        pagar(100);
+        let id_orden = sistema.crear_orden(2, 1).unwrap();
+        set_caller(tercero);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        sistema.calificar_compra(id_orden, 1).unwrap();
+        set_caller(tercero);
+        sistema.calificar_compra(id_orden, 1).unwrap();
+        assert_eq!(ranking(&sistema), vec![vendedor, tercero]);
+
+        let compradores = sistema.listar_mejores_compradores(0, MAX_TAMANO_PAGINA).unwrap();
+        assert_eq!(compradores.total, 1);
+        assert_eq!(compradores.elementos[0].get_id(), comprador);
     }
 }
@@ -0,0 +1,957 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Contrato de reportes de solo lectura sobre los datos públicos de `Sistema`.
+///
+/// `ReportesView` guarda una referencia al contrato principal y únicamente invoca sus
+/// mensajes de consulta. Todos los mensajes de este contrato reciben `&self`, por lo que
+/// la referencia almacenada nunca puede usarse para llamar a un mensaje que modifique
+/// el estado de `Sistema` (esos mensajes requieren `&mut SistemaRef`).
+#[ink::contract]
+mod reportes {
+    use ink::{
+        env::call::FromAccountId,
+        prelude::{string::String, vec::Vec},
+    };
+    use marketplacedescentralizado::prelude::*;
+
+    /// Cantidad de usuarios que se devuelven en los rankings de reputación
+    pub const TOP_USUARIOS: u32 = 5;
+
+    /// Usuario destacado dentro de un ranking de reputación
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct UsuarioDestacado {
+        pub id: AccountId,
+        pub nombre: String,
+        pub promedio: u32, //promedio de calificaciones multiplicado por 100
+        pub promedio_ponderado: Option<u32>, //promedio ponderado por precio de la orden, multiplicado por 100
+        pub calificaciones: u32,
+    }
+
+    /// Ventas acumuladas de un producto
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct VentasProducto {
+        pub id_producto: u32,
+        pub nombre: String,
+        pub unidades_vendidas: u32,
+        pub ordenes: u32,
+    }
+
+    /// Estadísticas de ventas y calificaciones de una categoría
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct EstadisticasCategoria {
+        pub id_categoria: u32,
+        pub nombre: String,
+        pub total_ventas: u32,
+        pub monto_total: Balance,
+        pub calificacion_promedio: Option<u32>, //multiplicada por 100
+        pub calificaciones: u32,
+    }
+
+    /// Cantidad de órdenes en las que participa un usuario
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct OrdenesUsuario {
+        pub id: AccountId,
+        pub como_comprador: u32,
+        pub como_vendedor: u32,
+    }
+
+    ///Estructura principal del contrato de reportes
+    #[ink(storage)]
+    pub struct ReportesView {
+        sistema: SistemaRef,
+    }
+
+    impl ReportesView {
+        /// #Constructor del contrato.
+        ///
+        /// # Parámetros
+        /// - `sistema`: AccountId del contrato `Sistema` ya desplegado del cual se leen los datos.
+        #[ink(constructor)]
+        pub fn new(sistema: AccountId) -> Self {
+            ReportesView {
+                sistema: SistemaRef::from_account_id(sistema),
+            }
+        }
+
+        /// Devuelve los 5 vendedores con mejor reputación, ordenados de mayor a menor, según el
+        /// ranking que `Sistema` actualiza con cada calificación.
+        ///
+        /// Solo se consideran usuarios que hayan recibido al menos una calificación como vendedor.
+        ///
+        /// # Errores
+        /// - Propaga los errores de `Sistema`.
+        #[ink(message)]
+        pub fn top_vendedores(&self) -> Result<Vec<UsuarioDestacado>, ErroresContrato> {
+            let ranking = self.sistema.listar_mejores_vendedores(0, TOP_USUARIOS)?;
+            Ok(usuarios_destacados(ranking.elementos, Rol::Vendedor))
+        }
+
+        /// Devuelve los 5 compradores con mejor reputación, ordenados de mayor a menor, según el
+        /// ranking que `Sistema` actualiza con cada calificación.
+        ///
+        /// Solo se consideran usuarios que hayan recibido al menos una calificación como comprador.
+        ///
+        /// # Errores
+        /// - Propaga los errores de `Sistema`.
+        #[ink(message)]
+        pub fn top_compradores(&self) -> Result<Vec<UsuarioDestacado>, ErroresContrato> {
+            let ranking = self.sistema.listar_mejores_compradores(0, TOP_USUARIOS)?;
+            Ok(usuarios_destacados(ranking.elementos, Rol::Comprador))
+        }
+
+        /// Devuelve una página de los productos vendidos, de más a menos unidades vendidas,
+        /// según las ventas que `Sistema` acumula cuando una orden pasa a `Recibida`.
+        ///
+        /// # Parámetros
+        /// - `desde`, `tamano_pagina`: Paginado, igual que en `Sistema::listar_productos_mas_vendidos`.
+        ///
+        /// # Errores
+        /// - Propaga los errores de `Sistema`, por ejemplo `TamanoPaginaInvalido`.
+        #[ink(message)]
+        pub fn productos_mas_vendidos(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<VentasProducto>, ErroresContrato> {
+            productos_mas_vendidos(
+                self.sistema
+                    .listar_productos_mas_vendidos(desde, tamano_pagina)?,
+                |ids| self.sistema.get_productos(ids),
+            )
+        }
+
+        /// Devuelve una página de las categorías con ventas, con el total vendido y la
+        /// calificación promedio que recibieron los vendedores en esas ventas, según lo que
+        /// `Sistema` acumula con cada venta y calificación.
+        ///
+        /// # Parámetros
+        /// - `desde`, `tamano_pagina`: Paginado, igual que en `Sistema::listar_ventas_por_categoria`.
+        ///
+        /// # Errores
+        /// - Propaga los errores de `Sistema`, por ejemplo `TamanoPaginaInvalido`.
+        #[ink(message)]
+        pub fn estadisticas_por_categoria(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<EstadisticasCategoria>, ErroresContrato> {
+            estadisticas_por_categoria(
+                self.sistema
+                    .listar_ventas_por_categoria(desde, tamano_pagina)?,
+                |ids| self.sistema.get_categorias(ids),
+            )
+        }
+
+        /// Devuelve una página de usuarios registrados con su cantidad de órdenes como comprador
+        /// y como vendedor, tomada de los contadores de `Sistema`.
+        ///
+        /// # Parámetros
+        /// - `desde`, `tamano_pagina`: Paginado sobre los usuarios, igual que en `Sistema::listar_usuarios`.
+        ///
+        /// # Errores
+        /// - Propaga los errores de `Sistema`, por ejemplo `TamanoPaginaInvalido`.
+        #[ink(message)]
+        pub fn cantidad_ordenes_por_usuario(
+            &self,
+            desde: u32,
+            tamano_pagina: u32,
+        ) -> Result<Pagina<OrdenesUsuario>, ErroresContrato> {
+            ordenes_por_usuario(self.sistema.listar_usuarios(desde, tamano_pagina)?, |id| {
+                self.sistema.get_cantidad_ordenes(id)
+            })
+        }
+    }
+
+    /// Reemplaza los elementos de una página por el resultado de `f`, conservando el paginado
+    fn mapear_pagina<T, U>(pagina: Pagina<T>, f: impl FnOnce(Vec<T>) -> Vec<U>) -> Pagina<U> {
+        Pagina {
+            elementos: f(pagina.elementos),
+            total: pagina.total,
+            siguiente: pagina.siguiente,
+        }
+    }
+
+    /// Calcula el promedio de `(suma, cantidad)` multiplicado por 100
+    fn promedio(suma: u32, cantidad: u32) -> Option<u32> {
+        if cantidad == 0 {
+            return None;
+        }
+        (suma as u64)
+            .checked_mul(100)?
+            .checked_div(cantidad as u64)
+            .map(|valor| valor as u32)
+    }
+
+    /// Calcula el promedio ponderado de `(suma ponderada, peso total)` multiplicado por 100
+    fn promedio_ponderado(suma: Balance, peso: Balance) -> Option<u32> {
+        suma.checked_mul(100)?
+            .checked_div(peso)
+            .map(|valor| valor as u32)
+    }
+
+    /// Arma los usuarios destacados de un ranking de `Sistema` con la reputación del rol indicado
+    pub fn usuarios_destacados(usuarios: Vec<Usuario>, rol: Rol) -> Vec<UsuarioDestacado> {
+        usuarios
+            .into_iter()
+            .filter_map(|usuario| {
+                let ((suma, cantidad), (suma_ponderada, peso)) = match rol {
+                    Rol::Comprador => (
+                        usuario.get_calificacion_comprador(),
+                        usuario.get_calificacion_ponderada_comprador(),
+                    ),
+                    _ => (
+                        usuario.get_calificacion_vendedor(),
+                        usuario.get_calificacion_ponderada_vendedor(),
+                    ),
+                };
+                Some(UsuarioDestacado {
+                    id: usuario.get_id(),
+                    nombre: usuario.get_name(),
+                    promedio: promedio(suma, cantidad)?,
+                    promedio_ponderado: promedio_ponderado(suma_ponderada, peso),
+                    calificaciones: cantidad,
+                })
+            })
+            .collect()
+    }
+
+    /// Completa una página del ranking de más vendidos de `Sistema` con el nombre de cada
+    /// producto, leyendo los productos de la página con una sola llamada a `obtener_productos`
+    pub fn productos_mas_vendidos(
+        ventas: Pagina<(u32, VentasAcumuladas)>,
+        obtener_productos: impl FnOnce(Vec<u32>) -> Result<Vec<Producto>, ErroresContrato>,
+    ) -> Result<Pagina<VentasProducto>, ErroresContrato> {
+        let productos = obtener_productos(ventas.elementos.iter().map(|(id, _)| *id).collect())?;
+        Ok(mapear_pagina(ventas, |ventas| {
+            ventas
+                .into_iter()
+                .zip(productos)
+                .map(|((id_producto, ventas), producto)| VentasProducto {
+                    id_producto,
+                    nombre: producto.get_nombre(),
+                    unidades_vendidas: ventas.unidades,
+                    ordenes: ventas.ordenes,
+                })
+                .collect()
+        }))
+    }
+
+    /// Completa una página de ventas por categoría de `Sistema` con el nombre y la calificación
+    /// promedio de cada categoría, leyendo las categorías con una sola llamada a `obtener_categorias`
+    pub fn estadisticas_por_categoria(
+        ventas: Pagina<(u32, VentasAcumuladas)>,
+        obtener_categorias: impl FnOnce(Vec<u32>) -> Result<Vec<Categoria>, ErroresContrato>,
+    ) -> Result<Pagina<EstadisticasCategoria>, ErroresContrato> {
+        let categorias = obtener_categorias(ventas.elementos.iter().map(|(id, _)| *id).collect())?;
+        Ok(mapear_pagina(ventas, |ventas| {
+            ventas
+                .into_iter()
+                .zip(categorias)
+                .map(
+                    |((id_categoria, ventas), categoria)| EstadisticasCategoria {
+                        id_categoria,
+                        nombre: categoria.get_nombre(),
+                        total_ventas: ventas.unidades,
+                        monto_total: ventas.monto,
+                        calificacion_promedio: promedio(
+                            ventas.suma_calificaciones,
+                            ventas.calificaciones,
+                        ),
+                        calificaciones: ventas.calificaciones,
+                    },
+                )
+                .collect()
+        }))
+    }
+
+    /// Arma la cantidad de órdenes de cada usuario de la página con los contadores que
+    /// devuelve `cantidad`, sin recorrer las órdenes
+    pub fn ordenes_por_usuario(
+        usuarios: Pagina<Usuario>,
+        mut cantidad: impl FnMut(AccountId) -> Result<CantidadOrdenes, ErroresContrato>,
+    ) -> Result<Pagina<OrdenesUsuario>, ErroresContrato> {
+        let mut elementos = Vec::with_capacity(usuarios.elementos.len());
+        for usuario in usuarios.elementos.iter() {
+            let id = usuario.get_id();
+            let CantidadOrdenes {
+                como_comprador,
+                como_vendedor,
+            } = cantidad(id)?;
+            elementos.push(OrdenesUsuario {
+                id,
+                como_comprador,
+                como_vendedor,
+            });
+        }
+        Ok(Pagina {
+            elementos,
+            total: usuarios.total,
+            siguiente: usuarios.siguiente,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reportes::*;
+    use ink::{env::DefaultEnvironment, primitives::AccountId};
+    use marketplacedescentralizado::prelude::*;
+
+    fn cuenta(n: u8) -> AccountId {
+        AccountId::from([n; 32])
+    }
+
+    fn set_caller(caller: AccountId) {
+        ink::env::test::set_caller::<DefaultEnvironment>(caller);
+    }
+
+    /// Simula que el caller actual envía `monto` al contrato junto con la próxima llamada
+    fn pagar(monto: u128) {
+        const SALDO_MINIMO: u128 = 1_000_000;
+        let caller = ink::env::caller::<DefaultEnvironment>();
+        let saldo = ink::env::test::get_account_balance::<DefaultEnvironment>(caller)
+            .unwrap_or_default()
+            .max(SALDO_MINIMO);
+        ink::env::test::set_account_balance::<DefaultEnvironment>(caller, saldo + monto);
+        ink::env::test::transfer_in::<DefaultEnvironment>(monto);
+    }
+
+    fn registrar(sistema: &mut Sistema, id: AccountId, nombre: &str, rol: Rol) {
+        set_caller(id);
+        sistema
+            .registrar_usuario(nombre.into(), format!("{nombre}@gmail.com"), rol)
+            .unwrap();
+    }
+
+    /// Crea un producto y lo publica con stock 10 y precio 100, devuelve el id de la publicación
+    fn publicar(sistema: &mut Sistema, vendedor: AccountId, nombre: &str, categoria: &str) -> u32 {
+        set_caller(vendedor);
+        let id_producto = sistema
+            .crear_producto(nombre.into(), "desc".into(), categoria.into(), 10)
+            .unwrap();
+        sistema.crear_publicacion(id_producto, 10, 100).unwrap()
+    }
+
+    /// Realiza una compra completa hasta `Recibida` y aplica las calificaciones indicadas
+    fn comprar(
+        sistema: &mut Sistema,
+        (comprador, vendedor): (AccountId, AccountId),
+        id_pub: u32,
+        cantidad: u32,
+        calificaciones: (Option<u8>, Option<u8>),
+    ) -> u32 {
+        set_caller(comprador);
+        pagar(100 * cantidad as u128);
+        let id_orden = sistema.crear_orden(id_pub, cantidad).unwrap();
+        set_caller(vendedor);
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(comprador);
+        sistema.recibir_producto(id_orden).unwrap();
+        if let Some(puntaje) = calificaciones.0 {
+            sistema.calificar_compra(id_orden, puntaje).unwrap();
+        }
+        if let Some(puntaje) = calificaciones.1 {
+            set_caller(vendedor);
+            sistema.calificar_compra(id_orden, puntaje).unwrap();
+        }
+        // con una sola calificación hay que esperar el plazo para que se revele
+        if calificaciones.0.is_some() != calificaciones.1.is_some() {
+            revelar_por_vencimiento(sistema, id_orden);
+        }
+        id_orden
+    }
+
+    /// Pasa a un nuevo bloque cuyo timestamp es exactamente `ms` milisegundos posterior al actual
+    fn avanzar_tiempo(ms: u64) {
+        let ahora = ink::env::block_timestamp::<DefaultEnvironment>();
+        ink::env::test::advance_block::<DefaultEnvironment>();
+        ink::env::test::set_block_timestamp::<DefaultEnvironment>(ahora + ms);
+    }
+
+    /// Deja vencer el plazo de revelación y revela las calificaciones de la orden
+    fn revelar_por_vencimiento(sistema: &mut Sistema, id_orden: u32) {
+        avanzar_tiempo(PLAZO_REVELACION_DEFAULT);
+        sistema.revelar_calificaciones(id_orden).unwrap();
+    }
+
+    /// Arma el ranking de productos más vendidos igual que el mensaje de `ReportesView`
+    fn mas_vendidos(sistema: &Sistema, desde: u32, tamano_pagina: u32) -> Pagina<VentasProducto> {
+        productos_mas_vendidos(
+            sistema
+                .listar_productos_mas_vendidos(desde, tamano_pagina)
+                .unwrap(),
+            |ids| sistema.get_productos(ids),
+        )
+        .unwrap()
+    }
+
+    /// Arma las estadísticas por categoría igual que el mensaje de `ReportesView`
+    fn por_categoria(sistema: &Sistema) -> Vec<EstadisticasCategoria> {
+        estadisticas_por_categoria(
+            sistema
+                .listar_ventas_por_categoria(0, MAX_TAMANO_PAGINA)
+                .unwrap(),
+            |ids| sistema.get_categorias(ids),
+        )
+        .unwrap()
+        .elementos
+    }
+
+    /// Sistema con un comprador (1, también owner), seis vendedores (2..=7) y dos categorías
+    fn setup_sistema() -> Sistema {
+        // la cuenta 1 despliega el contrato y queda como owner
+        set_caller(cuenta(1));
+        let mut sistema = Sistema::new();
+        registrar(&mut sistema, cuenta(1), "comprador", Rol::Comprador);
+        for n in 2..=7 {
+            registrar(
+                &mut sistema,
+                cuenta(n),
+                &format!("vendedor{n}"),
+                Rol::Vendedor,
+            );
+        }
+        set_caller(cuenta(1));
+        sistema.registrar_categoria("Libros".into()).unwrap();
+        sistema.registrar_categoria("Ropa".into()).unwrap();
+        sistema
+    }
+
+    #[ink::test]
+    fn test_top_vendedores_ordenados_y_limitados() {
+        let mut sistema = setup_sistema();
+        let puntajes = [(2, 3), (3, 5), (4, 1), (5, 4), (6, 2)];
+        for (vendedor, puntaje) in puntajes {
+            let id_pub = publicar(
+                &mut sistema,
+                cuenta(vendedor),
+                &format!("prod{vendedor}"),
+                "Libros",
+            );
+            comprar(
+                &mut sistema,
+                (cuenta(1), cuenta(vendedor)),
+                id_pub,
+                1,
+                (Some(puntaje), None),
+            );
+        }
+        // el vendedor 7 tiene dos calificaciones de 5, queda primero por desempate
+        let id_pub = publicar(&mut sistema, cuenta(7), "prod7", "Libros");
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(7)),
+            id_pub,
+            1,
+            (Some(5), None),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(7)),
+            id_pub,
+            1,
+            (Some(5), None),
+        );
+
+        let top = usuarios_destacados(
+            sistema
+                .listar_mejores_vendedores(0, TOP_USUARIOS)
+                .unwrap()
+                .elementos,
+            Rol::Vendedor,
+        );
+
+        assert_eq!(top.len(), TOP_USUARIOS as usize);
+        let ids: Vec<AccountId> = top.iter().map(|u| u.id).collect();
+        assert_eq!(
+            ids,
+            vec![cuenta(7), cuenta(3), cuenta(5), cuenta(2), cuenta(6)]
+        );
+        assert_eq!(top[0].promedio, 500);
+        assert_eq!(top[0].calificaciones, 2);
+        assert_eq!(top[0].nombre, "vendedor7");
+    }
+
+    #[ink::test]
+    fn test_top_compradores_excluye_usuarios_sin_calificaciones() {
+        let mut sistema = setup_sistema();
+        registrar(&mut sistema, cuenta(8), "comprador2", Rol::Ambos);
+        registrar(&mut sistema, cuenta(9), "comprador3", Rol::Comprador);
+        let id_pub = publicar(&mut sistema, cuenta(2), "prod", "Libros");
+
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            id_pub,
+            1,
+            (None, Some(3)),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            id_pub,
+            1,
+            (None, Some(4)),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(8), cuenta(2)),
+            id_pub,
+            1,
+            (None, Some(5)),
+        );
+        // el comprador 9 nunca fue calificado
+
+        let top = usuarios_destacados(
+            sistema
+                .listar_mejores_compradores(0, TOP_USUARIOS)
+                .unwrap()
+                .elementos,
+            Rol::Comprador,
+        );
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].id, cuenta(8));
+        assert_eq!(top[0].promedio, 500);
+        assert_eq!(top[1].id, cuenta(1));
+        assert_eq!(top[1].promedio, 350);
+        assert_eq!(top[1].calificaciones, 2);
+    }
+
+    #[ink::test]
+    fn test_top_vendedores_con_promedio_ponderado() {
+        let mut sistema = setup_sistema();
+        let id_pub = publicar(&mut sistema, cuenta(2), "prod", "Libros");
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            id_pub,
+            1,
+            (Some(1), None),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            id_pub,
+            3,
+            (Some(5), None),
+        );
+
+        let top = usuarios_destacados(
+            sistema
+                .listar_mejores_vendedores(0, TOP_USUARIOS)
+                .unwrap()
+                .elementos,
+            Rol::Vendedor,
+        );
+
+        assert_eq!(top[0].promedio, 300);
+        // la compra de 3 unidades pesa el triple: (1 * 100 + 5 * 300) / 400
+        assert_eq!(top[0].promedio_ponderado, Some(400));
+    }
+
+    #[ink::test]
+    fn test_productos_mas_vendidos() {
+        let mut sistema = setup_sistema();
+        let libro = publicar(&mut sistema, cuenta(2), "libro", "Libros");
+        let remera = publicar(&mut sistema, cuenta(3), "remera", "Ropa");
+        publicar(&mut sistema, cuenta(4), "sin ventas", "Ropa");
+
+        comprar(&mut sistema, (cuenta(1), cuenta(2)), libro, 2, (None, None));
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(3)),
+            remera,
+            1,
+            (None, None),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(3)),
+            remera,
+            4,
+            (None, None),
+        );
+
+        // una orden cancelada no cuenta como venta
+        set_caller(cuenta(1));
+        pagar(300);
+        let cancelada = sistema.crear_orden(libro, 3).unwrap();
+        sistema
+            .cancelar_orden(cancelada, MotivoCancelacion::PedidoDelComprador)
+            .unwrap();
+        set_caller(cuenta(2));
+        sistema
+            .cancelar_orden(cancelada, MotivoCancelacion::PedidoDelComprador)
+            .unwrap();
+
+        // una orden pendiente todavía no cuenta como venta
+        set_caller(cuenta(1));
+        pagar(100);
+        sistema.crear_orden(libro, 1).unwrap();
+
+        let ventas = mas_vendidos(&sistema, 0, MAX_TAMANO_PAGINA).elementos;
+        assert_eq!(ventas.len(), 2);
+        assert_eq!(ventas[0].nombre, "remera");
+        assert_eq!(ventas[0].unidades_vendidas, 5);
+        assert_eq!(ventas[0].ordenes, 2);
+        assert_eq!(ventas[1].nombre, "libro");
+        assert_eq!(ventas[1].unidades_vendidas, 2);
+
+        // el ranking es el mismo de página en página
+        let primera = mas_vendidos(&sistema, 0, 1);
+        assert_eq!(primera.elementos[0].nombre, "remera");
+        assert_eq!(primera.siguiente, Some(1));
+        let segunda = mas_vendidos(&sistema, 1, 1);
+        assert_eq!(
+            (
+                segunda.elementos[0].nombre.as_str(),
+                segunda.elementos[0].unidades_vendidas
+            ),
+            ("libro", 2)
+        );
+        assert_eq!(segunda.siguiente, None);
+    }
+
+    #[ink::test]
+    fn test_reportes_cuentan_cada_linea_de_una_orden() {
+        let mut sistema = setup_sistema();
+        let libro = publicar(&mut sistema, cuenta(2), "libro", "Libros");
+        let remera = publicar(&mut sistema, cuenta(2), "remera", "Ropa");
+
+        set_caller(cuenta(1));
+        sistema.agregar_al_carrito(libro, 2).unwrap();
+        sistema.agregar_al_carrito(remera, 3).unwrap();
+        pagar(500);
+        let id_orden = sistema.confirmar_carrito().unwrap();
+        set_caller(cuenta(2));
+        sistema.enviar_producto(id_orden).unwrap();
+        set_caller(cuenta(1));
+        sistema.recibir_producto(id_orden).unwrap();
+        sistema.calificar_compra(id_orden, 4).unwrap();
+        revelar_por_vencimiento(&mut sistema, id_orden);
+
+        let ventas = mas_vendidos(&sistema, 0, MAX_TAMANO_PAGINA).elementos;
+        assert_eq!(ventas.len(), 2);
+        assert_eq!(
+            (ventas[0].nombre.as_str(), ventas[0].unidades_vendidas),
+            ("remera", 3)
+        );
+        assert_eq!(
+            (ventas[1].nombre.as_str(), ventas[1].unidades_vendidas),
+            ("libro", 2)
+        );
+        assert!(ventas.iter().all(|venta| venta.ordenes == 1));
+
+        let estadisticas = por_categoria(&sistema);
+        assert_eq!(estadisticas[0].monto_total, 200);
+        assert_eq!(estadisticas[1].monto_total, 300);
+        // la calificación de la orden cuenta en ambas categorías
+        assert!(estadisticas
+            .iter()
+            .all(|e| e.calificaciones == 1 && e.calificacion_promedio == Some(400)));
+    }
+
+    #[ink::test]
+    fn test_estadisticas_por_categoria() {
+        let mut sistema = setup_sistema();
+        let libro = publicar(&mut sistema, cuenta(2), "libro", "Libros");
+        let remera = publicar(&mut sistema, cuenta(3), "remera", "Ropa");
+
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            libro,
+            2,
+            (Some(4), None),
+        );
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(2)),
+            libro,
+            1,
+            (Some(5), None),
+        );
+        comprar(&mut sistema, (cuenta(1), cuenta(2)), libro, 1, (None, None));
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(3)),
+            remera,
+            3,
+            (None, Some(2)),
+        );
+
+        // la categoría sin ventas no aparece en el reporte
+        set_caller(cuenta(1));
+        sistema.registrar_categoria("Hogar".into()).unwrap();
+        publicar(&mut sistema, cuenta(4), "mate", "Hogar");
+
+        let estadisticas = por_categoria(&sistema);
+        assert_eq!(estadisticas.len(), 2);
+        assert_eq!(estadisticas[0].nombre, "libros");
+        assert_eq!(estadisticas[0].total_ventas, 4);
+        assert_eq!(estadisticas[0].monto_total, 400);
+        assert_eq!(estadisticas[0].calificaciones, 2);
+        assert_eq!(estadisticas[0].calificacion_promedio, Some(450));
+
+        // la calificación al comprador no cuenta para la categoría
+        assert_eq!(estadisticas[1].nombre, "ropa");
+        assert_eq!(estadisticas[1].total_ventas, 3);
+        assert_eq!(estadisticas[1].calificaciones, 0);
+        assert_eq!(estadisticas[1].calificacion_promedio, None);
+    }
+
+    #[ink::test]
+    fn test_ordenes_por_usuario() {
+        let mut sistema = setup_sistema();
+        registrar(&mut sistema, cuenta(8), "ambos", Rol::Ambos);
+        let libro = publicar(&mut sistema, cuenta(2), "libro", "Libros");
+        let remera = publicar(&mut sistema, cuenta(8), "remera", "Ropa");
+
+        comprar(&mut sistema, (cuenta(1), cuenta(2)), libro, 1, (None, None));
+        comprar(&mut sistema, (cuenta(8), cuenta(2)), libro, 1, (None, None));
+        comprar(
+            &mut sistema,
+            (cuenta(1), cuenta(8)),
+            remera,
+            1,
+            (None, None),
+        );
+
+        let pagina = ordenes_por_usuario(
+            sistema.listar_usuarios(0, MAX_TAMANO_PAGINA).unwrap(),
+            |id| sistema.get_cantidad_ordenes(id),
+        )
+        .unwrap();
+        let ordenes = pagina.elementos;
+        let de = |id: AccountId| ordenes.iter().find(|o| o.id == id).cloned().unwrap();
+
+        assert_eq!(ordenes.len(), 8);
+        assert_eq!(pagina.siguiente, None);
+        assert_eq!(
+            de(cuenta(1)),
+            OrdenesUsuario {
+                id: cuenta(1),
+                como_comprador: 2,
+                como_vendedor: 0
+            }
+        );
+        assert_eq!(
+            de(cuenta(2)),
+            OrdenesUsuario {
+                id: cuenta(2),
+                como_comprador: 0,
+                como_vendedor: 2
+            }
+        );
+        assert_eq!(
+            de(cuenta(8)),
+            OrdenesUsuario {
+                id: cuenta(8),
+                como_comprador: 1,
+                como_vendedor: 1
+            }
+        );
+        assert_eq!(
+            de(cuenta(5)),
+            OrdenesUsuario {
+                id: cuenta(5),
+                como_comprador: 0,
+                como_vendedor: 0
+            }
+        );
+
+        let primera = ordenes_por_usuario(sistema.listar_usuarios(0, 3).unwrap(), |id| {
+            sistema.get_cantidad_ordenes(id)
+        })
+        .unwrap();
+        assert_eq!(primera.elementos.len(), 3);
+        assert_eq!(primera.siguiente, Some(3));
+        // un error de Sistema se propaga en lugar de cortar el reporte
+        assert_eq!(
+            ordenes_por_usuario(sistema.listar_usuarios(0, 3).unwrap(), |_| Err(
+                ErroresContrato::UsuarioNoExiste
+            )),
+            Err(ErroresContrato::UsuarioNoExiste)
+        );
+    }
+}
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_tests {
+    use crate::reportes::*;
+    use ink_e2e::ContractsBackend;
+    use marketplacedescentralizado::prelude::*;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    #[ink_e2e::test]
+    async fn e2e_reportes_leen_datos_del_sistema<Client: E2EBackend>(
+        mut client: Client,
+    ) -> E2EResult<()> {
+        // despliego el contrato principal y el de reportes apuntando a él
+        let mut constructor = SistemaRef::new();
+        let sistema = client
+            .instantiate(
+                "marketplacedescentralizado",
+                &ink_e2e::alice(),
+                &mut constructor,
+            )
+            .submit()
+            .await
+            .expect("no se pudo desplegar Sistema");
+        let mut llamada_sistema = sistema.call_builder::<Sistema>();
+
+        let mut constructor = ReportesViewRef::new(sistema.account_id);
+        let reportes = client
+            .instantiate("reportes", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("no se pudo desplegar ReportesView");
+        let llamada_reportes = reportes.call_builder::<ReportesView>();
+
+        // bob vende, charlie compra
+        let registrar =
+            llamada_sistema.registrar_usuario("bob".into(), "bob@gmail.com".into(), Rol::Vendedor);
+        client.call(&ink_e2e::bob(), &registrar).submit().await?;
+        let registrar = llamada_sistema.registrar_usuario(
+            "charlie".into(),
+            "charlie@gmail.com".into(),
+            Rol::Comprador,
+        );
+        client
+            .call(&ink_e2e::charlie(), &registrar)
+            .submit()
+            .await?;
+
+        let categoria = llamada_sistema.registrar_categoria("Libros".into());
+        client.call(&ink_e2e::alice(), &categoria).submit().await?;
+        let producto =
+            llamada_sistema.crear_producto("Rust".into(), "desc".into(), "Libros".into(), 10);
+        client.call(&ink_e2e::bob(), &producto).submit().await?;
+        let publicacion = llamada_sistema.crear_publicacion(0, 10, 100);
+        client.call(&ink_e2e::bob(), &publicacion).submit().await?;
+
+        let orden = llamada_sistema.crear_orden(0, 2);
+        client
+            .call(&ink_e2e::charlie(), &orden)
+            .value(200)
+            .submit()
+            .await?;
+        let enviar = llamada_sistema.enviar_producto(0);
+        client.call(&ink_e2e::bob(), &enviar).submit().await?;
+        let recibir = llamada_sistema.recibir_producto(0);
+        client.call(&ink_e2e::charlie(), &recibir).submit().await?;
+        let calificar = llamada_sistema.calificar_compra(0, 4);
+        client
+            .call(&ink_e2e::charlie(), &calificar)
+            .submit()
+            .await?;
+        // las calificaciones se revelan cuando califican las dos partes
+        let calificar = llamada_sistema.calificar_compra(0, 5);
+        client.call(&ink_e2e::bob(), &calificar).submit().await?;
+
+        // los reportes reflejan lo que ocurrió en Sistema
+        let top = client
+            .call(&ink_e2e::alice(), &llamada_reportes.top_vendedores())
+            .dry_run()
+            .await?
+            .return_value()
+            .unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].nombre, "bob");
+        assert_eq!(top[0].promedio, 400);
+
+        let ventas = client
+            .call(
+                &ink_e2e::alice(),
+                &llamada_reportes.productos_mas_vendidos(0, MAX_TAMANO_PAGINA),
+            )
+            .dry_run()
+            .await?
+            .return_value()
+            .unwrap()
+            .elementos;
+        assert_eq!(ventas[0].unidades_vendidas, 2);
+
+        let categorias = client
+            .call(
+                &ink_e2e::alice(),
+                &llamada_reportes.estadisticas_por_categoria(0, MAX_TAMANO_PAGINA),
+            )
+            .dry_run()
+            .await?
+            .return_value()
+            .unwrap()
+            .elementos;
+        assert_eq!(categorias[0].total_ventas, 2);
+        assert_eq!(categorias[0].calificacion_promedio, Some(400));
+
+        let ordenes = client
+            .call(
+                &ink_e2e::alice(),
+                &llamada_reportes.cantidad_ordenes_por_usuario(0, MAX_TAMANO_PAGINA),
+            )
+            .dry_run()
+            .await?
+            .return_value()
+            .unwrap();
+        assert_eq!(
+            ordenes
+                .elementos
+                .iter()
+                .map(|o| o.como_comprador)
+                .sum::<u32>(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test]
+    async fn e2e_reportes_sin_datos<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+        let mut constructor = SistemaRef::new();
+        let sistema = client
+            .instantiate(
+                "marketplacedescentralizado",
+                &ink_e2e::alice(),
+                &mut constructor,
+            )
+            .submit()
+            .await
+            .expect("no se pudo desplegar Sistema");
+
+        let mut constructor = ReportesViewRef::new(sistema.account_id);
+        let reportes = client
+            .instantiate("reportes", &ink_e2e::alice(), &mut constructor)
+            .submit()
+            .await
+            .expect("no se pudo desplegar ReportesView");
+        let llamada_reportes = reportes.call_builder::<ReportesView>();
+
+        let top = client
+            .call(&ink_e2e::alice(), &llamada_reportes.top_compradores())
+            .dry_run()
+            .await?
+            .return_value()
+            .unwrap();
+        assert!(top.is_empty());
+
+        // el tamaño de página inválido llega como error en lugar de un reporte vacío
+        let invalido = client
+            .call(
+                &ink_e2e::alice(),
+                &llamada_reportes.productos_mas_vendidos(0, MAX_TAMANO_PAGINA + 1),
+            )
+            .dry_run()
+            .await?
+            .return_value();
+        assert_eq!(invalido, Err(ErroresContrato::TamanoPaginaInvalido));
+
+        Ok(())
+    }
+}